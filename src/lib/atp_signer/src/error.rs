@@ -1,4 +1,6 @@
 pub use jsonrpc_core::types::response::Failure as RpcFailure;
+use jsonrpc_core::types::error::{Error as JsonRpcError, ErrorCode};
+use serde_json::Value;
 use thiserror::Error;
 
 #[derive(Debug, Error, PartialEq)]
@@ -11,6 +13,19 @@ pub enum RpcSignerError {
     NetworkError(String),
 }
 
+impl RpcSignerError {
+    /// Stable, machine-readable code for this error variant. Unlike the `Display`
+    /// message (which is meant for humans and may be reworded over time), callers
+    /// across a process boundary (e.g. a JSON-RPC client) can match on this value.
+    pub fn error_code(&self) -> i64 {
+        match self {
+            Self::MalformedResponse(_) => 1,
+            Self::RpcError(_) => 2,
+            Self::NetworkError(_) => 3,
+        }
+    }
+}
+
 #[derive(Debug, Error, PartialEq)]
 pub enum SignerError {
     #[error("Alaya private key required to perform an operation")]
@@ -32,3 +47,49 @@ pub enum SignerError {
     #[error("{0}")]
     CustomError(String),
 }
+
+impl SignerError {
+    /// Stable, machine-readable code for this error variant. Unlike the `Display`
+    /// message (which is meant for humans and may be reworded over time), callers
+    /// across a process boundary (e.g. a JSON-RPC client) can match on this value.
+    pub fn error_code(&self) -> i64 {
+        match self {
+            Self::MissingAtpPrivateKey => 100,
+            Self::MissingAtpSigner => 101,
+            Self::SigningFailed(_) => 102,
+            Self::UnlockingFailed(_) => 103,
+            Self::DecodeRawTxFailed(_) => 104,
+            Self::NoSigningKey => 105,
+            Self::DefineAddress => 106,
+            Self::RecoverAddress(_) => 107,
+            Self::CustomError(_) => 199,
+        }
+    }
+
+    /// The variant's associated string, if any, surfaced separately from the
+    /// human-readable message so a client can act on it without parsing text.
+    fn error_data(&self) -> Option<Value> {
+        match self {
+            Self::SigningFailed(reason)
+            | Self::UnlockingFailed(reason)
+            | Self::DecodeRawTxFailed(reason)
+            | Self::RecoverAddress(reason)
+            | Self::CustomError(reason) => Some(Value::String(reason.clone())),
+            Self::MissingAtpPrivateKey
+            | Self::MissingAtpSigner
+            | Self::NoSigningKey
+            | Self::DefineAddress => None,
+        }
+    }
+
+    /// Converts the error into a JSON-RPC error object, with `error_code` mapped into
+    /// the `code` field and the offending detail (if any) carried in `data`.
+    /// `JsonRpcSigner` does not call this conversion.
+    pub fn to_rpc_error(&self) -> JsonRpcError {
+        JsonRpcError {
+            code: ErrorCode::ServerError(self.error_code()),
+            message: self.to_string(),
+            data: self.error_data(),
+        }
+    }
+}
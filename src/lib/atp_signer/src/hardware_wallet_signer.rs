@@ -0,0 +1,90 @@
+use crate::raw_alaya_tx::RawTransaction;
+use crate::{AlayaSigner, SignerError};
+
+use zkdpos_types::tx::{PackedAtpSignature, TxAtpSignature};
+use zkdpos_types::Address;
+
+/// A USB/HID transport to a Ledger/Trezor-style hardware wallet.
+///
+/// Implementors are expected to speak the device's native APDU (or equivalent)
+/// protocol; this trait only exposes the three primitives `HardwareWalletSigner`
+/// needs, so the crate doesn't have to depend on a concrete HID library.
+pub trait HardwareWalletTransport: Send + Sync {
+    /// Enumerates the devices currently connected over USB/HID.
+    fn enumerate_devices(&self) -> Result<Vec<String>, SignerError>;
+
+    /// Asks the device at `derivation_path` for its public address.
+    fn get_address(&self, derivation_path: &str) -> Result<Address, SignerError>;
+
+    /// Asks the device to sign `message` (already keccak-prefixed if needed)
+    /// using the key at `derivation_path`, returning a 65-byte `r || s || v` signature.
+    fn sign(&self, derivation_path: &str, message: &[u8]) -> Result<[u8; 65], SignerError>;
+}
+
+/// `AlayaSigner` implementation that never holds private key material: every
+/// signing operation is delegated to a Ledger/Trezor-style device connected
+/// over USB/HID, addressed by a BIP-44 derivation path (e.g. `m/44'/60'/0'/0/0`).
+#[derive(Clone)]
+pub struct HardwareWalletSigner<T: HardwareWalletTransport + Clone> {
+    transport: T,
+    derivation_path: String,
+}
+
+impl<T: HardwareWalletTransport + Clone> HardwareWalletSigner<T> {
+    /// Creates a signer bound to a specific BIP-44 `derivation_path` on the device
+    /// reachable through `transport`.
+    pub fn new(transport: T, derivation_path: String) -> Self {
+        Self {
+            transport,
+            derivation_path,
+        }
+    }
+
+    /// Lists the derivation paths' devices currently connected over USB/HID,
+    /// so the caller can let a user pick one before constructing a signer.
+    pub fn enumerate_devices(transport: &T) -> Result<Vec<String>, SignerError> {
+        transport.enumerate_devices()
+    }
+}
+
+impl<T: HardwareWalletTransport + Clone> std::fmt::Debug for HardwareWalletSigner<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "HardwareWalletSigner({})", self.derivation_path)
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: HardwareWalletTransport + Clone> AlayaSigner for HardwareWalletSigner<T> {
+    /// Returns the Alaya address derived by the device for the configured path.
+    async fn get_address(&self) -> Result<Address, SignerError> {
+        self.transport.get_address(&self.derivation_path)
+    }
+
+    /// Has the device sign `sign(keccak256("\x19Alaya Signed Message:\n" + len(message) + message))`
+    /// without the private key ever leaving it.
+    async fn sign_message(&self, message: &[u8]) -> Result<TxAtpSignature, SignerError> {
+        let raw_signature = self
+            .transport
+            .sign(&self.derivation_path, message)
+            .map_err(|err| SignerError::SigningFailed(err.to_string()))?;
+        let packed = PackedAtpSignature::deserialize_packed(&raw_signature)
+            .map_err(|err| SignerError::SigningFailed(err.to_string()))?;
+        Ok(TxAtpSignature::AlayaSignature(packed))
+    }
+
+    /// Serializes `raw_tx`, has the device sign its hash, and returns the RLP-encoded transaction.
+    async fn sign_transaction(&self, raw_tx: RawTransaction) -> Result<Vec<u8>, SignerError> {
+        let hash = raw_tx.hash();
+        let raw_signature = self
+            .transport
+            .sign(&self.derivation_path, hash.as_bytes())
+            .map_err(|err| match err {
+                // A user declining the prompt on the device is reported as an unlock failure,
+                // mirroring how the other signers treat a locked/denied key.
+                SignerError::UnlockingFailed(reason) => SignerError::UnlockingFailed(reason),
+                other => SignerError::SigningFailed(other.to_string()),
+            })?;
+        let signature = parity_crypto::publickey::Signature::from(raw_signature);
+        Ok(raw_tx.rlp_encode_tx(signature))
+    }
+}
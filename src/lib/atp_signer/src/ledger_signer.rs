@@ -0,0 +1,170 @@
+use crate::raw_alaya_tx::RawTransaction;
+use crate::{AlayaSigner, SignerError};
+
+use parity_crypto::publickey::{public_to_address, Public, Signature};
+
+use zkdpos_types::tx::{PackedAtpSignature, TxAtpSignature};
+use zkdpos_types::Address;
+
+/// Ledger Ethereum app instruction class, shared by every APDU this signer sends.
+const CLA: u8 = 0xe0;
+/// Returns the public key (and, optionally, address) for a BIP-44 derivation path.
+const INS_GET_PUBLIC_KEY: u8 = 0x02;
+/// Signs the RLP-encoded, unsigned body of an Alaya transaction.
+const INS_SIGN_TRANSACTION: u8 = 0x04;
+/// Signs a message under the `"\x19Alaya Signed Message:\n"` personal-message scheme.
+const INS_SIGN_PERSONAL_MESSAGE: u8 = 0x08;
+
+/// Raw APDU transport to a connected Ledger device. Implementors own the
+/// USB/HID connection and only need to move bytes; `LedgerSigner` builds and
+/// parses the Ethereum app's APDUs on top of it, so the crate doesn't have to
+/// depend on a concrete HID library when the `ledger` feature is off.
+pub trait LedgerTransport: Send + Sync + Clone {
+    /// Sends one APDU command to the device and returns its response payload
+    /// (status words already stripped, with a non-success status turned into
+    /// an error by the implementor).
+    fn exchange(&self, apdu: &[u8]) -> Result<Vec<u8>, SignerError>;
+}
+
+/// Parses a BIP-44 derivation path (e.g. `m/44'/60'/0'/0/0`) into the byte
+/// encoding the Ledger Ethereum app expects in an APDU payload: a
+/// length-prefixed list of big-endian `u32`s, hardened components already
+/// OR'd with `0x8000_0000`.
+fn encode_derivation_path(path: &str) -> Result<Vec<u8>, SignerError> {
+    let trimmed = path.strip_prefix("m/").unwrap_or(path);
+    let components = if trimmed.is_empty() {
+        Vec::new()
+    } else {
+        trimmed
+            .split('/')
+            .map(|segment| {
+                let (digits, hardened) = match segment.strip_suffix('\'') {
+                    Some(digits) => (digits, true),
+                    None => (segment, false),
+                };
+                let index: u32 = digits
+                    .parse()
+                    .map_err(|_| SignerError::SigningFailed(format!("invalid derivation path: {}", path)))?;
+                Ok(if hardened { index | 0x8000_0000 } else { index })
+            })
+            .collect::<Result<Vec<u32>, SignerError>>()?
+    };
+    let mut out = Vec::with_capacity(1 + components.len() * 4);
+    out.push(components.len() as u8);
+    for component in components {
+        out.extend_from_slice(&component.to_be_bytes());
+    }
+    Ok(out)
+}
+
+/// `AlayaSigner` implementation that never holds private key material: every
+/// signing operation is an APDU exchange with a connected Ledger device,
+/// addressed by a BIP-44 derivation path (e.g. `m/44'/60'/0'/0/0`).
+#[derive(Clone)]
+pub struct LedgerSigner<T: LedgerTransport> {
+    transport: T,
+    derivation_path: String,
+}
+
+impl<T: LedgerTransport> LedgerSigner<T> {
+    /// Creates a signer bound to a specific BIP-44 `derivation_path` on the
+    /// device reachable through `transport`.
+    pub fn new(transport: T, derivation_path: String) -> Self {
+        Self {
+            transport,
+            derivation_path,
+        }
+    }
+
+    /// Asks the device for the uncompressed public key at `self.derivation_path`.
+    fn get_public_key(&self) -> Result<Public, SignerError> {
+        let apdu = build_apdu(INS_GET_PUBLIC_KEY, &encode_derivation_path(&self.derivation_path)?);
+        let response = self.transport.exchange(&apdu)?;
+        // The app replies with a one-byte public key length followed by the
+        // uncompressed key itself (`0x04 || X || Y`); the address and chain
+        // code that may follow aren't needed, since we derive the address
+        // ourselves from the public key below.
+        let pubkey_len = *response
+            .first()
+            .ok_or_else(|| SignerError::SigningFailed("empty response to GET_PUBLIC_KEY".into()))?
+            as usize;
+        let pubkey_bytes = response
+            .get(1..1 + pubkey_len)
+            .ok_or_else(|| SignerError::SigningFailed("truncated public key in device response".into()))?;
+        Public::from_slice(&pubkey_bytes[1..]).map_err(|err| SignerError::SigningFailed(err.to_string()))
+    }
+
+    /// Parses a device signature response (`v || r || s`, as the Ledger
+    /// Ethereum app returns it) into the `r || s || v` (electrum-style, `v`
+    /// already +27) layout both `Signature` and `PackedAtpSignature` expect.
+    fn parse_signature(response: &[u8]) -> Result<[u8; 65], SignerError> {
+        if response.len() != 65 {
+            return Err(SignerError::SigningFailed(
+                "unexpected signature length in device response".into(),
+            ));
+        }
+        let mut signature = [0u8; 65];
+        signature[..64].copy_from_slice(&response[1..65]);
+        signature[64] = response[0];
+        Ok(signature)
+    }
+}
+
+impl<T: LedgerTransport> std::fmt::Debug for LedgerSigner<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "LedgerSigner({:?})", self.derivation_path)
+    }
+}
+
+fn build_apdu(ins: u8, data: &[u8]) -> Vec<u8> {
+    let mut apdu = Vec::with_capacity(5 + data.len());
+    apdu.push(CLA);
+    apdu.push(ins);
+    apdu.push(0x00); // P1: first (and only) chunk
+    apdu.push(0x00); // P2: unused
+    apdu.push(data.len() as u8);
+    apdu.extend_from_slice(data);
+    apdu
+}
+
+#[async_trait::async_trait]
+impl<T: LedgerTransport> AlayaSigner for LedgerSigner<T> {
+    /// Derives the Alaya address from the device's exported public key for
+    /// the configured path, rather than from a locally held `H256`.
+    async fn get_address(&self) -> Result<Address, SignerError> {
+        let public_key = self.get_public_key()?;
+        Ok(public_to_address(&public_key))
+    }
+
+    /// Has the device sign `message` under its on-device
+    /// `"\x19Alaya Signed Message:\n"` personal-message prefixing, without the
+    /// private key ever leaving it.
+    async fn sign_message(&self, message: &[u8]) -> Result<TxAtpSignature, SignerError> {
+        let mut data = encode_derivation_path(&self.derivation_path)?;
+        data.extend_from_slice(&(message.len() as u32).to_be_bytes());
+        data.extend_from_slice(message);
+        let apdu = build_apdu(INS_SIGN_PERSONAL_MESSAGE, &data);
+        let response = self.transport.exchange(&apdu)?;
+        let signature = Self::parse_signature(&response)?;
+        let packed = PackedAtpSignature::deserialize_packed(&signature)
+            .map_err(|err| SignerError::SigningFailed(err.to_string()))?;
+        Ok(TxAtpSignature::AlayaSignature(packed))
+    }
+
+    /// Serializes `raw_tx`, has the device sign its RLP-encoded unsigned body,
+    /// and returns the same `raw_tx.rlp_encode_tx(sig)` bytes `PrivateKeySigner`
+    /// produces.
+    async fn sign_transaction(&self, raw_tx: RawTransaction) -> Result<Vec<u8>, SignerError> {
+        let mut data = encode_derivation_path(&self.derivation_path)?;
+        data.extend_from_slice(raw_tx.hash().as_bytes());
+        let apdu = build_apdu(INS_SIGN_TRANSACTION, &data);
+        let response = self.transport.exchange(&apdu).map_err(|err| match err {
+            // A user declining the prompt on the device is reported as an unlock
+            // failure, mirroring how the other signers treat a locked/denied key.
+            SignerError::UnlockingFailed(reason) => SignerError::UnlockingFailed(reason),
+            other => SignerError::SigningFailed(other.to_string()),
+        })?;
+        let signature = Self::parse_signature(&response)?;
+        Ok(raw_tx.rlp_encode_tx(Signature::from(signature)))
+    }
+}
@@ -6,12 +6,18 @@ use error::SignerError;
 use zkdpos_types::tx::TxAtpSignature;
 use zkdpos_types::Address;
 
+pub use hardware_wallet_signer::{HardwareWalletSigner, HardwareWalletTransport};
 pub use json_rpc_signer::JsonRpcSigner;
+#[cfg(feature = "ledger")]
+pub use ledger_signer::{LedgerSigner, LedgerTransport};
 pub use pk_signer::PrivateKeySigner;
 pub use raw_alaya_tx::RawTransaction;
 
 pub mod error;
+pub mod hardware_wallet_signer;
 pub mod json_rpc_signer;
+#[cfg(feature = "ledger")]
+pub mod ledger_signer;
 pub mod pk_signer;
 pub mod raw_alaya_tx;
 
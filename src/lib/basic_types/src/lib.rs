@@ -47,3 +47,9 @@ basic_type!(
     AtpBlockId,
     u64
 );
+
+basic_type!(
+    /// Unique identifier of an AMM liquidity pool in the zkDpos network.
+    LiquidityId,
+    u16
+);
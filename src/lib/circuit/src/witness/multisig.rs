@@ -0,0 +1,260 @@
+// External deps
+use zkdpos_crypto::franklin_crypto::{
+    bellman::pairing::{
+        bn256::{Bn256, Fr},
+        ff::{Field, PrimeField},
+    },
+    rescue::RescueEngine,
+};
+// Workspace deps
+use zkdpos_crypto::{
+    circuit::{
+        account::CircuitAccountTree,
+        utils::{append_be_fixed_width, le_bit_vector_into_field_element},
+    },
+    params::{
+        account_tree_depth, ACCOUNT_ID_BIT_WIDTH, CHUNK_BIT_WIDTH, NEW_PUBKEY_HASH_WIDTH,
+        NONCE_BIT_WIDTH, TX_TYPE_BIT_WIDTH,
+    },
+};
+use zkdpos_types::operations::ChangePubKeyOp;
+// Local deps
+use crate::{
+    operation::{Operation, OperationArguments, OperationBranch, OperationBranchWitness},
+    utils::resize_grow_only,
+    witness::{
+        utils::{apply_leaf_operation, get_audits, SigDataInput},
+        Witness,
+    },
+};
+
+/// A `ChangePubKey` that installs a threshold-multisig signer set on the
+/// account, rather than a single signing key: `new_pub_key_hash` is
+/// `AccountSignerSet::commitment()`, and `signer_commitments` holds each
+/// registered signer's own `PubKeyHash` (as a field element), in the order
+/// they're bitmap-indexed by `ThresholdMusigSignature`.
+pub struct MultisigData {
+    pub account_address: u32,
+    pub new_pub_key_hash: Fr,
+    pub threshold: u8,
+    pub signer_commitments: Vec<Fr>,
+}
+
+pub struct MultisigWitness<E: RescueEngine> {
+    pub before: OperationBranch<E>,
+    pub after: OperationBranch<E>,
+    pub args: OperationArguments<E>,
+    pub before_root: Option<E::Fr>,
+    pub after_root: Option<E::Fr>,
+    pub tx_type: Option<E::Fr>,
+    /// Each registered signer's `PubKeyHash`, exposed separately from `args`
+    /// since there's no fixed number of them (`N` in `M`-of-`N`) and they're
+    /// folded into the signed message rather than the leaf witness.
+    pub signer_commitments: Vec<Fr>,
+}
+
+/// Converts a 20-byte `PubKeyHash` into the field element its big-endian bit
+/// decomposition represents, matching how `new_pub_key_hash` bits are packed
+/// everywhere else in this witness (most-significant byte/bit first).
+fn pub_key_hash_bytes_into_fr(data: &[u8]) -> Fr {
+    let mut bits = Vec::with_capacity(data.len() * 8);
+    for byte in data {
+        for i in (0..8).rev() {
+            bits.push((byte >> i) & 1 == 1);
+        }
+    }
+    bits.reverse();
+    le_bit_vector_into_field_element(&bits)
+}
+
+impl Witness for MultisigWitness<Bn256> {
+    type OperationType = ChangePubKeyOp;
+    type CalculateOpsInput = SigDataInput;
+
+    fn apply_tx(tree: &mut CircuitAccountTree, change_pub_key: &ChangePubKeyOp) -> Self {
+        let new_pub_key_hash_fe = pub_key_hash_bytes_into_fr(&change_pub_key.tx.new_pk_hash.data);
+        let signer_commitments = change_pub_key
+            .tx
+            .signer_set
+            .as_ref()
+            .map(|signer_set| {
+                signer_set
+                    .signers
+                    .iter()
+                    .map(|signer| pub_key_hash_bytes_into_fr(&signer.data))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let multisig_data = MultisigData {
+            account_address: *change_pub_key.account_id,
+            new_pub_key_hash: new_pub_key_hash_fe,
+            threshold: change_pub_key.threshold,
+            signer_commitments,
+        };
+        Self::apply_data(tree, &multisig_data)
+    }
+
+    fn get_pubdata(&self) -> Vec<bool> {
+        let mut pubdata_bits = vec![];
+        append_be_fixed_width(&mut pubdata_bits, &self.tx_type.unwrap(), TX_TYPE_BIT_WIDTH);
+
+        append_be_fixed_width(
+            &mut pubdata_bits,
+            &self.before.address.unwrap(),
+            ACCOUNT_ID_BIT_WIDTH,
+        );
+
+        append_be_fixed_width(
+            &mut pubdata_bits,
+            &self.args.new_pub_key_hash.unwrap(),
+            NEW_PUBKEY_HASH_WIDTH,
+        );
+
+        resize_grow_only(
+            &mut pubdata_bits,
+            ChangePubKeyOp::CHUNKS * CHUNK_BIT_WIDTH,
+            false,
+        );
+        pubdata_bits
+    }
+
+    fn get_offset_commitment_data(&self) -> Vec<bool> {
+        vec![false; ChangePubKeyOp::CHUNKS * 8]
+    }
+
+    fn calculate_operations(&self, input: SigDataInput) -> Vec<Operation<Bn256>> {
+        let pubdata_chunks: Vec<_> = self
+            .get_pubdata()
+            .chunks(CHUNK_BIT_WIDTH)
+            .map(|x| le_bit_vector_into_field_element(&x.to_vec()))
+            .collect();
+        let operation_zero = Operation {
+            new_root: self.after_root,
+            tx_type: self.tx_type,
+            chunk: Some(Fr::from_str("0").unwrap()),
+            pubdata_chunk: Some(pubdata_chunks[0]),
+            first_sig_msg: Some(input.first_sig_msg),
+            second_sig_msg: Some(input.second_sig_msg),
+            third_sig_msg: Some(input.third_sig_msg),
+            signature_data: input.signature.clone(),
+            signer_pub_key_packed: input.signer_pub_key_packed.to_vec(),
+            args: self.args.clone(),
+            lhs: self.before.clone(),
+            rhs: self.before.clone(),
+        };
+
+        let operations: Vec<Operation<_>> = vec![operation_zero];
+        operations
+    }
+}
+
+impl<E: RescueEngine> MultisigWitness<E> {
+    /// Builds the signed message the same way `CloseAccountWitness::get_sig_bits`
+    /// does (`tx_type || pub_key_hash || nonce`), with the `M`-of-`N` signer set's
+    /// own commitments appended: each participating signer must prove they hold
+    /// one of these keys, so the message they co-sign has to bind to the exact
+    /// set being installed, not just its aggregate commitment.
+    pub fn get_sig_bits(&self) -> Vec<bool> {
+        let mut sig_bits = vec![];
+        append_be_fixed_width(
+            &mut sig_bits,
+            &Fr::from_str("7").unwrap(), //Corresponding tx_type (ChangePubKey)
+            TX_TYPE_BIT_WIDTH,
+        );
+        append_be_fixed_width(
+            &mut sig_bits,
+            &self.before.witness.account_witness.pub_key_hash.unwrap(),
+            NEW_PUBKEY_HASH_WIDTH,
+        );
+
+        append_be_fixed_width(
+            &mut sig_bits,
+            &self.before.witness.account_witness.nonce.unwrap(),
+            NONCE_BIT_WIDTH,
+        );
+
+        for signer_commitment in &self.signer_commitments {
+            append_be_fixed_width(&mut sig_bits, signer_commitment, NEW_PUBKEY_HASH_WIDTH);
+        }
+        sig_bits
+    }
+}
+
+impl MultisigWitness<Bn256> {
+    fn apply_data(tree: &mut CircuitAccountTree, multisig_data: &MultisigData) -> Self {
+        //preparing data and base witness
+        let before_root = tree.root_hash();
+        vlog::debug!("Initial root = {}", before_root);
+        let (audit_path_before, audit_balance_path_before) =
+            get_audits(tree, multisig_data.account_address, 0);
+
+        let capacity = tree.capacity();
+        assert_eq!(capacity, 1 << account_tree_depth());
+        let account_address_fe =
+            Fr::from_str(&multisig_data.account_address.to_string()).unwrap();
+
+        //calculate a and b
+        let a = Fr::zero();
+        let b = Fr::zero();
+
+        //applying the threshold-multisig signer-set install: the account's
+        //pub_key_hash becomes the signer set's commitment, same as an
+        //ordinary ChangePubKey except the new key has no single owner.
+        let new_pub_key_hash = multisig_data.new_pub_key_hash;
+        let (account_witness_before, account_witness_after, balance_before, balance_after) =
+            apply_leaf_operation(
+                tree,
+                multisig_data.account_address,
+                0,
+                |acc| {
+                    acc.pub_key_hash = new_pub_key_hash;
+                },
+                |_| {},
+            );
+
+        let after_root = tree.root_hash();
+        vlog::debug!("After root = {}", after_root);
+        let (audit_path_after, audit_balance_path_after) =
+            get_audits(tree, multisig_data.account_address, 0);
+
+        MultisigWitness {
+            before: OperationBranch {
+                address: Some(account_address_fe),
+                token: Some(Fr::zero()),
+                witness: OperationBranchWitness {
+                    account_witness: account_witness_before,
+                    account_path: audit_path_before,
+                    balance_value: Some(balance_before),
+                    balance_subtree_path: audit_balance_path_before,
+                },
+            },
+            after: OperationBranch {
+                address: Some(account_address_fe),
+                token: Some(Fr::zero()),
+                witness: OperationBranchWitness {
+                    account_witness: account_witness_after,
+                    account_path: audit_path_after,
+                    balance_value: Some(balance_after),
+                    balance_subtree_path: audit_balance_path_after,
+                },
+            },
+            args: OperationArguments {
+                atp_address: Some(Fr::zero()),
+                amount_packed: Some(Fr::zero()),
+                full_amount: Some(Fr::zero()),
+                pub_nonce: Some(Fr::zero()),
+                fee: Some(Fr::zero()),
+                a: Some(a),
+                b: Some(b),
+                new_pub_key_hash: Some(new_pub_key_hash),
+                valid_from: Some(Fr::zero()),
+                valid_until: Some(Fr::from_str(&u32::MAX.to_string()).unwrap()),
+            },
+            before_root: Some(before_root),
+            after_root: Some(after_root),
+            tx_type: Some(Fr::from_str("7").unwrap()),
+            signer_commitments: multisig_data.signer_commitments.clone(),
+        }
+    }
+}
@@ -1,8 +1,9 @@
 use anyhow::{ensure, format_err};
 use std::time::Instant;
-use zkdpos_crypto::params::{max_account_id};
+use zkdpos_crypto::params::max_account_id;
 use zkdpos_types::{
-    AccountUpdate, AccountUpdates, AddLiquidity, AddLiquidityOp, Address, PubKeyHash, ZkDposOp,
+    AccountUpdate, AccountUpdates, AddLiquidity, AddLiquidityOp, Address, Pool, PubKeyHash,
+    ZkDposOp,
 };
 
 use crate::{
@@ -26,8 +27,17 @@ impl TxHandler<AddLiquidity> for ZkDposState {
             from_account.pub_key_hash != PubKeyHash::default(),
             "Account is locked"
         );
+        // An account whose `pub_key_hash` was installed as a threshold-multisig
+        // commitment (see `ChangePubKey::new_multisig`) has no single signing
+        // key, so it authorizes via `threshold_auth` against its registered
+        // signer set instead of the ordinary single-signer `signature`.
+        let threshold_signer = from_account
+            .signer_set
+            .as_ref()
+            .and_then(|signer_set| tx.verify_threshold_auth(signer_set));
         ensure!(
-            tx.verify_signature() == Some(from_account.pub_key_hash),
+            tx.verify_signature() == Some(from_account.pub_key_hash)
+                || threshold_signer == Some(from_account.pub_key_hash),
             "AddLiquidity signature is incorrect"
         );
         ensure!(
@@ -51,6 +61,12 @@ impl TxHandler<AddLiquidity> for ZkDposState {
         })
     }
 
+    /// Deposits `amount_a_desired`/`amount_b_desired` into the pool's reserves and mints the
+    /// depositor LP shares for it, following Uniswap-V2 math: the first deposit sets the price
+    /// and mints `sqrt(amount_a * amount_b)` shares (less the permanently locked
+    /// `pool::MINIMUM_LIQUIDITY`), while later deposits must match the pool's existing ratio
+    /// and mint shares proportionally. LP shares are tracked as an ordinary account balance of
+    /// the pool's reserved `Pool::lp_token_id`, so minting is just another `AccountUpdate`.
     fn apply_op(
         &mut self,
         op: &Self::Op,
@@ -60,60 +76,114 @@ impl TxHandler<AddLiquidity> for ZkDposState {
             op.from <= max_account_id(),
             "AddLiquidity from account id is bigger than max supported"
         );
-        ensure!(
-            op.to <= max_account_id(),
-            "AddLiquidity to account id is bigger than max supported"
-        );
 
         let mut updates = Vec::new();
-        let mut from_account = self.get_account(op.from).unwrap();
-        let mut to_account = self.get_account(op.to).unwrap();
+        let mut account = self.get_account(op.from).unwrap();
 
-        let from_old_balance = from_account.get_balance(op.tx.token);
-        let from_old_nonce = from_account.nonce;
+        let old_nonce = account.nonce;
+        ensure!(op.tx.nonce == old_nonce, "Nonce mismatch");
 
-        ensure!(op.tx.nonce == from_old_nonce, "Nonce mismatch");
+        let old_balance_a = account.get_balance(op.tx.token_a);
+        let old_balance_b = account.get_balance(op.tx.token_b);
         ensure!(
-            from_old_balance >= &op.tx.amount_a_min + &op.tx.fee_a,
-            "Not enough balance"
+            old_balance_a >= &op.tx.amount_a_desired + &op.tx.fee_a,
+            "Not enough balance of token A"
+        );
+        ensure!(
+            old_balance_b >= &op.tx.amount_b_desired + &op.tx.fee_b,
+            "Not enough balance of token B"
+        );
+        self.token_limits
+            .check(op.tx.token_a, &op.tx.amount_a_desired)?;
+        self.token_limits
+            .check(op.tx.token_b, &op.tx.amount_b_desired)?;
+
+        let mut pool = self
+            .get_pool(op.tx.liquidity_id)
+            .unwrap_or_else(|| Pool::empty(op.tx.liquidity_id, op.tx.token_a, op.tx.token_b));
+
+        let (amount_a, amount_b, shares) = if pool.is_empty() {
+            let shares = Pool::initial_shares(&op.tx.amount_a_desired, &op.tx.amount_b_desired)
+                .ok_or_else(|| format_err!("Initial deposit is too small to mint any shares"))?;
+            // The locked minimum is counted in `total_shares` but credited to no account.
+            pool.total_shares += num::BigUint::from(zkdpos_types::pool::MINIMUM_LIQUIDITY);
+            (
+                op.tx.amount_a_desired.clone(),
+                op.tx.amount_b_desired.clone(),
+                shares,
+            )
+        } else {
+            ensure!(
+                op.tx.effective_ratio().eq_exact(&pool.effective_ratio()),
+                "Deposit does not match the pool's current reserve ratio"
+            );
+            ensure!(
+                op.tx.covers_price(pool.effective_ratio().to_scaled_price())?,
+                "Pool's current price is outside the deposit's concentrated-liquidity band"
+            );
+            let shares = pool.proportional_shares(&op.tx.amount_a_desired, &op.tx.amount_b_desired);
+            (
+                op.tx.amount_a_desired.clone(),
+                op.tx.amount_b_desired.clone(),
+                shares,
+            )
+        };
+        ensure!(
+            amount_a >= op.tx.amount_a_min,
+            "Deposit of token A is below the minimum accepted"
+        );
+        ensure!(
+            amount_b >= op.tx.amount_b_min,
+            "Deposit of token B is below the minimum accepted"
         );
 
-        from_account.sub_balance(op.tx.token, &(&op.tx.amount_a_desired + &op.tx.fee_a));
-        *from_account.nonce += 1;
-
-        let from_new_balance = from_account.get_balance(op.tx.token);
-        let from_new_nonce = from_account.nonce;
+        account.sub_balance(op.tx.token_a, &(&amount_a + &op.tx.fee_a));
+        account.sub_balance(op.tx.token_b, &(&amount_b + &op.tx.fee_b));
+        *account.nonce += 1;
+        let new_nonce = account.nonce;
 
-        let to_old_balance = to_account.get_balance(op.tx.token);
-        let to_account_nonce = to_account.nonce;
+        let new_balance_a = account.get_balance(op.tx.token_a);
+        let new_balance_b = account.get_balance(op.tx.token_b);
 
-        to_account.add_balance(op.tx.token, &op.tx.amount_b_desired);
+        let lp_token = Pool::lp_token_id(op.tx.liquidity_id);
+        let old_lp_balance = account.get_balance(lp_token);
+        account.add_balance(lp_token, &shares);
+        let new_lp_balance = account.get_balance(lp_token);
 
-        let to_new_balance = to_account.get_balance(op.tx.token);
+        self.insert_account(op.from, account);
 
-        self.insert_account(op.from, from_account);
-        self.insert_account(op.to, to_account);
+        pool.reserve_a += amount_a;
+        pool.reserve_b += amount_b;
+        pool.total_shares += shares;
+        self.insert_pool(op.tx.liquidity_id, pool);
 
         updates.push((
             op.from,
             AccountUpdate::UpdateBalance {
-                balance_update: (op.tx.token, from_old_balance, from_new_balance),
-                old_nonce: from_old_nonce,
-                new_nonce: from_new_nonce,
+                balance_update: (op.tx.token_a, old_balance_a, new_balance_a),
+                old_nonce,
+                new_nonce,
+            },
+        ));
+        updates.push((
+            op.from,
+            AccountUpdate::UpdateBalance {
+                balance_update: (op.tx.token_b, old_balance_b, new_balance_b),
+                old_nonce: new_nonce,
+                new_nonce,
             },
         ));
-
         updates.push((
-            op.to,
+            op.from,
             AccountUpdate::UpdateBalance {
-                balance_update: (op.tx.token, to_old_balance, to_new_balance),
-                old_nonce: to_account_nonce,
-                new_nonce: to_account_nonce,
+                balance_update: (lp_token, old_lp_balance, new_lp_balance),
+                old_nonce: new_nonce,
+                new_nonce,
             },
         ));
 
         let fee = CollectedFee {
-            token: op.tx.token,
+            token: op.tx.token_a,
             amount: op.tx.fee_a.clone(),
         };
 
@@ -0,0 +1,128 @@
+use anyhow::{ensure, format_err};
+use std::time::Instant;
+use zkdpos_crypto::params::max_account_id;
+use zkdpos_types::tx::next_durable_nonce;
+use zkdpos_types::{AccountUpdate, AccountUpdates, AdvanceNonce, AdvanceNonceOp, PubKeyHash, ZkDposOp};
+
+use crate::{
+    handler::TxHandler,
+    state::{CollectedFee, OpSuccess, ZkDposState},
+};
+
+impl TxHandler<AdvanceNonce> for ZkDposState {
+    type Op = AdvanceNonceOp;
+
+    fn create_op(&self, tx: AdvanceNonce) -> Result<Self::Op, anyhow::Error> {
+        let (account_id, account) = self
+            .get_account_by_address(&tx.account)
+            .ok_or_else(|| format_err!("AdvanceNonce account does not exist"))?;
+        ensure!(
+            account.pub_key_hash != PubKeyHash::default(),
+            "Account is locked"
+        );
+        ensure!(
+            tx.verify_signature() == Some(account.pub_key_hash),
+            "AdvanceNonce signature is incorrect"
+        );
+        ensure!(
+            account_id == tx.account_id,
+            "AdvanceNonce account id is incorrect"
+        );
+        ensure!(
+            tx.expected_durable_nonce == account.durable_nonce,
+            "Durable nonce mismatch"
+        );
+
+        let new_durable_nonce = next_durable_nonce(tx.expected_durable_nonce, self.current_block_number);
+
+        Ok(AdvanceNonceOp {
+            tx,
+            account_id,
+            new_durable_nonce,
+        })
+    }
+
+    fn apply_tx(&mut self, tx: AdvanceNonce) -> Result<OpSuccess, anyhow::Error> {
+        let op = self.create_op(tx)?;
+
+        let (fee, updates) = <Self as TxHandler<AdvanceNonce>>::apply_op(self, &op)?;
+        Ok(OpSuccess {
+            fee,
+            updates,
+            executed_op: ZkDposOp::AdvanceNonce(Box::new(op)),
+        })
+    }
+
+    /// Advances `account_id`'s durable nonce to `op.new_durable_nonce` and
+    /// collects the fee. Every check below runs, via `ensure!`, before
+    /// `self.insert_account` is called - so a failure anywhere (stale nonce,
+    /// insufficient balance, a durable nonce that moved on since `create_op`)
+    /// naturally leaves the account's stored `durable_nonce` untouched, same
+    /// as any other handler's rollback-on-failure behavior.
+    ///
+    /// The `durable_nonce` mutation is logged as its own `AccountUpdate`
+    /// entry below, alongside the balance update, so it can be reverted and
+    /// reconstructed from L1 updates the same as any other account field
+    /// change - the whole point of a durable nonce is that the stored value
+    /// gates replay, so it has to be part of the reversible state diff.
+    fn apply_op(
+        &mut self,
+        op: &Self::Op,
+    ) -> Result<(Option<CollectedFee>, AccountUpdates), anyhow::Error> {
+        let start = Instant::now();
+        ensure!(
+            op.account_id <= max_account_id(),
+            "AdvanceNonce account id is bigger than max supported"
+        );
+
+        let mut updates = Vec::new();
+        let mut account = self.get_account(op.account_id).unwrap();
+
+        let old_balance = account.get_balance(op.tx.fee_token);
+        let old_nonce = account.nonce;
+
+        ensure!(op.tx.nonce == old_nonce, "Nonce mismatch");
+        ensure!(
+            op.tx.expected_durable_nonce == account.durable_nonce,
+            "Durable nonce mismatch"
+        );
+        ensure!(old_balance >= op.tx.fee, "Not enough balance");
+
+        let old_durable_nonce = account.durable_nonce;
+
+        account.sub_balance(op.tx.fee_token, &op.tx.fee);
+        *account.nonce += 1;
+        account.durable_nonce = op.new_durable_nonce;
+
+        let new_balance = account.get_balance(op.tx.fee_token);
+        let new_nonce = account.nonce;
+
+        self.insert_account(op.account_id, account);
+
+        updates.push((
+            op.account_id,
+            AccountUpdate::UpdateBalance {
+                balance_update: (op.tx.fee_token, old_balance, new_balance),
+                old_nonce,
+                new_nonce,
+            },
+        ));
+        updates.push((
+            op.account_id,
+            AccountUpdate::ChangeDurableNonce {
+                old_durable_nonce,
+                new_durable_nonce: op.new_durable_nonce,
+                old_nonce,
+                new_nonce,
+            },
+        ));
+
+        let fee = CollectedFee {
+            token: op.tx.fee_token,
+            amount: op.tx.fee.clone(),
+        };
+
+        metrics::histogram!("state.advance_nonce", start.elapsed());
+        Ok((Some(fee), updates))
+    }
+}
@@ -0,0 +1,270 @@
+use anyhow::{ensure, format_err};
+use num::BigUint;
+use parity_crypto::Keccak256;
+use std::time::Instant;
+use zkdpos_crypto::params::{self, max_account_id};
+use zkdpos_types::{
+    AccountId, AccountUpdate, AccountUpdates, Address, Conditional, ConditionalOp,
+    ConditionalSettle, ConditionalSettleOp, PubKeyHash, TokenId, ZkDposOp,
+};
+
+use crate::{
+    handler::TxHandler,
+    state::{CollectedFee, OpSuccess, ZkDposState},
+};
+
+/// A commitment binding every term of a `Conditional` escrow that a
+/// `ConditionalSettle` is otherwise free to make up on its own: the
+/// predicate, both the release and refund addresses, the token, and the
+/// amount. Installed into the `pending` escrow account's `pub_key_hash` at
+/// lock time by `apply_conditional_op` - the same "repurpose `pub_key_hash`
+/// as a commitment slot" trick `apply_range_settle_op` uses - and re-derived
+/// at settlement time so a `ConditionalSettle` can't swap in a different
+/// recipient, predicate, or amount than the ones the funder actually locked
+/// against.
+fn lock_commitment(
+    predicate: &zkdpos_types::priority_ops::ConditionalPredicate,
+    to: &Address,
+    from: &Address,
+    token: TokenId,
+    amount: &BigUint,
+) -> PubKeyHash {
+    let mut preimage = Vec::new();
+    preimage.extend_from_slice(&predicate.to_be_bytes());
+    preimage.extend_from_slice(to.as_bytes());
+    preimage.extend_from_slice(from.as_bytes());
+    preimage.extend_from_slice(&token.to_be_bytes());
+    preimage.extend_from_slice(&amount.to_bytes_be());
+    let hash = preimage.keccak256();
+    PubKeyHash::from_bytes(&hash[hash.len() - 20..])
+        .expect("keccak256 output truncated to 20 bytes is always a valid PubKeyHash")
+}
+
+impl ZkDposState {
+    /// Applies the escrow-lock phase of a `Conditional` priority operation: moves
+    /// `priority_op.amount + priority_op.fee` out of `from` into `pending`'s escrow
+    /// sub-balance, and installs `lock_commitment` into `pending`'s `pub_key_hash`
+    /// so the eventual `ConditionalSettle` can be checked against the terms the
+    /// funder actually locked against - mirrors `apply_range_settle_op`. No fee is
+    /// collected here, since the operation may still be refunded - the fee is
+    /// only paid out once `ConditionalSettle` resolves the predicate, the same
+    /// way `apply_exchange_op` collects `tx.fee` on success.
+    pub fn apply_conditional_op(
+        &mut self,
+        priority_op: Conditional,
+        from: AccountId,
+        to: AccountId,
+        pending: AccountId,
+    ) -> Result<(Option<CollectedFee>, AccountUpdates, ConditionalOp), anyhow::Error> {
+        let start = Instant::now();
+        ensure!(
+            from <= max_account_id(),
+            "Conditional from account id is bigger than max supported"
+        );
+        ensure!(
+            pending <= max_account_id(),
+            "Conditional pending account id is bigger than max supported"
+        );
+
+        let mut updates = Vec::new();
+        let locked = &priority_op.amount + &priority_op.fee;
+
+        let mut from_account = self
+            .get_account(from)
+            .ok_or_else(|| format_err!("Conditional from account does not exist"))?;
+        let from_old_balance = from_account.get_balance(priority_op.token);
+        let from_old_nonce = from_account.nonce;
+        ensure!(from_old_balance >= locked, "Not enough balance");
+
+        from_account.sub_balance(priority_op.token, &locked);
+        let from_new_balance = from_account.get_balance(priority_op.token);
+        self.insert_account(from, from_account);
+
+        updates.push((
+            from,
+            AccountUpdate::UpdateBalance {
+                balance_update: (priority_op.token, from_old_balance, from_new_balance),
+                old_nonce: from_old_nonce,
+                new_nonce: from_old_nonce,
+            },
+        ));
+
+        let mut pending_account = self
+            .get_account(pending)
+            .ok_or_else(|| format_err!("Conditional escrow account does not exist"))?;
+        let pending_old_balance = pending_account.get_balance(priority_op.token);
+        let pending_nonce = pending_account.nonce;
+        let old_pub_key_hash = pending_account.pub_key_hash;
+
+        pending_account.add_balance(priority_op.token, &locked);
+        let commitment = lock_commitment(
+            &priority_op.predicate,
+            &priority_op.to,
+            &priority_op.from,
+            priority_op.token,
+            &priority_op.amount,
+        );
+        pending_account.pub_key_hash = commitment;
+        let pending_new_balance = pending_account.get_balance(priority_op.token);
+        self.insert_account(pending, pending_account);
+
+        updates.push((
+            pending,
+            AccountUpdate::UpdateBalance {
+                balance_update: (priority_op.token, pending_old_balance, pending_new_balance),
+                old_nonce: pending_nonce,
+                new_nonce: pending_nonce,
+            },
+        ));
+        // The commitment installed into `pub_key_hash` above gates whether a
+        // later `ConditionalSettle` is accepted (see `create_op` below), so
+        // it has to be part of the reversible state diff - mirroring the
+        // `ChangePubKeyHash` convention - rather than a silent field mutation
+        // that can't be rolled back or reconstructed from L1 updates.
+        updates.push((
+            pending,
+            AccountUpdate::ChangePubKeyHash {
+                old_pub_key_hash,
+                old_nonce: pending_nonce,
+                new_pub_key_hash: commitment,
+                new_nonce: pending_nonce,
+            },
+        ));
+
+        let op = ConditionalOp {
+            priority_op,
+            from,
+            to,
+            pending,
+        };
+
+        metrics::histogram!("state.conditional", start.elapsed());
+        Ok((None, updates, op))
+    }
+}
+
+impl TxHandler<ConditionalSettle> for ZkDposState {
+    type Op = ConditionalSettleOp;
+
+    /// A settlement only becomes valid once `tx.predicate` is satisfied (release
+    /// to `tx.to`) or, failing that, `tx.deadline_block` has passed (refund to
+    /// `tx.from`) - see `ConditionalSettle::is_release`/`is_refund`. Until
+    /// either holds, the escrow simply stays locked in `tx.pending`. Before
+    /// either check runs, `tx`'s terms are checked against the commitment
+    /// `apply_conditional_op` installed into `tx.pending`'s `pub_key_hash` at
+    /// lock time - without this check, anyone could submit a settlement
+    /// naming themselves as `to` and draining someone else's escrow.
+    fn create_op(&self, tx: ConditionalSettle) -> Result<Self::Op, anyhow::Error> {
+        ensure!(
+            tx.token <= params::max_token_id(),
+            "Token id is not supported"
+        );
+
+        let pending_account = self
+            .get_account(tx.pending)
+            .ok_or_else(|| format_err!("Conditional settle pending account does not exist"))?;
+        ensure!(
+            pending_account.pub_key_hash
+                == lock_commitment(&tx.predicate, &tx.to, &tx.from, tx.token, &tx.amount),
+            "ConditionalSettle terms do not match the escrowed lock"
+        );
+
+        let released = tx.is_release(self.current_block_number);
+        ensure!(
+            released || tx.is_refund(self.current_block_number),
+            "Conditional settle predicate is not yet resolvable"
+        );
+
+        let target_address = if released { tx.to } else { tx.from };
+        let (receiver, _receiver_account) = self
+            .get_account_by_address(&target_address)
+            .ok_or_else(|| format_err!("Conditional settle receiver account does not exist"))?;
+        let pending = tx.pending;
+
+        Ok(ConditionalSettleOp {
+            tx,
+            pending,
+            receiver,
+            released,
+        })
+    }
+
+    fn apply_tx(&mut self, tx: ConditionalSettle) -> Result<OpSuccess, anyhow::Error> {
+        let op = self.create_op(tx)?;
+
+        let (fee, updates) = <Self as TxHandler<ConditionalSettle>>::apply_op(self, &op)?;
+        Ok(OpSuccess {
+            fee,
+            updates,
+            executed_op: ZkDposOp::ConditionalSettle(Box::new(op)),
+        })
+    }
+
+    /// Moves `tx.amount + tx.fee` out of the escrow sub-account into
+    /// `receiver` in one step - unlike `apply_conditional_op`'s lock phase,
+    /// settlement is final, so the fee is collected here instead of being
+    /// carried forward again.
+    fn apply_op(
+        &mut self,
+        op: &Self::Op,
+    ) -> Result<(Option<CollectedFee>, AccountUpdates), anyhow::Error> {
+        let start = Instant::now();
+        ensure!(
+            op.pending <= max_account_id(),
+            "Conditional settle pending account id is bigger than max supported"
+        );
+        ensure!(
+            op.receiver <= max_account_id(),
+            "Conditional settle receiver account id is bigger than max supported"
+        );
+
+        let mut updates = Vec::new();
+        let locked = &op.tx.amount + &op.tx.fee;
+
+        let mut pending_account = self.get_account(op.pending).unwrap();
+        let pending_old_balance = pending_account.get_balance(op.tx.token);
+        let pending_nonce = pending_account.nonce;
+        ensure!(
+            pending_old_balance >= locked,
+            "Conditional settle: escrow balance too low"
+        );
+
+        pending_account.sub_balance(op.tx.token, &locked);
+        let pending_new_balance = pending_account.get_balance(op.tx.token);
+        self.insert_account(op.pending, pending_account);
+
+        updates.push((
+            op.pending,
+            AccountUpdate::UpdateBalance {
+                balance_update: (op.tx.token, pending_old_balance, pending_new_balance),
+                old_nonce: pending_nonce,
+                new_nonce: pending_nonce,
+            },
+        ));
+
+        let mut receiver_account = self.get_account(op.receiver).unwrap();
+        let receiver_old_balance = receiver_account.get_balance(op.tx.token);
+        let receiver_nonce = receiver_account.nonce;
+
+        receiver_account.add_balance(op.tx.token, &op.tx.amount);
+        let receiver_new_balance = receiver_account.get_balance(op.tx.token);
+        self.insert_account(op.receiver, receiver_account);
+
+        updates.push((
+            op.receiver,
+            AccountUpdate::UpdateBalance {
+                balance_update: (op.tx.token, receiver_old_balance, receiver_new_balance),
+                old_nonce: receiver_nonce,
+                new_nonce: receiver_nonce,
+            },
+        ));
+
+        let fee = CollectedFee {
+            token: op.tx.token,
+            amount: op.tx.fee.clone(),
+        };
+
+        metrics::histogram!("state.conditional_settle", start.elapsed());
+        Ok((Some(fee), updates))
+    }
+}
@@ -0,0 +1,170 @@
+use anyhow::{ensure, format_err};
+use std::time::Instant;
+use zkdpos_crypto::params::{self, max_account_id};
+use zkdpos_types::tx::next_durable_nonce;
+use zkdpos_types::{
+    AccountUpdate, AccountUpdates, Address, ConditionalTransfer, ConditionalTransferOp, PubKeyHash,
+    ZkDposOp,
+};
+
+use crate::{
+    handler::TxHandler,
+    state::{CollectedFee, OpSuccess, ZkDposState},
+};
+
+impl TxHandler<ConditionalTransfer> for ZkDposState {
+    type Op = ConditionalTransferOp;
+
+    fn create_op(&self, tx: ConditionalTransfer) -> Result<Self::Op, anyhow::Error> {
+        ensure!(
+            tx.token <= params::max_token_id(),
+            "Token id is not supported"
+        );
+        ensure!(
+            tx.to != Address::zero(),
+            "Conditional transfer to Account with address 0 is not allowed"
+        );
+        let (from, from_account) = self
+            .get_account_by_address(&tx.from)
+            .ok_or_else(|| format_err!("From account does not exist"))?;
+        ensure!(
+            from_account.pub_key_hash != PubKeyHash::default(),
+            "Account is locked"
+        );
+        let signer = tx.verify_signature();
+        // A delegate installed via `GrantDelegate` may sign on the account's
+        // behalf in place of its own `pub_key_hash`, the same as the account
+        // signing for itself.
+        ensure!(
+            signer == Some(from_account.pub_key_hash) || (signer.is_some() && signer == from_account.delegate),
+            "Conditional transfer signature is incorrect"
+        );
+        ensure!(
+            from == tx.account_id,
+            "Conditional transfer account id is incorrect"
+        );
+        let (to, _to_account) = self
+            .get_account_by_address(&tx.to)
+            .ok_or_else(|| format_err!("To account does not exist"))?;
+
+        let satisfied_leaf_bitmap = tx.satisfied_leaf_bitmap(self.current_block_timestamp);
+        let released = tx.is_satisfied(self.current_block_timestamp);
+
+        Ok(ConditionalTransferOp {
+            tx,
+            from,
+            to,
+            satisfied_leaf_bitmap,
+            released,
+        })
+    }
+
+    fn apply_tx(&mut self, tx: ConditionalTransfer) -> Result<OpSuccess, anyhow::Error> {
+        let op = self.create_op(tx)?;
+
+        let (fee, updates) = <Self as TxHandler<ConditionalTransfer>>::apply_op(self, &op)?;
+        Ok(OpSuccess {
+            fee,
+            updates,
+            executed_op: ZkDposOp::ConditionalTransfer(Box::new(op)),
+        })
+    }
+
+    /// Always debits `from` for `amount + fee` (the funds leave the sender's
+    /// balance into escrow the moment the transfer is submitted, same as
+    /// `EscrowTransfer`), but only credits `to` when `op.released` is set.
+    /// A predicate that isn't yet satisfied leaves the funds debited and
+    /// un-credited; a later resubmission of the same transfer with more
+    /// `approvals` recomputes `released` in `create_op` and performs the
+    /// actual release on that subsequent `apply_op` call.
+    fn apply_op(
+        &mut self,
+        op: &Self::Op,
+    ) -> Result<(Option<CollectedFee>, AccountUpdates), anyhow::Error> {
+        let start = Instant::now();
+        ensure!(
+            op.from <= max_account_id(),
+            "Conditional transfer from account id is bigger than max supported"
+        );
+        ensure!(
+            op.to <= max_account_id(),
+            "Conditional transfer to account id is bigger than max supported"
+        );
+
+        let mut updates = Vec::new();
+        let mut from_account = self.get_account(op.from).unwrap();
+
+        let old_balance = from_account.get_balance(op.tx.token);
+        let old_nonce = from_account.nonce;
+
+        // A transaction bound to a durable nonce (see `AdvanceNonce`) is
+        // authorized by that value staying current rather than by `nonce`
+        // matching the sequential counter, so it can be pre-signed without
+        // expiring the moment another tx from the account lands. Executing
+        // it rotates the stored durable nonce forward, exactly as submitting
+        // an explicit `AdvanceNonce` would, so the same signed transaction
+        // can't be replayed.
+        if let Some(expected_durable_nonce) = op.tx.durable_nonce {
+            ensure!(
+                expected_durable_nonce == from_account.durable_nonce,
+                "Durable nonce mismatch"
+            );
+        } else {
+            ensure!(op.tx.nonce == old_nonce, "Nonce mismatch");
+        }
+        ensure!(
+            old_balance >= &op.tx.amount + &op.tx.fee,
+            "Not enough balance"
+        );
+        self.token_limits.check(op.tx.token, &op.tx.amount)?;
+
+        from_account.sub_balance(op.tx.token, &(&op.tx.amount + &op.tx.fee));
+        if let Some(expected_durable_nonce) = op.tx.durable_nonce {
+            from_account.durable_nonce = next_durable_nonce(expected_durable_nonce, self.current_block_number);
+        } else {
+            *from_account.nonce += 1;
+        }
+
+        let new_balance = from_account.get_balance(op.tx.token);
+        let new_nonce = from_account.nonce;
+
+        self.insert_account(op.from, from_account);
+
+        updates.push((
+            op.from,
+            AccountUpdate::UpdateBalance {
+                balance_update: (op.tx.token, old_balance, new_balance),
+                old_nonce,
+                new_nonce,
+            },
+        ));
+
+        if op.released {
+            let mut to_account = self.get_account(op.to).unwrap();
+            let to_old_balance = to_account.get_balance(op.tx.token);
+            let to_nonce = to_account.nonce;
+
+            to_account.add_balance(op.tx.token, &op.tx.amount);
+            let to_new_balance = to_account.get_balance(op.tx.token);
+
+            self.insert_account(op.to, to_account);
+
+            updates.push((
+                op.to,
+                AccountUpdate::UpdateBalance {
+                    balance_update: (op.tx.token, to_old_balance, to_new_balance),
+                    old_nonce: to_nonce,
+                    new_nonce: to_nonce,
+                },
+            ));
+        }
+
+        let fee = CollectedFee {
+            token: op.tx.token,
+            amount: op.tx.fee.clone(),
+        };
+
+        metrics::histogram!("state.conditional_transfer", start.elapsed());
+        Ok((Some(fee), updates))
+    }
+}
@@ -29,14 +29,44 @@ impl TxHandler<Exchange> for ZkDposState {
             from_account.pub_key_hash != PubKeyHash::default(),
             "Account is locked"
         );
+        // An account whose `pub_key_hash` was installed as a threshold-multisig
+        // commitment (see `ChangePubKey::new_multisig`) has no single signing
+        // key, so it authorizes via `threshold_auth` against its registered
+        // signer set instead of the ordinary single-signer `signature`.
+        let threshold_signer = from_account
+            .signer_set
+            .as_ref()
+            .and_then(|signer_set| tx.verify_threshold_auth(signer_set));
         ensure!(
-            tx.verify_signature() == Some(from_account.pub_key_hash),
+            tx.verify_signature() == Some(from_account.pub_key_hash)
+                || threshold_signer == Some(from_account.pub_key_hash),
             "Exchange signature is incorrect"
         );
         ensure!(from == tx.account_id, "Exchange account id is incorrect");
+        // `chain_id == 0` is the legacy "any chain" sentinel, kept during the
+        // migration window so transactions signed before chain binding existed
+        // still verify; once a tx does carry a chain-id, it must match ours,
+        // since the id is folded into the bytes `verify_signature` rehashes.
+        ensure!(
+            tx.chain_id == 0 || tx.chain_id == self.chain_id,
+            "Exchange chain id does not match this deployment"
+        );
 
-
-        let exchange_op = ExchangeOp { tx, from, to: from };
+        tx.verify_price_attestation(&self.price_oracle)
+            .map_err(|err| format_err!("Exchange price attestation is invalid: {}", err))?;
+
+        let attested_price = tx
+            .price_attestation
+            .as_ref()
+            .map(|attestation| attestation.price)
+            .unwrap_or(0);
+        let exchange_op = ExchangeOp {
+            tx,
+            from,
+            to: from,
+            attested_price,
+            price_valid: attested_price != 0,
+        };
 
         let outcome = ExchangeOutcome::Exchange(exchange_op);
 
@@ -93,6 +123,7 @@ impl ZkDposState {
             from_old_balance >= &op.tx.amount + &op.tx.fee,
             "Not enough balance"
         );
+        self.token_limits.check(op.tx.token, &op.tx.amount)?;
 
         from_account.sub_balance(op.tx.token, &(&op.tx.amount + &op.tx.fee));
         *from_account.nonce += 1;
@@ -0,0 +1,109 @@
+use anyhow::{ensure, format_err};
+use std::time::Instant;
+use zkdpos_crypto::params::max_account_id;
+use zkdpos_types::{AccountUpdate, AccountUpdates, GrantDelegate, GrantDelegateOp, PubKeyHash, ZkDposOp};
+
+use crate::{
+    handler::TxHandler,
+    state::{CollectedFee, OpSuccess, ZkDposState},
+};
+
+impl TxHandler<GrantDelegate> for ZkDposState {
+    type Op = GrantDelegateOp;
+
+    fn create_op(&self, tx: GrantDelegate) -> Result<Self::Op, anyhow::Error> {
+        let account_id = tx.account_id;
+        let account = self
+            .get_account(account_id)
+            .ok_or_else(|| format_err!("GrantDelegate account does not exist"))?;
+        ensure!(
+            account.pub_key_hash != PubKeyHash::default(),
+            "Account is locked"
+        );
+        ensure!(
+            tx.verify_signature() == Some(account.pub_key_hash),
+            "GrantDelegate signature is incorrect"
+        );
+
+        Ok(GrantDelegateOp { tx, account_id })
+    }
+
+    fn apply_tx(&mut self, tx: GrantDelegate) -> Result<OpSuccess, anyhow::Error> {
+        let op = self.create_op(tx)?;
+
+        let (fee, updates) = <Self as TxHandler<GrantDelegate>>::apply_op(self, &op)?;
+        Ok(OpSuccess {
+            fee,
+            updates,
+            executed_op: ZkDposOp::GrantDelegate(Box::new(op)),
+        })
+    }
+
+    /// Installs (or, when `op.tx.delegate` is the default `PubKeyHash`,
+    /// revokes) `account_id`'s delegated signer and collects the fee. The
+    /// `delegate` mutation is logged as its own `AccountUpdate` entry below,
+    /// alongside the balance update, so a delegation grant/revocation can be
+    /// reverted and reconstructed from L1 updates like any other account
+    /// field change.
+    fn apply_op(
+        &mut self,
+        op: &Self::Op,
+    ) -> Result<(Option<CollectedFee>, AccountUpdates), anyhow::Error> {
+        let start = Instant::now();
+        ensure!(
+            op.account_id <= max_account_id(),
+            "GrantDelegate account id is bigger than max supported"
+        );
+
+        let mut updates = Vec::new();
+        let mut account = self.get_account(op.account_id).unwrap();
+
+        let old_balance = account.get_balance(op.tx.fee_token);
+        let old_nonce = account.nonce;
+
+        ensure!(op.tx.nonce == old_nonce, "Nonce mismatch");
+        ensure!(old_balance >= op.tx.fee, "Not enough balance");
+
+        let old_delegate = account.delegate;
+        let new_delegate = if op.tx.delegate == PubKeyHash::default() {
+            None
+        } else {
+            Some(op.tx.delegate)
+        };
+
+        account.sub_balance(op.tx.fee_token, &op.tx.fee);
+        *account.nonce += 1;
+        account.delegate = new_delegate;
+
+        let new_balance = account.get_balance(op.tx.fee_token);
+        let new_nonce = account.nonce;
+
+        self.insert_account(op.account_id, account);
+
+        updates.push((
+            op.account_id,
+            AccountUpdate::UpdateBalance {
+                balance_update: (op.tx.fee_token, old_balance, new_balance),
+                old_nonce,
+                new_nonce,
+            },
+        ));
+        updates.push((
+            op.account_id,
+            AccountUpdate::ChangeDelegate {
+                old_delegate,
+                new_delegate,
+                old_nonce,
+                new_nonce,
+            },
+        ));
+
+        let fee = CollectedFee {
+            token: op.tx.fee_token,
+            amount: op.tx.fee.clone(),
+        };
+
+        metrics::histogram!("state.grant_delegate", start.elapsed());
+        Ok((Some(fee), updates))
+    }
+}
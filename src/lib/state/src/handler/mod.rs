@@ -1,16 +1,23 @@
 use crate::state::{CollectedFee, OpSuccess};
 use zkdpos_types::AccountUpdates;
 
+mod advance_nonce;
 mod change_pubkey;
 mod close;
+mod conditional;
+mod conditional_transfer;
 mod deposit;
 mod forced_exit;
 mod full_exit;
+mod grant_delegate;
+mod order_match;
+mod range_settle;
 mod transfer;
 mod withdraw;
 mod exchange;
 mod add_liquidity;
 mod remove_liquidity;
+mod swap;
 
 /// TxHandler trait encapsulates the logic of each individual transaction
 /// handling. By transactions we assume both zkDpos network transactions,
@@ -0,0 +1,239 @@
+use anyhow::{ensure, format_err};
+use std::time::Instant;
+use zkdpos_crypto::params::max_account_id;
+use zkdpos_types::{AccountUpdate, AccountUpdates, OrderMatch, OrderMatchOp, PubKeyHash, ZkDposOp};
+
+use crate::{
+    handler::TxHandler,
+    state::{CollectedFee, OpSuccess, ZkDposState},
+};
+
+impl TxHandler<OrderMatch> for ZkDposState {
+    type Op = OrderMatchOp;
+
+    fn create_op(&self, tx: OrderMatch) -> Result<Self::Op, anyhow::Error> {
+        ensure!(
+            tx.order_a.token_sell == tx.order_b.token_buy,
+            "Order match token mismatch: order_a does not sell what order_b buys"
+        );
+        ensure!(
+            tx.order_b.token_sell == tx.order_a.token_buy,
+            "Order match token mismatch: order_b does not sell what order_a buys"
+        );
+        ensure!(
+            tx.amount <= tx.order_a.amount,
+            "Order match amount exceeds order_a's own amount"
+        );
+
+        let (account_a, account_a_state) = self
+            .get_account(tx.order_a.account_id)
+            .map(|account| (tx.order_a.account_id, account))
+            .ok_or_else(|| format_err!("Order a account does not exist"))?;
+        let (account_b, account_b_state) = self
+            .get_account(tx.order_b.account_id)
+            .map(|account| (tx.order_b.account_id, account))
+            .ok_or_else(|| format_err!("Order b account does not exist"))?;
+        ensure!(
+            account_a_state.pub_key_hash != PubKeyHash::default(),
+            "Order a account is locked"
+        );
+        ensure!(
+            account_b_state.pub_key_hash != PubKeyHash::default(),
+            "Order b account is locked"
+        );
+        ensure!(
+            tx.order_a.verify_signature() == Some(account_a_state.pub_key_hash),
+            "Order a signature is incorrect"
+        );
+        ensure!(
+            tx.order_b.verify_signature() == Some(account_b_state.pub_key_hash),
+            "Order b signature is incorrect"
+        );
+        ensure!(
+            tx.order_a.nonce == account_a_state.nonce,
+            "Order a nonce mismatch"
+        );
+        ensure!(
+            tx.order_b.nonce == account_b_state.nonce,
+            "Order b nonce mismatch"
+        );
+        ensure!(
+            tx.order_a.time_range.check_correctness(),
+            "Order a time range is invalid"
+        );
+        ensure!(
+            tx.order_b.time_range.check_correctness(),
+            "Order b time range is invalid"
+        );
+
+        let (recipient_a, _) = self
+            .get_account_by_address(&tx.order_a.recipient)
+            .ok_or_else(|| format_err!("Order a recipient account does not exist"))?;
+        let (recipient_b, _) = self
+            .get_account_by_address(&tx.order_b.recipient)
+            .ok_or_else(|| format_err!("Order b recipient account does not exist"))?;
+
+        // `amount_b` is the quantity of `order_b.token_sell` this match fills,
+        // derived from `order_a`'s own price and rounded down, so any
+        // integer-division dust stays with `order_b` rather than `order_a`.
+        let (price_sell, price_buy) = tx.order_a.price();
+        let amount_b = (&tx.amount * &price_buy) / &price_sell;
+        ensure!(
+            amount_b <= tx.order_b.amount,
+            "Order match amount exceeds order_b's own amount"
+        );
+
+        // Cross-multiplied limit checks: `actual_price >= order.price` as
+        // `actual_sell * order.price.1 >= order.price.0 * actual_buy`, so
+        // neither side ever loses precision to a division.
+        ensure!(
+            &tx.amount * &price_buy >= &price_sell * &amount_b,
+            "Order a limit price is not satisfied"
+        );
+        let (order_b_price_sell, order_b_price_buy) = tx.order_b.price();
+        ensure!(
+            &amount_b * &order_b_price_buy >= &order_b_price_sell * &tx.amount,
+            "Order b limit price is not satisfied"
+        );
+
+        Ok(OrderMatchOp {
+            tx,
+            account_a,
+            account_b,
+            recipient_a,
+            recipient_b,
+            amount_b,
+        })
+    }
+
+    fn apply_tx(&mut self, tx: OrderMatch) -> Result<OpSuccess, anyhow::Error> {
+        let op = self.create_op(tx)?;
+
+        let (fee, updates) = <Self as TxHandler<OrderMatch>>::apply_op(self, &op)?;
+        Ok(OpSuccess {
+            fee,
+            updates,
+            executed_op: ZkDposOp::OrderMatch(Box::new(op)),
+        })
+    }
+
+    /// Debits `account_a` for `order_a`'s filled amount plus the submitter's
+    /// fee (both in `order_a.token_sell`), debits `account_b` for `order_b`'s
+    /// filled amount, and credits each recipient with the other side's sold
+    /// token, settling the two orders atomically within one operation.
+    fn apply_op(
+        &mut self,
+        op: &Self::Op,
+    ) -> Result<(Option<CollectedFee>, AccountUpdates), anyhow::Error> {
+        let start = Instant::now();
+        ensure!(
+            op.account_a <= max_account_id(),
+            "Order match account a id is bigger than max supported"
+        );
+        ensure!(
+            op.account_b <= max_account_id(),
+            "Order match account b id is bigger than max supported"
+        );
+        ensure!(
+            op.recipient_a <= max_account_id(),
+            "Order match recipient a id is bigger than max supported"
+        );
+        ensure!(
+            op.recipient_b <= max_account_id(),
+            "Order match recipient b id is bigger than max supported"
+        );
+
+        let mut updates = Vec::new();
+
+        let mut account_a = self.get_account(op.account_a).unwrap();
+        let token_sell_a = op.tx.order_a.token_sell;
+        let token_sell_b = op.tx.order_b.token_sell;
+
+        let old_balance_a = account_a.get_balance(token_sell_a);
+        let old_nonce_a = account_a.nonce;
+        ensure!(
+            old_balance_a >= &op.tx.amount + &op.tx.fee,
+            "Order a account does not have enough balance"
+        );
+        self.token_limits.check(token_sell_a, &op.tx.amount)?;
+
+        account_a.sub_balance(token_sell_a, &(&op.tx.amount + &op.tx.fee));
+        *account_a.nonce += 1;
+        let new_balance_a = account_a.get_balance(token_sell_a);
+        let new_nonce_a = account_a.nonce;
+        self.insert_account(op.account_a, account_a);
+
+        updates.push((
+            op.account_a,
+            AccountUpdate::UpdateBalance {
+                balance_update: (token_sell_a, old_balance_a, new_balance_a),
+                old_nonce: old_nonce_a,
+                new_nonce: new_nonce_a,
+            },
+        ));
+
+        let mut account_b = self.get_account(op.account_b).unwrap();
+        let old_balance_b = account_b.get_balance(token_sell_b);
+        let old_nonce_b = account_b.nonce;
+        ensure!(
+            old_balance_b >= op.amount_b,
+            "Order b account does not have enough balance"
+        );
+        self.token_limits.check(token_sell_b, &op.amount_b)?;
+
+        account_b.sub_balance(token_sell_b, &op.amount_b);
+        *account_b.nonce += 1;
+        let new_balance_b = account_b.get_balance(token_sell_b);
+        let new_nonce_b = account_b.nonce;
+        self.insert_account(op.account_b, account_b);
+
+        updates.push((
+            op.account_b,
+            AccountUpdate::UpdateBalance {
+                balance_update: (token_sell_b, old_balance_b, new_balance_b),
+                old_nonce: old_nonce_b,
+                new_nonce: new_nonce_b,
+            },
+        ));
+
+        let mut recipient_a = self.get_account(op.recipient_a).unwrap();
+        let recipient_a_old_balance = recipient_a.get_balance(token_sell_b);
+        let recipient_a_nonce = recipient_a.nonce;
+        recipient_a.add_balance(token_sell_b, &op.amount_b);
+        let recipient_a_new_balance = recipient_a.get_balance(token_sell_b);
+        self.insert_account(op.recipient_a, recipient_a);
+
+        updates.push((
+            op.recipient_a,
+            AccountUpdate::UpdateBalance {
+                balance_update: (token_sell_b, recipient_a_old_balance, recipient_a_new_balance),
+                old_nonce: recipient_a_nonce,
+                new_nonce: recipient_a_nonce,
+            },
+        ));
+
+        let mut recipient_b = self.get_account(op.recipient_b).unwrap();
+        let recipient_b_old_balance = recipient_b.get_balance(token_sell_a);
+        let recipient_b_nonce = recipient_b.nonce;
+        recipient_b.add_balance(token_sell_a, &op.tx.amount);
+        let recipient_b_new_balance = recipient_b.get_balance(token_sell_a);
+        self.insert_account(op.recipient_b, recipient_b);
+
+        updates.push((
+            op.recipient_b,
+            AccountUpdate::UpdateBalance {
+                balance_update: (token_sell_a, recipient_b_old_balance, recipient_b_new_balance),
+                old_nonce: recipient_b_nonce,
+                new_nonce: recipient_b_nonce,
+            },
+        ));
+
+        let fee = CollectedFee {
+            token: token_sell_a,
+            amount: op.tx.fee.clone(),
+        };
+
+        metrics::histogram!("state.order_match", start.elapsed());
+        Ok((Some(fee), updates))
+    }
+}
@@ -0,0 +1,314 @@
+use anyhow::{ensure, format_err};
+use num::BigUint;
+use parity_crypto::Keccak256;
+use std::time::Instant;
+use zkdpos_crypto::params::max_account_id;
+use zkdpos_types::{
+    AccountId, AccountUpdate, AccountUpdates, Address, PubKeyHash, RangeSettle,
+    RangeSettleComplete, RangeSettleCompleteOp, RangeSettleOp, TokenId, ZkDposOp,
+};
+
+use crate::{
+    handler::TxHandler,
+    state::{CollectedFee, OpSuccess, ZkDposState},
+};
+
+/// A single commitment binding every term of a `RangeSettle` escrow that
+/// can't be committed to the lock op's own fixed-offset pubdata: the oracle
+/// identity, the full payout curve, and the recipients/token/amount that
+/// `RangeSettleComplete` is otherwise free to make up on its own. Installed
+/// into the `pending` escrow account's `pub_key_hash` at lock time by
+/// `apply_range_settle_op`, the same "repurpose `pub_key_hash` as a
+/// commitment slot" trick `ChangePubKey::new_multisig` uses for
+/// `AccountSignerSet`, and re-derived at settlement time so a
+/// `RangeSettleComplete` can't swap in different terms - including a
+/// different payout recipient or a larger amount - than the ones the funder
+/// actually locked against.
+#[allow(clippy::too_many_arguments)]
+fn lock_commitment(
+    oracle_pubkey_hash: &PubKeyHash,
+    curve: &zkdpos_types::dlc::RangeSettleCurve,
+    to_a: &Address,
+    to_b: &Address,
+    token: TokenId,
+    amount: &BigUint,
+) -> PubKeyHash {
+    let mut preimage = Vec::new();
+    preimage.extend_from_slice(&oracle_pubkey_hash.data);
+    preimage.extend_from_slice(&curve.commitment().data);
+    preimage.extend_from_slice(to_a.as_bytes());
+    preimage.extend_from_slice(to_b.as_bytes());
+    preimage.extend_from_slice(&token.to_be_bytes());
+    preimage.extend_from_slice(&amount.to_bytes_be());
+    let hash = preimage.keccak256();
+    PubKeyHash::from_bytes(&hash[hash.len() - 20..])
+        .expect("keccak256 output truncated to 20 bytes is always a valid PubKeyHash")
+}
+
+impl ZkDposState {
+    /// Applies the escrow-lock phase of a `RangeSettle` priority operation: moves
+    /// `priority_op.amount + priority_op.fee` out of `from` into `pending`'s escrow
+    /// sub-balance, and installs `lock_commitment` into `pending`'s `pub_key_hash`
+    /// so the eventual `RangeSettleComplete` can be checked against the oracle/curve
+    /// the funder actually agreed to. No fee is collected here, since the operation
+    /// may still be refunded - mirrors `apply_conditional_op`.
+    pub fn apply_range_settle_op(
+        &mut self,
+        priority_op: RangeSettle,
+        from: AccountId,
+        to_a: AccountId,
+        to_b: AccountId,
+        pending: AccountId,
+    ) -> Result<(Option<CollectedFee>, AccountUpdates, RangeSettleOp), anyhow::Error> {
+        let start = Instant::now();
+        ensure!(
+            from <= max_account_id(),
+            "RangeSettle from account id is bigger than max supported"
+        );
+        ensure!(
+            pending <= max_account_id(),
+            "RangeSettle pending account id is bigger than max supported"
+        );
+
+        let mut updates = Vec::new();
+        let locked = &priority_op.amount + &priority_op.fee;
+
+        let mut from_account = self
+            .get_account(from)
+            .ok_or_else(|| format_err!("RangeSettle from account does not exist"))?;
+        let from_old_balance = from_account.get_balance(priority_op.token);
+        let from_old_nonce = from_account.nonce;
+        ensure!(from_old_balance >= locked, "Not enough balance");
+
+        from_account.sub_balance(priority_op.token, &locked);
+        let from_new_balance = from_account.get_balance(priority_op.token);
+        self.insert_account(from, from_account);
+
+        updates.push((
+            from,
+            AccountUpdate::UpdateBalance {
+                balance_update: (priority_op.token, from_old_balance, from_new_balance),
+                old_nonce: from_old_nonce,
+                new_nonce: from_old_nonce,
+            },
+        ));
+
+        let mut pending_account = self
+            .get_account(pending)
+            .ok_or_else(|| format_err!("RangeSettle escrow account does not exist"))?;
+        let pending_old_balance = pending_account.get_balance(priority_op.token);
+        let pending_nonce = pending_account.nonce;
+        let old_pub_key_hash = pending_account.pub_key_hash;
+
+        pending_account.add_balance(priority_op.token, &locked);
+        pending_account.pub_key_hash = lock_commitment(
+            &priority_op.oracle_pubkey_hash,
+            &priority_op.curve,
+            &priority_op.to_a,
+            &priority_op.to_b,
+            priority_op.token,
+            &priority_op.amount,
+        );
+        let pending_new_balance = pending_account.get_balance(priority_op.token);
+        self.insert_account(pending, pending_account);
+
+        updates.push((
+            pending,
+            AccountUpdate::UpdateBalance {
+                balance_update: (priority_op.token, pending_old_balance, pending_new_balance),
+                old_nonce: pending_nonce,
+                new_nonce: pending_nonce,
+            },
+        ));
+        // The commitment installed into `pub_key_hash` above gates whether a
+        // later `RangeSettleComplete` is accepted (see `create_op` below), so
+        // it has to be part of the reversible state diff - mirroring the
+        // `ChangePubKeyHash` convention - rather than a silent field mutation
+        // that can't be rolled back or reconstructed from L1 updates.
+        updates.push((
+            pending,
+            AccountUpdate::ChangePubKeyHash {
+                old_pub_key_hash,
+                old_nonce: pending_nonce,
+                new_pub_key_hash: lock_commitment(
+                    &priority_op.oracle_pubkey_hash,
+                    &priority_op.curve,
+                    &priority_op.to_a,
+                    &priority_op.to_b,
+                    priority_op.token,
+                    &priority_op.amount,
+                ),
+                new_nonce: pending_nonce,
+            },
+        ));
+
+        let op = RangeSettleOp {
+            priority_op,
+            from,
+            to_a,
+            to_b,
+            pending,
+        };
+
+        metrics::histogram!("state.range_settle", start.elapsed());
+        Ok((None, updates, op))
+    }
+}
+
+impl TxHandler<RangeSettleComplete> for ZkDposState {
+    type Op = RangeSettleCompleteOp;
+
+    /// Resolves the escrow's payout split via `tx.resolved_payout_a_bp`, after
+    /// first checking that `tx.oracle_pubkey_hash`/`tx.curve` match the
+    /// commitment `apply_range_settle_op` installed into `tx.pending`'s
+    /// `pub_key_hash` at lock time - without this check, anyone could submit a
+    /// `RangeSettleComplete` carrying a fabricated curve to steer the payout.
+    fn create_op(&self, tx: RangeSettleComplete) -> Result<Self::Op, anyhow::Error> {
+        let pending_account = self
+            .get_account(tx.pending)
+            .ok_or_else(|| format_err!("RangeSettle pending account does not exist"))?;
+        ensure!(
+            pending_account.pub_key_hash
+                == lock_commitment(
+                    &tx.oracle_pubkey_hash,
+                    &tx.curve,
+                    &tx.to_a,
+                    &tx.to_b,
+                    tx.token,
+                    &tx.amount,
+                ),
+            "RangeSettleComplete curve/oracle does not match the escrowed lock"
+        );
+
+        let payout_a_bp = tx
+            .resolved_payout_a_bp(self.current_block_number)
+            .ok_or_else(|| format_err!("RangeSettle outcome is not yet resolvable"))?;
+        let refunded = tx
+            .oracle_attestation
+            .as_ref()
+            .map_or(true, |attestation| {
+                !(attestation.oracle_pubkey_hash == tx.oracle_pubkey_hash
+                    && attestation.verify()
+                    && tx.curve.payout_bp_for_outcome(attestation.outcome) == Some(payout_a_bp))
+            });
+
+        let (to_a, _) = self
+            .get_account_by_address(&tx.to_a)
+            .ok_or_else(|| format_err!("RangeSettle to_a account does not exist"))?;
+        let (to_b, _) = self
+            .get_account_by_address(&tx.to_b)
+            .ok_or_else(|| format_err!("RangeSettle to_b account does not exist"))?;
+
+        let payout_a = (&tx.amount * payout_a_bp as u64) / 10_000u64;
+        let payout_b = &tx.amount - &payout_a;
+        let pending = tx.pending;
+
+        Ok(RangeSettleCompleteOp {
+            tx,
+            pending,
+            to_a,
+            to_b,
+            payout_a,
+            payout_b,
+            refunded,
+        })
+    }
+
+    fn apply_tx(&mut self, tx: RangeSettleComplete) -> Result<OpSuccess, anyhow::Error> {
+        let op = self.create_op(tx)?;
+
+        let (fee, updates) = <Self as TxHandler<RangeSettleComplete>>::apply_op(self, &op)?;
+        Ok(OpSuccess {
+            fee,
+            updates,
+            executed_op: ZkDposOp::RangeSettleComplete(Box::new(op)),
+        })
+    }
+
+    /// Moves `tx.amount + tx.fee` out of the escrow sub-account, crediting
+    /// `payout_a` to `to_a` and `payout_b` to `to_b` - settlement is final, so
+    /// the fee is collected here instead of being carried forward again,
+    /// mirroring the `ConditionalSettle` handler's `apply_op`.
+    fn apply_op(
+        &mut self,
+        op: &Self::Op,
+    ) -> Result<(Option<CollectedFee>, AccountUpdates), anyhow::Error> {
+        let start = Instant::now();
+        ensure!(
+            op.pending <= max_account_id(),
+            "RangeSettleComplete pending account id is bigger than max supported"
+        );
+        ensure!(
+            op.to_a <= max_account_id(),
+            "RangeSettleComplete to_a account id is bigger than max supported"
+        );
+        ensure!(
+            op.to_b <= max_account_id(),
+            "RangeSettleComplete to_b account id is bigger than max supported"
+        );
+
+        let mut updates = Vec::new();
+        let locked = &op.tx.amount + &op.tx.fee;
+
+        let mut pending_account = self.get_account(op.pending).unwrap();
+        let pending_old_balance = pending_account.get_balance(op.tx.token);
+        let pending_nonce = pending_account.nonce;
+        ensure!(
+            pending_old_balance >= locked,
+            "RangeSettleComplete: escrow balance too low"
+        );
+
+        pending_account.sub_balance(op.tx.token, &locked);
+        let pending_new_balance = pending_account.get_balance(op.tx.token);
+        self.insert_account(op.pending, pending_account);
+
+        updates.push((
+            op.pending,
+            AccountUpdate::UpdateBalance {
+                balance_update: (op.tx.token, pending_old_balance, pending_new_balance),
+                old_nonce: pending_nonce,
+                new_nonce: pending_nonce,
+            },
+        ));
+
+        let mut to_a_account = self.get_account(op.to_a).unwrap();
+        let to_a_old_balance = to_a_account.get_balance(op.tx.token);
+        let to_a_nonce = to_a_account.nonce;
+        to_a_account.add_balance(op.tx.token, &op.payout_a);
+        let to_a_new_balance = to_a_account.get_balance(op.tx.token);
+        self.insert_account(op.to_a, to_a_account);
+
+        updates.push((
+            op.to_a,
+            AccountUpdate::UpdateBalance {
+                balance_update: (op.tx.token, to_a_old_balance, to_a_new_balance),
+                old_nonce: to_a_nonce,
+                new_nonce: to_a_nonce,
+            },
+        ));
+
+        let mut to_b_account = self.get_account(op.to_b).unwrap();
+        let to_b_old_balance = to_b_account.get_balance(op.tx.token);
+        let to_b_nonce = to_b_account.nonce;
+        to_b_account.add_balance(op.tx.token, &op.payout_b);
+        let to_b_new_balance = to_b_account.get_balance(op.tx.token);
+        self.insert_account(op.to_b, to_b_account);
+
+        updates.push((
+            op.to_b,
+            AccountUpdate::UpdateBalance {
+                balance_update: (op.tx.token, to_b_old_balance, to_b_new_balance),
+                old_nonce: to_b_nonce,
+                new_nonce: to_b_nonce,
+            },
+        ));
+
+        let fee = CollectedFee {
+            token: op.tx.token,
+            amount: op.tx.fee.clone(),
+        };
+
+        metrics::histogram!("state.range_settle_complete", start.elapsed());
+        Ok((Some(fee), updates))
+    }
+}
@@ -1,8 +1,9 @@
 use anyhow::{ensure, format_err};
 use std::time::Instant;
-use zkdpos_crypto::params::{self, max_account_id};
+use zkdpos_crypto::params::max_account_id;
 use zkdpos_types::{
-    AccountUpdate, AccountUpdates, Address, PubKeyHash, RemoveLiquidity, RemoveLiquidityOp, ZkDposOp
+    AccountUpdate, AccountUpdates, Address, Pool, PubKeyHash, RemoveLiquidity, RemoveLiquidityOp,
+    ZkDposOp,
 };
 
 use crate::{
@@ -14,10 +15,6 @@ impl TxHandler<RemoveLiquidity> for ZkDposState {
     type Op = RemoveLiquidityOp;
 
     fn create_op(&self, tx: RemoveLiquidity) -> Result<Self::Op, anyhow::Error> {
-        ensure!(
-            tx.token <= params::max_token_id(),
-            "Token id is not supported"
-        );
         ensure!(
             tx.to != Address::zero(),
             "RemoveLiquidity to Account with address 0 is not allowed"
@@ -30,12 +27,23 @@ impl TxHandler<RemoveLiquidity> for ZkDposState {
             from_account.pub_key_hash != PubKeyHash::default(),
             "Account is locked"
         );
+        // An account whose `pub_key_hash` was installed as a threshold-multisig
+        // commitment (see `ChangePubKey::new_multisig`) has no single signing
+        // key, so it authorizes via `threshold_auth` against its registered
+        // signer set instead of the ordinary single-signer `signature`.
+        let threshold_signer = from_account
+            .signer_set
+            .as_ref()
+            .and_then(|signer_set| tx.verify_threshold_auth(signer_set));
         ensure!(
-            tx.verify_signature() == Some(from_account.pub_key_hash),
+            tx.verify_signature() == Some(from_account.pub_key_hash)
+                || threshold_signer == Some(from_account.pub_key_hash),
             "RemoveLiquidity signature is incorrect"
         );
-        ensure!(from == tx.account_id, "RemoveLiquidity account id is incorrect");
-
+        ensure!(
+            from == tx.account_id,
+            "RemoveLiquidity account id is incorrect"
+        );
 
         let remove_liquidity_op = RemoveLiquidityOp { tx, from, to: from };
 
@@ -53,6 +61,8 @@ impl TxHandler<RemoveLiquidity> for ZkDposState {
         })
     }
 
+    /// Burns `shares` of the sender's LP balance and returns the corresponding proportion
+    /// of both pool reserves, enforcing `amount_a_min`/`amount_b_min` slippage bounds.
     fn apply_op(
         &mut self,
         op: &Self::Op,
@@ -60,68 +70,95 @@ impl TxHandler<RemoveLiquidity> for ZkDposState {
         let start = Instant::now();
         ensure!(
             op.from <= max_account_id(),
-            "AddLiquidity from account id is bigger than max supported"
-        );
-        ensure!(
-            op.to <= max_account_id(),
-            "AddLiquidity to account id is bigger than max supported"
+            "RemoveLiquidity from account id is bigger than max supported"
         );
 
+        let mut pool = self
+            .get_pool(op.tx.liquidity_id)
+            .ok_or_else(|| format_err!("Pool does not exist"))?;
+        ensure!(!pool.is_empty(), "Pool has no liquidity to remove");
 
         let mut updates = Vec::new();
-        let mut from_account = self.get_account(op.from).unwrap();
-        let mut to_account = self.get_account(op.to).unwrap();
+        let mut account = self.get_account(op.from).unwrap();
+
+        let old_nonce = account.nonce;
+        ensure!(op.tx.nonce == old_nonce, "Nonce mismatch");
 
-        let from_old_balance = from_account.get_balance(op.tx.token);
-        let from_old_nonce = from_account.nonce;
+        let lp_token = Pool::lp_token_id(op.tx.liquidity_id);
+        let old_lp_balance = account.get_balance(lp_token);
+        ensure!(old_lp_balance >= &op.tx.shares, "Not enough LP shares");
 
-        ensure!(op.tx.nonce == from_old_nonce, "Nonce mismatch");
+        let (amount_a, amount_b) = pool.shares_to_amounts(&op.tx.shares);
         ensure!(
-            from_old_balance >= &op.tx.amount_a_desired + &op.tx.fee_a,
-            "Not enough balance"
+            amount_a >= op.tx.amount_a_min,
+            "Withdrawal of token A is below the minimum accepted"
         );
+        ensure!(
+            amount_b >= op.tx.amount_b_min,
+            "Withdrawal of token B is below the minimum accepted"
+        );
+        self.token_limits.check(op.tx.token_a, &amount_a)?;
+        self.token_limits.check(op.tx.token_b, &amount_b)?;
 
-        from_account.sub_balance(op.tx.token, &(&op.tx.amount_a_desired + &op.tx.fee_a));
-        *from_account.nonce += 1;
-
-        let from_new_balance = from_account.get_balance(op.tx.token);
-        let from_new_nonce = from_account.nonce;
+        let old_balance_a = account.get_balance(op.tx.token_a);
+        let old_balance_b = account.get_balance(op.tx.token_b);
+        ensure!(
+            &amount_a >= &op.tx.fee_a,
+            "Withdrawal of token A does not cover the fee"
+        );
+        ensure!(
+            &amount_b >= &op.tx.fee_b,
+            "Withdrawal of token B does not cover the fee"
+        );
 
-        let to_old_balance = to_account.get_balance(op.tx.token);
-        let to_account_nonce = to_account.nonce;
+        account.sub_balance(lp_token, &op.tx.shares);
+        account.add_balance(op.tx.token_a, &(&amount_a - &op.tx.fee_a));
+        account.add_balance(op.tx.token_b, &(&amount_b - &op.tx.fee_b));
+        *account.nonce += 1;
+        let new_nonce = account.nonce;
 
-        to_account.add_balance(op.tx.token, &op.tx.amount_b_desired);
+        let new_lp_balance = account.get_balance(lp_token);
+        let new_balance_a = account.get_balance(op.tx.token_a);
+        let new_balance_b = account.get_balance(op.tx.token_b);
 
-        let to_new_balance = to_account.get_balance(op.tx.token);
+        self.insert_account(op.from, account);
 
-        self.insert_account(op.from, from_account);
-        self.insert_account(op.to, to_account);
+        pool.reserve_a -= amount_a;
+        pool.reserve_b -= amount_b;
+        pool.total_shares -= &op.tx.shares;
+        self.insert_pool(op.tx.liquidity_id, pool);
 
         updates.push((
             op.from,
             AccountUpdate::UpdateBalance {
-                balance_update: (op.tx.token, from_old_balance, from_new_balance),
-                old_nonce: from_old_nonce,
-                new_nonce: from_new_nonce,
+                balance_update: (lp_token, old_lp_balance, new_lp_balance),
+                old_nonce,
+                new_nonce,
             },
         ));
-
         updates.push((
-            op.to,
+            op.from,
             AccountUpdate::UpdateBalance {
-                balance_update: (op.tx.token, to_old_balance, to_new_balance),
-                old_nonce: to_account_nonce,
-                new_nonce: to_account_nonce,
+                balance_update: (op.tx.token_a, old_balance_a, new_balance_a),
+                old_nonce: new_nonce,
+                new_nonce,
+            },
+        ));
+        updates.push((
+            op.from,
+            AccountUpdate::UpdateBalance {
+                balance_update: (op.tx.token_b, old_balance_b, new_balance_b),
+                old_nonce: new_nonce,
+                new_nonce,
             },
         ));
 
         let fee = CollectedFee {
-            token: op.tx.token,
+            token: op.tx.token_a,
             amount: op.tx.fee_a.clone(),
         };
 
         metrics::histogram!("state.remove_liquidity", start.elapsed());
         Ok((Some(fee), updates))
     }
-
-}
\ No newline at end of file
+}
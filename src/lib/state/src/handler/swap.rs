@@ -0,0 +1,148 @@
+use anyhow::{ensure, format_err};
+use std::time::Instant;
+use zkdpos_crypto::params::max_account_id;
+use zkdpos_types::{AccountUpdate, AccountUpdates, Pool, PubKeyHash, Swap, SwapOp, ZkDposOp};
+
+use crate::{
+    handler::TxHandler,
+    state::{CollectedFee, OpSuccess, ZkDposState},
+};
+
+impl TxHandler<Swap> for ZkDposState {
+    type Op = SwapOp;
+
+    fn create_op(&self, tx: Swap) -> Result<Self::Op, anyhow::Error> {
+        let account_id = tx.account_id;
+        let account = self
+            .get_account(tx.account_id)
+            .ok_or_else(|| format_err!("Account does not exist"))?;
+        ensure!(
+            account.pub_key_hash != PubKeyHash::default(),
+            "Account is locked"
+        );
+        // An account whose `pub_key_hash` was installed as a threshold-multisig
+        // commitment (see `ChangePubKey::new_multisig`) has no single signing
+        // key, so it authorizes via `threshold_auth` against its registered
+        // signer set instead of the ordinary single-signer `signature`.
+        let threshold_signer = account
+            .signer_set
+            .as_ref()
+            .and_then(|signer_set| tx.verify_threshold_auth(signer_set));
+        ensure!(
+            tx.verify_signature() == Some(account.pub_key_hash)
+                || threshold_signer == Some(account.pub_key_hash),
+            "Swap signature is incorrect"
+        );
+
+        Ok(SwapOp { tx, account_id })
+    }
+
+    fn apply_tx(&mut self, tx: Swap) -> Result<OpSuccess, anyhow::Error> {
+        let op = self.create_op(tx)?;
+
+        let (fee, updates) = <Self as TxHandler<Swap>>::apply_op(self, &op)?;
+        Ok(OpSuccess {
+            fee,
+            updates,
+            executed_op: ZkDposOp::Swap(Box::new(op)),
+        })
+    }
+
+    /// Trades `amount_in` of `token_in` against the pool's constant-product reserves for
+    /// `token_out`, failing the transaction if the output would fall below `amount_out_min`.
+    fn apply_op(
+        &mut self,
+        op: &Self::Op,
+    ) -> Result<(Option<CollectedFee>, AccountUpdates), anyhow::Error> {
+        let start = Instant::now();
+        ensure!(
+            op.account_id <= max_account_id(),
+            "Swap account id is bigger than max supported"
+        );
+
+        let mut pool = self
+            .get_pool(op.tx.liquidity_id)
+            .ok_or_else(|| format_err!("Pool does not exist"))?;
+        ensure!(!pool.is_empty(), "Pool has no liquidity to swap against");
+
+        let (reserve_in, reserve_out, expected_token_out) = if op.tx.token_in == pool.token_a {
+            (pool.reserve_a.clone(), pool.reserve_b.clone(), pool.token_b)
+        } else if op.tx.token_in == pool.token_b {
+            (pool.reserve_b.clone(), pool.reserve_a.clone(), pool.token_a)
+        } else {
+            anyhow::bail!("Swap token is not part of the pool");
+        };
+        // `token_out` is fully user-controlled; without this check a signer
+        // could set it to any token id - including one with no pool at all -
+        // and mint it from nothing while only the pool's real opposite token
+        // reserve is debited.
+        ensure!(
+            op.tx.token_out == expected_token_out,
+            "Swap token_out does not match the pool's opposite token"
+        );
+
+        let mut updates = Vec::new();
+        let mut account = self.get_account(op.account_id).unwrap();
+
+        let old_nonce = account.nonce;
+        ensure!(op.tx.nonce == old_nonce, "Nonce mismatch");
+
+        let old_balance_in = account.get_balance(op.tx.token_in);
+        let old_balance_out = account.get_balance(op.tx.token_out);
+        ensure!(
+            old_balance_in >= &op.tx.amount_in + &op.tx.fee,
+            "Not enough balance"
+        );
+        self.token_limits.check(op.tx.token_in, &op.tx.amount_in)?;
+
+        let amount_out = Pool::swap_output(&reserve_in, &reserve_out, &op.tx.amount_in);
+        ensure!(
+            amount_out >= op.tx.amount_out_min,
+            "Swap output is below the minimum accepted"
+        );
+
+        account.sub_balance(op.tx.token_in, &(&op.tx.amount_in + &op.tx.fee));
+        account.add_balance(op.tx.token_out, &amount_out);
+        *account.nonce += 1;
+        let new_nonce = account.nonce;
+
+        let new_balance_in = account.get_balance(op.tx.token_in);
+        let new_balance_out = account.get_balance(op.tx.token_out);
+
+        self.insert_account(op.account_id, account);
+
+        if op.tx.token_in == pool.token_a {
+            pool.reserve_a += op.tx.amount_in.clone();
+            pool.reserve_b -= amount_out.clone();
+        } else {
+            pool.reserve_b += op.tx.amount_in.clone();
+            pool.reserve_a -= amount_out.clone();
+        }
+        self.insert_pool(op.tx.liquidity_id, pool);
+
+        updates.push((
+            op.account_id,
+            AccountUpdate::UpdateBalance {
+                balance_update: (op.tx.token_in, old_balance_in, new_balance_in),
+                old_nonce,
+                new_nonce,
+            },
+        ));
+        updates.push((
+            op.account_id,
+            AccountUpdate::UpdateBalance {
+                balance_update: (op.tx.token_out, old_balance_out, new_balance_out),
+                old_nonce: new_nonce,
+                new_nonce,
+            },
+        ));
+
+        let fee = CollectedFee {
+            token: op.tx.token_in,
+            amount: op.tx.fee.clone(),
+        };
+
+        metrics::histogram!("state.swap", start.elapsed());
+        Ok((Some(fee), updates))
+    }
+}
@@ -0,0 +1,117 @@
+use anyhow::{ensure, format_err};
+use std::time::Instant;
+use zkdpos_crypto::params::{self, max_account_id};
+use zkdpos_types::{AccountUpdate, AccountUpdates, PubKeyHash, Withdraw, WithdrawOp, ZkDposOp};
+
+use crate::{
+    handler::TxHandler,
+    state::{CollectedFee, OpSuccess, ZkDposState},
+};
+
+impl TxHandler<Withdraw> for ZkDposState {
+    type Op = WithdrawOp;
+
+    fn create_op(&self, tx: Withdraw) -> Result<Self::Op, anyhow::Error> {
+        ensure!(
+            tx.token <= params::max_token_id(),
+            "Token id is not supported"
+        );
+        ensure!(
+            tx.account_id <= max_account_id(),
+            "Withdraw account id is bigger than max supported"
+        );
+        let account = self
+            .get_account(tx.account_id)
+            .ok_or_else(|| format_err!("Account does not exist"))?;
+        ensure!(
+            account.pub_key_hash != PubKeyHash::default(),
+            "Account is locked"
+        );
+        // An account whose `pub_key_hash` was installed as a threshold-multisig
+        // commitment (see `ChangePubKey::new_multisig`) has no single signing
+        // key, so it authorizes via `threshold_auth` against its registered
+        // signer set instead of the ordinary single-signer `signature`.
+        let threshold_signer = account
+            .signer_set
+            .as_ref()
+            .and_then(|signer_set| tx.verify_threshold_auth(signer_set));
+        ensure!(
+            tx.verify_signature() == Some(account.pub_key_hash)
+                || threshold_signer == Some(account.pub_key_hash),
+            "Withdraw signature is incorrect"
+        );
+        // `chain_id == 0` is the legacy "any chain" sentinel, kept during the
+        // migration window so transactions signed before chain binding existed
+        // still verify; once a tx does carry a chain-id, it must match ours,
+        // since the id is folded into the bytes `verify_signature` rehashes.
+        ensure!(
+            tx.chain_id == 0 || tx.chain_id == self.chain_id,
+            "Withdraw chain id does not match this deployment"
+        );
+
+        let account_id = tx.account_id;
+        Ok(WithdrawOp { tx, account_id })
+    }
+
+    fn apply_tx(&mut self, tx: Withdraw) -> Result<OpSuccess, anyhow::Error> {
+        let op = self.create_op(tx)?;
+
+        let (fee, updates) = <Self as TxHandler<Withdraw>>::apply_op(self, &op)?;
+        Ok(OpSuccess {
+            fee,
+            updates,
+            executed_op: ZkDposOp::Withdraw(Box::new(op)),
+        })
+    }
+
+    /// Debits `tx.amount + tx.fee` of `tx.token` from the withdrawing
+    /// account; the withdrawn `tx.amount` itself isn't credited to anyone on
+    /// L2 - it leaves via the L1 withdrawal queue (see
+    /// `WithdrawOp::get_withdrawal_data`).
+    fn apply_op(
+        &mut self,
+        op: &Self::Op,
+    ) -> Result<(Option<CollectedFee>, AccountUpdates), anyhow::Error> {
+        let start = Instant::now();
+        ensure!(
+            op.account_id <= max_account_id(),
+            "Withdraw account id is bigger than max supported"
+        );
+
+        let mut updates = Vec::new();
+        let mut account = self.get_account(op.account_id).unwrap();
+
+        let old_balance = account.get_balance(op.tx.token);
+        let old_nonce = account.nonce;
+        ensure!(op.tx.nonce == old_nonce, "Nonce mismatch");
+        ensure!(
+            old_balance >= &op.tx.amount + &op.tx.fee,
+            "Not enough balance"
+        );
+        self.token_limits.check(op.tx.token, &op.tx.amount)?;
+
+        account.sub_balance(op.tx.token, &(&op.tx.amount + &op.tx.fee));
+        *account.nonce += 1;
+        let new_nonce = account.nonce;
+        let new_balance = account.get_balance(op.tx.token);
+
+        self.insert_account(op.account_id, account);
+
+        updates.push((
+            op.account_id,
+            AccountUpdate::UpdateBalance {
+                balance_update: (op.tx.token, old_balance, new_balance),
+                old_nonce,
+                new_nonce,
+            },
+        ));
+
+        let fee = CollectedFee {
+            token: op.tx.token,
+            amount: op.tx.fee.clone(),
+        };
+
+        metrics::histogram!("state.withdraw", start.elapsed());
+        Ok((Some(fee), updates))
+    }
+}
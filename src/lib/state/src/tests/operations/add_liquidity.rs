@@ -0,0 +1,142 @@
+use crate::{
+    handler::TxHandler,
+    state::ZkDposState,
+    tests::{AccountState::*, PlasmaTestBuilder},
+};
+use num::{BigUint, Zero};
+use zkdpos_types::account::AccountUpdate;
+use zkdpos_types::tx::{AddLiquidity, TimeRange};
+use zkdpos_types::{LiquidityId, Pool, TokenId};
+
+// `AddLiquidity` isn't wired into `ZkDposTx`'s dispatch enum (unlike `Swap`/
+// `RemoveLiquidity`), so these tests go through its `TxHandler` impl directly
+// instead of `PlasmaTestBuilder`'s generic `test_tx_success`/`test_tx_fail`.
+
+/// First deposit into an empty pool mints `sqrt(amount_a * amount_b) -
+/// MINIMUM_LIQUIDITY` shares and moves both tokens (plus `fee_a`) out of the
+/// depositor's balance.
+#[test]
+fn add_liquidity_to_empty_pool() {
+    let token_a = TokenId(0);
+    let token_b = TokenId(1);
+    let liquidity_id = LiquidityId(0);
+    let mut tb = PlasmaTestBuilder::new();
+    let (account_id, account, sk) = tb.add_account(Unlocked);
+    tb.set_balance(account_id, token_a, BigUint::from(20_000u32));
+    tb.set_balance(account_id, token_b, BigUint::from(20_000u32));
+
+    let amount_a = BigUint::from(10_000u32);
+    let amount_b = BigUint::from(10_000u32);
+    let fee_a = BigUint::from(10u32);
+    let shares = Pool::initial_shares(&amount_a, &amount_b).expect("shares should be minted");
+    let lp_token = Pool::lp_token_id(liquidity_id);
+
+    let add_liquidity = AddLiquidity::new_signed(
+        account_id,
+        liquidity_id,
+        account.address,
+        amount_a.clone(),
+        amount_b.clone(),
+        BigUint::zero(),
+        BigUint::zero(),
+        token_a,
+        token_b,
+        fee_a.clone(),
+        BigUint::zero(),
+        0,
+        1_000_000_000,
+        account.nonce,
+        TimeRange::default(),
+        &sk,
+    )
+    .expect("signing should succeed");
+
+    let success = <ZkDposState as TxHandler<AddLiquidity>>::apply_tx(&mut tb.state, add_liquidity)
+        .expect("AddLiquidity should succeed");
+
+    assert_eq!(
+        success.updates,
+        vec![
+            (
+                account_id,
+                AccountUpdate::UpdateBalance {
+                    old_nonce: account.nonce,
+                    new_nonce: account.nonce + 1,
+                    balance_update: (
+                        token_a,
+                        BigUint::from(20_000u32),
+                        BigUint::from(20_000u32) - &amount_a - &fee_a,
+                    ),
+                },
+            ),
+            (
+                account_id,
+                AccountUpdate::UpdateBalance {
+                    old_nonce: account.nonce + 1,
+                    new_nonce: account.nonce + 1,
+                    balance_update: (
+                        token_b,
+                        BigUint::from(20_000u32),
+                        BigUint::from(20_000u32) - &amount_b,
+                    ),
+                },
+            ),
+            (
+                account_id,
+                AccountUpdate::UpdateBalance {
+                    old_nonce: account.nonce + 1,
+                    new_nonce: account.nonce + 1,
+                    balance_update: (lp_token, BigUint::zero(), shares),
+                },
+            ),
+        ]
+    );
+
+    let pool = tb
+        .state
+        .get_pool(liquidity_id)
+        .expect("pool should have been created");
+    assert_eq!(pool.reserve_a, amount_a);
+    assert_eq!(pool.reserve_b, amount_b);
+}
+
+/// A deposit the sender can't cover (insufficient token A balance) is
+/// rejected and leaves the account untouched.
+#[test]
+fn add_liquidity_insufficient_balance() {
+    let token_a = TokenId(0);
+    let token_b = TokenId(1);
+    let liquidity_id = LiquidityId(0);
+    let mut tb = PlasmaTestBuilder::new();
+    let (account_id, account, sk) = tb.add_account(Unlocked);
+    tb.set_balance(account_id, token_a, BigUint::from(100u32));
+    tb.set_balance(account_id, token_b, BigUint::from(20_000u32));
+
+    let add_liquidity = AddLiquidity::new_signed(
+        account_id,
+        liquidity_id,
+        account.address,
+        BigUint::from(10_000u32),
+        BigUint::from(10_000u32),
+        BigUint::zero(),
+        BigUint::zero(),
+        token_a,
+        token_b,
+        BigUint::from(10u32),
+        BigUint::zero(),
+        0,
+        1_000_000_000,
+        account.nonce,
+        TimeRange::default(),
+        &sk,
+    )
+    .expect("signing should succeed");
+
+    let error = <ZkDposState as TxHandler<AddLiquidity>>::apply_tx(&mut tb.state, add_liquidity)
+        .expect_err("AddLiquidity should fail");
+    assert_eq!(error.to_string(), "Not enough balance of token A");
+
+    let account_after = tb.state.get_account(account_id).unwrap();
+    assert_eq!(account_after.get_balance(token_a), BigUint::from(100u32));
+    assert_eq!(account_after.nonce, account.nonce);
+}
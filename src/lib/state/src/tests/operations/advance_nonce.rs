@@ -0,0 +1,102 @@
+use crate::{
+    handler::TxHandler,
+    state::ZkDposState,
+    tests::{AccountState::*, PlasmaTestBuilder},
+};
+use num::BigUint;
+use zkdpos_basic_types::H256;
+use zkdpos_types::account::AccountUpdate;
+use zkdpos_types::tx::{next_durable_nonce, AdvanceNonce, TimeRange};
+use zkdpos_types::TokenId;
+
+/// Advancing a durable nonce debits the fee and, per the review fix, records
+/// the nonce rotation as its own `ChangeDurableNonce` update rather than
+/// riding along silently on the balance update.
+#[test]
+fn advance_nonce_records_balance_and_durable_nonce_updates() {
+    let token = TokenId(0);
+    let mut tb = PlasmaTestBuilder::new();
+    let (account_id, account, sk) = tb.add_account(Unlocked);
+    tb.set_balance(account_id, token, BigUint::from(100u32));
+
+    let fee = BigUint::from(1u32);
+    let tx = AdvanceNonce::new_signed(
+        account_id,
+        account.address,
+        account.durable_nonce,
+        token,
+        fee.clone(),
+        account.nonce,
+        TimeRange::default(),
+        &sk,
+    )
+    .expect("signing should succeed");
+
+    let expected_new_durable_nonce =
+        next_durable_nonce(account.durable_nonce, tb.state.current_block_number);
+
+    let success = <ZkDposState as TxHandler<AdvanceNonce>>::apply_tx(&mut tb.state, tx)
+        .expect("AdvanceNonce should succeed");
+
+    assert_eq!(
+        success.updates,
+        vec![
+            (
+                account_id,
+                AccountUpdate::UpdateBalance {
+                    old_nonce: account.nonce,
+                    new_nonce: account.nonce + 1,
+                    balance_update: (
+                        token,
+                        BigUint::from(100u32),
+                        BigUint::from(100u32) - &fee,
+                    ),
+                },
+            ),
+            (
+                account_id,
+                AccountUpdate::ChangeDurableNonce {
+                    old_durable_nonce: account.durable_nonce,
+                    new_durable_nonce: expected_new_durable_nonce,
+                    old_nonce: account.nonce,
+                    new_nonce: account.nonce + 1,
+                },
+            ),
+        ]
+    );
+
+    let account_after = tb.state.get_account(account_id).unwrap();
+    assert_eq!(account_after.durable_nonce, expected_new_durable_nonce);
+}
+
+/// Submitting against a stale `expected_durable_nonce` - one that no longer
+/// matches the account's current stored value - is rejected, and leaves the
+/// account's durable nonce (and balance) untouched.
+#[test]
+fn advance_nonce_rejects_stale_expected_value() {
+    let token = TokenId(0);
+    let mut tb = PlasmaTestBuilder::new();
+    let (account_id, account, sk) = tb.add_account(Unlocked);
+    tb.set_balance(account_id, token, BigUint::from(100u32));
+
+    let stale_value = H256::from_low_u64_be(0xdead_beef);
+    let tx = AdvanceNonce::new_signed(
+        account_id,
+        account.address,
+        stale_value,
+        token,
+        BigUint::from(1u32),
+        account.nonce,
+        TimeRange::default(),
+        &sk,
+    )
+    .expect("signing should succeed");
+
+    let error = <ZkDposState as TxHandler<AdvanceNonce>>::apply_tx(&mut tb.state, tx)
+        .expect_err("AdvanceNonce should fail");
+    assert_eq!(error.to_string(), "Durable nonce mismatch");
+
+    let account_after = tb.state.get_account(account_id).unwrap();
+    assert_eq!(account_after.durable_nonce, account.durable_nonce);
+    assert_eq!(account_after.get_balance(token), BigUint::from(100u32));
+}
@@ -0,0 +1,334 @@
+use crate::{
+    handler::TxHandler,
+    state::ZkDposState,
+    tests::{AccountState::*, PlasmaTestBuilder},
+};
+use num::{BigUint, Zero};
+use zkdpos_types::account::AccountUpdate;
+use zkdpos_types::priority_ops::{Conditional, ConditionalPredicate};
+use zkdpos_types::tx::{ConditionalSettle, TimeRange};
+use zkdpos_types::TokenId;
+
+/// The escrow-lock phase moves `amount + fee` out of `from` into `pending`,
+/// leaving `to` untouched until a later `ConditionalSettle` resolves, and
+/// records the lock commitment as its own `ChangePubKeyHash` update rather
+/// than a silent field mutation that `create_op` couldn't later re-derive
+/// from a reconstructed tree.
+#[test]
+fn lock_phase_moves_funds_into_escrow_and_records_commitment() {
+    let token = TokenId(0);
+    let mut tb = PlasmaTestBuilder::new();
+    let (from_id, from_account, _) = tb.add_account(Locked);
+    let (to_id, to_account, _) = tb.add_account(Locked);
+    let (pending_id, pending_account, _) = tb.add_account(Locked);
+    tb.set_balance(from_id, token, BigUint::from(1_000u32));
+
+    let priority_op = Conditional {
+        from: from_account.address,
+        to: to_account.address,
+        token,
+        amount: BigUint::from(100u32),
+        fee: BigUint::from(1u32),
+        predicate: ConditionalPredicate::After(0),
+    };
+
+    let (fee, updates, op) = tb
+        .state
+        .apply_conditional_op(priority_op, from_id, to_id, pending_id)
+        .expect("lock phase should succeed");
+
+    assert!(fee.is_none());
+    assert_eq!(updates.len(), 3);
+    assert_eq!(
+        updates[0],
+        (
+            from_id,
+            AccountUpdate::UpdateBalance {
+                old_nonce: from_account.nonce,
+                new_nonce: from_account.nonce,
+                balance_update: (token, BigUint::from(1_000u32), BigUint::from(899u32)),
+            },
+        )
+    );
+    assert_eq!(
+        updates[1],
+        (
+            pending_id,
+            AccountUpdate::UpdateBalance {
+                old_nonce: from_account.nonce,
+                new_nonce: from_account.nonce,
+                balance_update: (token, BigUint::zero(), BigUint::from(101u32)),
+            },
+        )
+    );
+    match &updates[2] {
+        (account_id, AccountUpdate::ChangePubKeyHash { old_pub_key_hash, new_pub_key_hash, .. }) => {
+            assert_eq!(*account_id, pending_id);
+            assert_eq!(*old_pub_key_hash, pending_account.pub_key_hash);
+            assert_ne!(*new_pub_key_hash, pending_account.pub_key_hash);
+        }
+        other => panic!("expected a ChangePubKeyHash update, got {:?}", other),
+    }
+
+    assert_eq!(op.from, from_id);
+    assert_eq!(op.to, to_id);
+    assert_eq!(op.pending, pending_id);
+}
+
+/// A `from` account without enough balance to cover `amount + fee` is
+/// rejected before anything is moved into escrow.
+#[test]
+fn lock_phase_rejects_insufficient_balance() {
+    let token = TokenId(0);
+    let mut tb = PlasmaTestBuilder::new();
+    let (from_id, from_account, _) = tb.add_account(Locked);
+    let (to_id, to_account, _) = tb.add_account(Locked);
+    let (pending_id, _pending_account, _) = tb.add_account(Locked);
+    tb.set_balance(from_id, token, BigUint::from(10u32));
+
+    let priority_op = Conditional {
+        from: from_account.address,
+        to: to_account.address,
+        token,
+        amount: BigUint::from(100u32),
+        fee: BigUint::from(1u32),
+        predicate: ConditionalPredicate::After(0),
+    };
+
+    let error = tb
+        .state
+        .apply_conditional_op(priority_op, from_id, to_id, pending_id)
+        .expect_err("lock phase should fail");
+    assert_eq!(error.to_string(), "Not enough balance");
+
+    let from_after = tb.state.get_account(from_id).unwrap();
+    assert_eq!(from_after.get_balance(token), BigUint::from(10u32));
+}
+
+/// Once `predicate` is satisfied, settlement releases the escrowed amount to
+/// `to` and collects `fee`.
+#[test]
+fn settle_releases_to_recipient_when_predicate_satisfied() {
+    let token = TokenId(0);
+    let mut tb = PlasmaTestBuilder::new();
+    let (account_id, account, _) = tb.add_account(Locked);
+    let (pending_id, _pending_account, _) = tb.add_account(Locked);
+    let (to_id, to_account, _) = tb.add_account(Locked);
+    tb.set_balance(account_id, token, BigUint::from(1_000u32));
+
+    let priority_op = Conditional {
+        from: account.address,
+        to: to_account.address,
+        token,
+        amount: BigUint::from(100u32),
+        fee: BigUint::from(1u32),
+        predicate: ConditionalPredicate::After(0),
+    };
+    tb.state
+        .apply_conditional_op(priority_op, account_id, to_id, pending_id)
+        .expect("lock phase should succeed");
+
+    let settle = ConditionalSettle::new(
+        account_id,
+        pending_id,
+        to_account.address,
+        account.address,
+        token,
+        BigUint::from(100u32),
+        BigUint::from(1u32),
+        ConditionalPredicate::After(0),
+        0,
+        None,
+        account.nonce,
+        TimeRange::default(),
+        None,
+    );
+
+    let success = <ZkDposState as TxHandler<ConditionalSettle>>::apply_tx(&mut tb.state, settle)
+        .expect("settlement should succeed");
+
+    assert_eq!(
+        success.updates,
+        vec![
+            (
+                pending_id,
+                AccountUpdate::UpdateBalance {
+                    old_nonce: account.nonce,
+                    new_nonce: account.nonce,
+                    balance_update: (token, BigUint::from(101u32), BigUint::zero()),
+                },
+            ),
+            (
+                to_id,
+                AccountUpdate::UpdateBalance {
+                    old_nonce: to_account.nonce,
+                    new_nonce: to_account.nonce,
+                    balance_update: (token, BigUint::zero(), BigUint::from(100u32)),
+                },
+            ),
+        ]
+    );
+}
+
+/// Once the predicate is still unmet but `deadline_block` has passed,
+/// settlement refunds `from` instead.
+#[test]
+fn settle_refunds_sender_once_deadline_passes() {
+    let token = TokenId(0);
+    let mut tb = PlasmaTestBuilder::new();
+    let (account_id, account, _) = tb.add_account(Locked);
+    let (pending_id, _pending_account, _) = tb.add_account(Locked);
+    let (to_id, to_account, _) = tb.add_account(Locked);
+    tb.set_balance(account_id, token, BigUint::from(1_000u32));
+
+    let priority_op = Conditional {
+        from: account.address,
+        to: to_account.address,
+        token,
+        amount: BigUint::from(100u32),
+        fee: BigUint::from(1u32),
+        predicate: ConditionalPredicate::After(1_000),
+    };
+    tb.state
+        .apply_conditional_op(priority_op, account_id, to_id, pending_id)
+        .expect("lock phase should succeed");
+    tb.state.current_block_number = 10;
+
+    let settle = ConditionalSettle::new(
+        account_id,
+        pending_id,
+        to_account.address,
+        account.address,
+        token,
+        BigUint::from(100u32),
+        BigUint::from(1u32),
+        ConditionalPredicate::After(1_000),
+        5,
+        None,
+        account.nonce,
+        TimeRange::default(),
+        None,
+    );
+
+    let success = <ZkDposState as TxHandler<ConditionalSettle>>::apply_tx(&mut tb.state, settle)
+        .expect("refund should succeed");
+
+    assert_eq!(
+        success.updates,
+        vec![
+            (
+                pending_id,
+                AccountUpdate::UpdateBalance {
+                    old_nonce: account.nonce,
+                    new_nonce: account.nonce,
+                    balance_update: (token, BigUint::from(101u32), BigUint::zero()),
+                },
+            ),
+            (
+                account_id,
+                AccountUpdate::UpdateBalance {
+                    old_nonce: account.nonce,
+                    new_nonce: account.nonce,
+                    balance_update: (token, BigUint::from(899u32), BigUint::from(999u32)),
+                },
+            ),
+        ]
+    );
+}
+
+/// While the predicate is unmet and `deadline_block` hasn't passed either,
+/// the settlement isn't resolvable yet and is rejected outright.
+#[test]
+fn settle_rejected_while_unresolved() {
+    let token = TokenId(0);
+    let mut tb = PlasmaTestBuilder::new();
+    let (account_id, account, _) = tb.add_account(Locked);
+    let (pending_id, _pending_account, _) = tb.add_account(Locked);
+    let (to_id, to_account, _) = tb.add_account(Locked);
+    tb.set_balance(account_id, token, BigUint::from(1_000u32));
+
+    let priority_op = Conditional {
+        from: account.address,
+        to: to_account.address,
+        token,
+        amount: BigUint::from(100u32),
+        fee: BigUint::from(1u32),
+        predicate: ConditionalPredicate::After(1_000),
+    };
+    tb.state
+        .apply_conditional_op(priority_op, account_id, to_id, pending_id)
+        .expect("lock phase should succeed");
+
+    let settle = ConditionalSettle::new(
+        account_id,
+        pending_id,
+        to_account.address,
+        account.address,
+        token,
+        BigUint::from(100u32),
+        BigUint::from(1u32),
+        ConditionalPredicate::After(1_000),
+        1_000,
+        None,
+        account.nonce,
+        TimeRange::default(),
+        None,
+    );
+
+    let error = <ZkDposState as TxHandler<ConditionalSettle>>::apply_tx(&mut tb.state, settle)
+        .expect_err("settlement should be rejected");
+    assert_eq!(error.to_string(), "Conditional settle predicate is not yet resolvable");
+
+    let pending_after = tb.state.get_account(pending_id).unwrap();
+    assert_eq!(pending_after.get_balance(token), BigUint::from(101u32));
+}
+
+/// A `ConditionalSettle` carrying terms that don't match what was actually
+/// locked - e.g. a `to` the submitter doesn't control - is rejected, even
+/// though the predicate itself would otherwise resolve to a release.
+#[test]
+fn settle_rejects_terms_mismatching_the_lock() {
+    let token = TokenId(0);
+    let mut tb = PlasmaTestBuilder::new();
+    let (account_id, account, _) = tb.add_account(Locked);
+    let (pending_id, _pending_account, _) = tb.add_account(Locked);
+    let (to_id, to_account, _) = tb.add_account(Locked);
+    let (_, attacker_account, _) = tb.add_account(Locked);
+    tb.set_balance(account_id, token, BigUint::from(1_000u32));
+
+    let priority_op = Conditional {
+        from: account.address,
+        to: to_account.address,
+        token,
+        amount: BigUint::from(100u32),
+        fee: BigUint::from(1u32),
+        predicate: ConditionalPredicate::After(1_000),
+    };
+    tb.state
+        .apply_conditional_op(priority_op, account_id, to_id, pending_id)
+        .expect("lock phase should succeed");
+
+    let settle = ConditionalSettle::new(
+        account_id,
+        pending_id,
+        // Different recipient than the one actually locked against.
+        attacker_account.address,
+        account.address,
+        token,
+        BigUint::from(100u32),
+        BigUint::from(1u32),
+        ConditionalPredicate::After(0),
+        0,
+        None,
+        account.nonce,
+        TimeRange::default(),
+        None,
+    );
+
+    let error = <ZkDposState as TxHandler<ConditionalSettle>>::apply_tx(&mut tb.state, settle)
+        .expect_err("settlement should be rejected");
+    assert_eq!(
+        error.to_string(),
+        "ConditionalSettle terms do not match the escrowed lock"
+    );
+}
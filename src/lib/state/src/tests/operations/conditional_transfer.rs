@@ -0,0 +1,155 @@
+use crate::{
+    handler::TxHandler,
+    state::ZkDposState,
+    tests::{AccountState::*, PlasmaTestBuilder},
+};
+use num::BigUint;
+use zkdpos_types::account::AccountUpdate;
+use zkdpos_types::tx::primitives::PredicateNode;
+use zkdpos_types::tx::{ConditionalTransfer, TimeRange};
+use zkdpos_types::TokenId;
+
+/// A `Timelock` predicate is satisfied the moment it's submitted (the
+/// default `TimeRange` always covers "now"), so funds move from sender to
+/// receiver in a single `apply_op` call, same as an ordinary transfer.
+#[test]
+fn timelock_predicate_releases_immediately() {
+    let token = TokenId(0);
+    let mut tb = PlasmaTestBuilder::new();
+    let (from_id, from_account, sk) = tb.add_account(Unlocked);
+    let (to_id, to_account, _) = tb.add_account(Locked);
+    tb.set_balance(from_id, token, BigUint::from(1_000u32));
+
+    let amount = BigUint::from(100u32);
+    let fee = BigUint::from(1u32);
+    let tx = ConditionalTransfer::new_signed(
+        from_id,
+        from_account.address,
+        to_account.address,
+        token,
+        amount.clone(),
+        fee.clone(),
+        PredicateNode::Timelock,
+        Vec::new(),
+        from_account.nonce,
+        None,
+        TimeRange::default(),
+        &sk,
+    )
+    .expect("signing should succeed");
+
+    let success = <ZkDposState as TxHandler<ConditionalTransfer>>::apply_tx(&mut tb.state, tx)
+        .expect("ConditionalTransfer should succeed");
+
+    assert_eq!(
+        success.updates,
+        vec![
+            (
+                from_id,
+                AccountUpdate::UpdateBalance {
+                    old_nonce: from_account.nonce,
+                    new_nonce: from_account.nonce + 1,
+                    balance_update: (
+                        token,
+                        BigUint::from(1_000u32),
+                        BigUint::from(1_000u32) - &amount - &fee,
+                    ),
+                },
+            ),
+            (
+                to_id,
+                AccountUpdate::UpdateBalance {
+                    old_nonce: to_account.nonce,
+                    new_nonce: to_account.nonce,
+                    balance_update: (token, BigUint::zero(), amount.clone()),
+                },
+            ),
+        ]
+    );
+}
+
+/// An `Approver` predicate with no matching approval is not yet satisfied:
+/// the funds leave the sender's balance into escrow, but the receiver is not
+/// credited until a later resubmission actually carries the approval.
+#[test]
+fn unsatisfied_approver_predicate_escrows_without_crediting() {
+    let token = TokenId(0);
+    let mut tb = PlasmaTestBuilder::new();
+    let (from_id, from_account, sk) = tb.add_account(Unlocked);
+    let (to_id, to_account, _) = tb.add_account(Locked);
+    let (_, approver_account, _) = tb.add_account(Unlocked);
+    tb.set_balance(from_id, token, BigUint::from(1_000u32));
+
+    let amount = BigUint::from(100u32);
+    let fee = BigUint::from(1u32);
+    let tx = ConditionalTransfer::new_signed(
+        from_id,
+        from_account.address,
+        to_account.address,
+        token,
+        amount.clone(),
+        fee.clone(),
+        PredicateNode::Approver(approver_account.pub_key_hash),
+        Vec::new(),
+        from_account.nonce,
+        None,
+        TimeRange::default(),
+        &sk,
+    )
+    .expect("signing should succeed");
+
+    let success = <ZkDposState as TxHandler<ConditionalTransfer>>::apply_tx(&mut tb.state, tx)
+        .expect("ConditionalTransfer should still debit the sender");
+
+    assert_eq!(
+        success.updates,
+        vec![(
+            from_id,
+            AccountUpdate::UpdateBalance {
+                old_nonce: from_account.nonce,
+                new_nonce: from_account.nonce + 1,
+                balance_update: (
+                    token,
+                    BigUint::from(1_000u32),
+                    BigUint::from(1_000u32) - &amount - &fee,
+                ),
+            },
+        )]
+    );
+    let to_after = tb.state.get_account(to_id).unwrap();
+    assert_eq!(to_after.get_balance(token), BigUint::zero());
+}
+
+/// A sender without enough balance to cover `amount + fee` is rejected
+/// outright, regardless of whether the predicate would have been satisfied.
+#[test]
+fn insufficient_balance_is_rejected() {
+    let token = TokenId(0);
+    let mut tb = PlasmaTestBuilder::new();
+    let (from_id, from_account, sk) = tb.add_account(Unlocked);
+    let (_, to_account, _) = tb.add_account(Locked);
+    tb.set_balance(from_id, token, BigUint::from(10u32));
+
+    let tx = ConditionalTransfer::new_signed(
+        from_id,
+        from_account.address,
+        to_account.address,
+        token,
+        BigUint::from(100u32),
+        BigUint::from(1u32),
+        PredicateNode::Timelock,
+        Vec::new(),
+        from_account.nonce,
+        None,
+        TimeRange::default(),
+        &sk,
+    )
+    .expect("signing should succeed");
+
+    let error = <ZkDposState as TxHandler<ConditionalTransfer>>::apply_tx(&mut tb.state, tx)
+        .expect_err("ConditionalTransfer should fail");
+    assert_eq!(error.to_string(), "Not enough balance");
+
+    let from_after = tb.state.get_account(from_id).unwrap();
+    assert_eq!(from_after.get_balance(token), BigUint::from(10u32));
+}
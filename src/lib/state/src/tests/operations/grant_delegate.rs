@@ -0,0 +1,156 @@
+use crate::{
+    handler::TxHandler,
+    state::ZkDposState,
+    tests::{AccountState::*, PlasmaTestBuilder},
+};
+use num::BigUint;
+use zkdpos_crypto::priv_key_from_fs;
+use zkdpos_crypto::rand::{thread_rng, Rng};
+use zkdpos_types::account::{AccountUpdate, PubKeyHash};
+use zkdpos_types::tx::{GrantDelegate, TimeRange};
+use zkdpos_types::TokenId;
+
+/// Granting a delegate debits the fee and, per the review fix, records the
+/// delegation as its own `ChangeDelegate` update rather than riding along
+/// silently on the balance update.
+#[test]
+fn grant_delegate_records_balance_and_delegate_updates() {
+    let token = TokenId(0);
+    let mut tb = PlasmaTestBuilder::new();
+    let (account_id, account, sk) = tb.add_account(Unlocked);
+    tb.set_balance(account_id, token, BigUint::from(100u32));
+
+    let delegate_key = priv_key_from_fs(thread_rng().gen());
+    let delegate = PubKeyHash::from_privkey(&delegate_key);
+
+    let fee = BigUint::from(1u32);
+    let tx = GrantDelegate::new_signed(
+        account_id,
+        delegate,
+        token,
+        fee.clone(),
+        account.nonce,
+        TimeRange::default(),
+        &sk,
+    )
+    .expect("signing should succeed");
+
+    let success = <ZkDposState as TxHandler<GrantDelegate>>::apply_tx(&mut tb.state, tx)
+        .expect("GrantDelegate should succeed");
+
+    assert_eq!(
+        success.updates,
+        vec![
+            (
+                account_id,
+                AccountUpdate::UpdateBalance {
+                    old_nonce: account.nonce,
+                    new_nonce: account.nonce + 1,
+                    balance_update: (
+                        token,
+                        BigUint::from(100u32),
+                        BigUint::from(100u32) - &fee,
+                    ),
+                },
+            ),
+            (
+                account_id,
+                AccountUpdate::ChangeDelegate {
+                    old_delegate: account.delegate,
+                    new_delegate: Some(delegate),
+                    old_nonce: account.nonce,
+                    new_nonce: account.nonce + 1,
+                },
+            ),
+        ]
+    );
+
+    let account_after = tb.state.get_account(account_id).unwrap();
+    assert_eq!(account_after.delegate, Some(delegate));
+}
+
+/// Submitting `GrantDelegate` with the default `PubKeyHash` revokes whatever
+/// delegate is currently installed, recording the transition back to `None`.
+#[test]
+fn grant_delegate_with_default_hash_revokes_existing_delegate() {
+    let token = TokenId(0);
+    let mut tb = PlasmaTestBuilder::new();
+    let (account_id, account, sk) = tb.add_account(Unlocked);
+    tb.set_balance(account_id, token, BigUint::from(100u32));
+
+    let delegate_key = priv_key_from_fs(thread_rng().gen());
+    let delegate = PubKeyHash::from_privkey(&delegate_key);
+    let grant = GrantDelegate::new_signed(
+        account_id,
+        delegate,
+        token,
+        BigUint::from(1u32),
+        account.nonce,
+        TimeRange::default(),
+        &sk,
+    )
+    .expect("signing should succeed");
+    <ZkDposState as TxHandler<GrantDelegate>>::apply_tx(&mut tb.state, grant)
+        .expect("initial grant should succeed");
+    let account = tb.state.get_account(account_id).unwrap();
+    assert_eq!(account.delegate, Some(delegate));
+
+    let revoke = GrantDelegate::new_signed(
+        account_id,
+        PubKeyHash::default(),
+        token,
+        BigUint::from(1u32),
+        account.nonce,
+        TimeRange::default(),
+        &sk,
+    )
+    .expect("signing should succeed");
+
+    let success = <ZkDposState as TxHandler<GrantDelegate>>::apply_tx(&mut tb.state, revoke)
+        .expect("GrantDelegate revoke should succeed");
+
+    assert_eq!(
+        success.updates[1],
+        (
+            account_id,
+            AccountUpdate::ChangeDelegate {
+                old_delegate: Some(delegate),
+                new_delegate: None,
+                old_nonce: account.nonce,
+                new_nonce: account.nonce + 1,
+            },
+        )
+    );
+
+    let account_after = tb.state.get_account(account_id).unwrap();
+    assert_eq!(account_after.delegate, None);
+}
+
+/// An account without enough balance to cover `fee` is rejected, leaving its
+/// delegate untouched.
+#[test]
+fn grant_delegate_rejects_insufficient_balance() {
+    let token = TokenId(0);
+    let mut tb = PlasmaTestBuilder::new();
+    let (account_id, account, sk) = tb.add_account(Unlocked);
+
+    let delegate_key = priv_key_from_fs(thread_rng().gen());
+    let delegate = PubKeyHash::from_privkey(&delegate_key);
+    let tx = GrantDelegate::new_signed(
+        account_id,
+        delegate,
+        token,
+        BigUint::from(1u32),
+        account.nonce,
+        TimeRange::default(),
+        &sk,
+    )
+    .expect("signing should succeed");
+
+    let error = <ZkDposState as TxHandler<GrantDelegate>>::apply_tx(&mut tb.state, tx)
+        .expect_err("GrantDelegate should fail");
+    assert_eq!(error.to_string(), "Not enough balance");
+
+    let account_after = tb.state.get_account(account_id).unwrap();
+    assert_eq!(account_after.delegate, None);
+}
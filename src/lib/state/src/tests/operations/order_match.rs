@@ -0,0 +1,250 @@
+use crate::{
+    handler::TxHandler,
+    state::ZkDposState,
+    tests::{AccountState::*, PlasmaTestBuilder},
+};
+use num::{BigUint, Zero};
+use zkdpos_types::account::AccountUpdate;
+use zkdpos_types::tx::{Order, OrderMatch, TimeRange, TxSignature};
+use zkdpos_types::TokenId;
+
+fn signed_order(
+    account_id: zkdpos_types::AccountId,
+    recipient: zkdpos_types::Address,
+    nonce: zkdpos_types::Nonce,
+    token_sell: TokenId,
+    token_buy: TokenId,
+    price_sell: u32,
+    price_buy: u32,
+    amount: u32,
+    sk: &zkdpos_crypto::PrivateKey<zkdpos_types::Engine>,
+) -> Order {
+    let mut order = Order {
+        account_id,
+        recipient,
+        nonce,
+        token_sell,
+        token_buy,
+        price_sell: BigUint::from(price_sell),
+        price_buy: BigUint::from(price_buy),
+        amount: BigUint::from(amount),
+        time_range: TimeRange::default(),
+        signature: TxSignature::default(),
+    };
+    order.signature = TxSignature::sign_musig(sk, &order.get_bytes());
+    order
+}
+
+/// Two orders at a 1:1 price, each filled for 500 of their respective sold
+/// token, settle into debits on both order owners and matching credits on
+/// both recipients (here, the owners themselves).
+#[test]
+fn order_match_settles_both_sides() {
+    let token_a = TokenId(0);
+    let token_b = TokenId(1);
+    let mut tb = PlasmaTestBuilder::new();
+    let (account_a_id, account_a, sk_a) = tb.add_account(Unlocked);
+    let (account_b_id, account_b, sk_b) = tb.add_account(Unlocked);
+    tb.set_balance(account_a_id, token_a, BigUint::from(1_000u32));
+    tb.set_balance(account_b_id, token_b, BigUint::from(1_000u32));
+
+    let order_a = signed_order(
+        account_a_id,
+        account_a.address,
+        account_a.nonce,
+        token_a,
+        token_b,
+        1,
+        1,
+        1_000,
+        &sk_a,
+    );
+    let order_b = signed_order(
+        account_b_id,
+        account_b.address,
+        account_b.nonce,
+        token_b,
+        token_a,
+        1,
+        1,
+        1_000,
+        &sk_b,
+    );
+
+    let fee = BigUint::from(2u32);
+    let order_match = OrderMatch::new_signed(
+        account_a_id,
+        order_a,
+        order_b,
+        BigUint::from(500u32),
+        fee.clone(),
+        account_a.nonce,
+        TimeRange::default(),
+        &sk_a,
+    )
+    .expect("signing should succeed");
+
+    let success = <ZkDposState as TxHandler<OrderMatch>>::apply_tx(&mut tb.state, order_match)
+        .expect("OrderMatch should succeed");
+
+    assert_eq!(
+        success.updates,
+        vec![
+            (
+                account_a_id,
+                AccountUpdate::UpdateBalance {
+                    old_nonce: account_a.nonce,
+                    new_nonce: account_a.nonce + 1,
+                    balance_update: (
+                        token_a,
+                        BigUint::from(1_000u32),
+                        BigUint::from(1_000u32) - BigUint::from(500u32) - &fee,
+                    ),
+                },
+            ),
+            (
+                account_b_id,
+                AccountUpdate::UpdateBalance {
+                    old_nonce: account_b.nonce,
+                    new_nonce: account_b.nonce + 1,
+                    balance_update: (
+                        token_b,
+                        BigUint::from(1_000u32),
+                        BigUint::from(500u32),
+                    ),
+                },
+            ),
+            (
+                account_a_id,
+                AccountUpdate::UpdateBalance {
+                    old_nonce: account_a.nonce,
+                    new_nonce: account_a.nonce,
+                    balance_update: (token_b, BigUint::zero(), BigUint::from(500u32)),
+                },
+            ),
+            (
+                account_b_id,
+                AccountUpdate::UpdateBalance {
+                    old_nonce: account_b.nonce,
+                    new_nonce: account_b.nonce,
+                    balance_update: (token_a, BigUint::zero(), BigUint::from(500u32)),
+                },
+            ),
+        ]
+    );
+}
+
+/// `order_a`'s owner without enough `token_sell` balance to cover the filled
+/// amount plus fee is rejected before any balance moves.
+#[test]
+fn order_match_rejects_insufficient_balance() {
+    let token_a = TokenId(0);
+    let token_b = TokenId(1);
+    let mut tb = PlasmaTestBuilder::new();
+    let (account_a_id, account_a, sk_a) = tb.add_account(Unlocked);
+    let (account_b_id, account_b, sk_b) = tb.add_account(Unlocked);
+    tb.set_balance(account_a_id, token_a, BigUint::from(10u32));
+    tb.set_balance(account_b_id, token_b, BigUint::from(1_000u32));
+
+    let order_a = signed_order(
+        account_a_id,
+        account_a.address,
+        account_a.nonce,
+        token_a,
+        token_b,
+        1,
+        1,
+        1_000,
+        &sk_a,
+    );
+    let order_b = signed_order(
+        account_b_id,
+        account_b.address,
+        account_b.nonce,
+        token_b,
+        token_a,
+        1,
+        1,
+        1_000,
+        &sk_b,
+    );
+
+    let order_match = OrderMatch::new_signed(
+        account_a_id,
+        order_a,
+        order_b,
+        BigUint::from(500u32),
+        BigUint::from(2u32),
+        account_a.nonce,
+        TimeRange::default(),
+        &sk_a,
+    )
+    .expect("signing should succeed");
+
+    let error = <ZkDposState as TxHandler<OrderMatch>>::apply_tx(&mut tb.state, order_match)
+        .expect_err("OrderMatch should fail");
+    assert_eq!(error.to_string(), "Order a account does not have enough balance");
+
+    let account_a_after = tb.state.get_account(account_a_id).unwrap();
+    assert_eq!(account_a_after.get_balance(token_a), BigUint::from(10u32));
+    assert_eq!(account_a_after.nonce, account_a.nonce);
+}
+
+/// A fill whose implied `amount_b` (derived from `order_a`'s price) would
+/// exceed `order_b`'s own signed `amount` is rejected - `order_b`'s owner
+/// only authorized up to that much of `token_sell`, regardless of how much
+/// balance `account_b` happens to hold.
+#[test]
+fn order_match_rejects_amount_b_exceeding_order_b_amount() {
+    let token_a = TokenId(0);
+    let token_b = TokenId(1);
+    let mut tb = PlasmaTestBuilder::new();
+    let (account_a_id, account_a, sk_a) = tb.add_account(Unlocked);
+    let (account_b_id, account_b, sk_b) = tb.add_account(Unlocked);
+    tb.set_balance(account_a_id, token_a, BigUint::from(1_000u32));
+    tb.set_balance(account_b_id, token_b, BigUint::from(1_000u32));
+
+    let order_a = signed_order(
+        account_a_id,
+        account_a.address,
+        account_a.nonce,
+        token_a,
+        token_b,
+        1,
+        1,
+        1_000,
+        &sk_a,
+    );
+    // `order_b`'s owner only authorized up to 100 of token_b.
+    let order_b = signed_order(
+        account_b_id,
+        account_b.address,
+        account_b.nonce,
+        token_b,
+        token_a,
+        1,
+        1,
+        100,
+        &sk_b,
+    );
+
+    let order_match = OrderMatch::new_signed(
+        account_a_id,
+        order_a,
+        order_b,
+        BigUint::from(500u32),
+        BigUint::from(2u32),
+        account_a.nonce,
+        TimeRange::default(),
+        &sk_a,
+    )
+    .expect("signing should succeed");
+
+    let error = <ZkDposState as TxHandler<OrderMatch>>::apply_tx(&mut tb.state, order_match)
+        .expect_err("OrderMatch should fail");
+    assert_eq!(error.to_string(), "Order match amount exceeds order_b's own amount");
+
+    let account_b_after = tb.state.get_account(account_b_id).unwrap();
+    assert_eq!(account_b_after.get_balance(token_b), BigUint::from(1_000u32));
+    assert_eq!(account_b_after.nonce, account_b.nonce);
+}
@@ -0,0 +1,254 @@
+use crate::{
+    handler::TxHandler,
+    state::ZkDposState,
+    tests::{AccountState::*, PlasmaTestBuilder},
+};
+use num::{BigUint, Zero};
+use zkdpos_types::account::{AccountUpdate, PubKeyHash};
+use zkdpos_types::dlc::RangeSettleCurve;
+use zkdpos_types::priority_ops::RangeSettle;
+use zkdpos_types::tx::{RangeSettleComplete, TimeRange};
+use zkdpos_types::TokenId;
+
+fn curve(refund_payout_a_bp: u16) -> RangeSettleCurve {
+    RangeSettleCurve {
+        base: 10,
+        num_digits: 1,
+        conditions: Vec::new(),
+        refund_payout_a_bp,
+    }
+}
+
+/// The lock phase escrows `amount + fee` into `pending` and, per the review
+/// fix, records the lock commitment as its own `ChangePubKeyHash` update
+/// rather than a silent field mutation that `create_op` couldn't later
+/// re-derive from a reconstructed tree.
+#[test]
+fn lock_phase_escrows_funds_and_records_commitment() {
+    let token = TokenId(0);
+    let mut tb = PlasmaTestBuilder::new();
+    let (from_id, from_account, _) = tb.add_account(Locked);
+    let (to_a_id, to_a_account, _) = tb.add_account(Locked);
+    let (to_b_id, to_b_account, _) = tb.add_account(Locked);
+    let (pending_id, pending_account, _) = tb.add_account(Locked);
+    tb.set_balance(from_id, token, BigUint::from(1_000u32));
+
+    let priority_op = RangeSettle {
+        from: from_account.address,
+        to_a: to_a_account.address,
+        to_b: to_b_account.address,
+        token,
+        amount: BigUint::from(100u32),
+        fee: BigUint::from(1u32),
+        oracle_pubkey_hash: PubKeyHash::default(),
+        curve: curve(6_000),
+    };
+
+    let (fee, updates, op) = tb
+        .state
+        .apply_range_settle_op(priority_op, from_id, to_a_id, to_b_id, pending_id)
+        .expect("lock phase should succeed");
+
+    assert!(fee.is_none());
+    assert_eq!(updates.len(), 3);
+    assert_eq!(
+        updates[0],
+        (
+            from_id,
+            AccountUpdate::UpdateBalance {
+                old_nonce: from_account.nonce,
+                new_nonce: from_account.nonce,
+                balance_update: (token, BigUint::from(1_000u32), BigUint::from(899u32)),
+            },
+        )
+    );
+    assert_eq!(
+        updates[1],
+        (
+            pending_id,
+            AccountUpdate::UpdateBalance {
+                old_nonce: pending_account.nonce,
+                new_nonce: pending_account.nonce,
+                balance_update: (token, BigUint::zero(), BigUint::from(101u32)),
+            },
+        )
+    );
+    match &updates[2] {
+        (account_id, AccountUpdate::ChangePubKeyHash { old_pub_key_hash, new_pub_key_hash, .. }) => {
+            assert_eq!(*account_id, pending_id);
+            assert_eq!(*old_pub_key_hash, pending_account.pub_key_hash);
+            assert_ne!(*new_pub_key_hash, pending_account.pub_key_hash);
+        }
+        other => panic!("expected a ChangePubKeyHash update, got {:?}", other),
+    }
+
+    let pending_after = tb.state.get_account(pending_id).unwrap();
+    assert_eq!(op.pending, pending_id);
+    assert_ne!(pending_after.pub_key_hash, pending_account.pub_key_hash);
+}
+
+/// A `from` account without enough balance to cover `amount + fee` is
+/// rejected before anything is escrowed or committed.
+#[test]
+fn lock_phase_rejects_insufficient_balance() {
+    let token = TokenId(0);
+    let mut tb = PlasmaTestBuilder::new();
+    let (from_id, from_account, _) = tb.add_account(Locked);
+    let (to_a_id, to_a_account, _) = tb.add_account(Locked);
+    let (to_b_id, to_b_account, _) = tb.add_account(Locked);
+    let (pending_id, pending_account, _) = tb.add_account(Locked);
+    tb.set_balance(from_id, token, BigUint::from(10u32));
+
+    let priority_op = RangeSettle {
+        from: from_account.address,
+        to_a: to_a_account.address,
+        to_b: to_b_account.address,
+        token,
+        amount: BigUint::from(100u32),
+        fee: BigUint::from(1u32),
+        oracle_pubkey_hash: PubKeyHash::default(),
+        curve: curve(6_000),
+    };
+
+    let error = tb
+        .state
+        .apply_range_settle_op(priority_op, from_id, to_a_id, to_b_id, pending_id)
+        .expect_err("lock phase should fail");
+    assert_eq!(error.to_string(), "Not enough balance");
+
+    let pending_after = tb.state.get_account(pending_id).unwrap();
+    assert_eq!(pending_after.pub_key_hash, pending_account.pub_key_hash);
+}
+
+/// Once the deadline passes with no oracle attestation, settlement falls
+/// back to `curve.refund_payout_a_bp` and splits the escrowed pot between
+/// `to_a`/`to_b` accordingly - the re-derived commitment from the lock phase
+/// must match, or the settlement couldn't even reach this point.
+#[test]
+fn settle_splits_payout_after_deadline_with_no_attestation() {
+    let token = TokenId(0);
+    let mut tb = PlasmaTestBuilder::new();
+    let (from_id, from_account, _) = tb.add_account(Locked);
+    let (to_a_id, to_a_account, _) = tb.add_account(Locked);
+    let (to_b_id, to_b_account, _) = tb.add_account(Locked);
+    let (pending_id, _pending_account, _) = tb.add_account(Locked);
+    tb.set_balance(from_id, token, BigUint::from(1_000u32));
+
+    let locked_curve = curve(6_000);
+    let priority_op = RangeSettle {
+        from: from_account.address,
+        to_a: to_a_account.address,
+        to_b: to_b_account.address,
+        token,
+        amount: BigUint::from(100u32),
+        fee: BigUint::from(1u32),
+        oracle_pubkey_hash: PubKeyHash::default(),
+        curve: locked_curve.clone(),
+    };
+    tb.state
+        .apply_range_settle_op(priority_op, from_id, to_a_id, to_b_id, pending_id)
+        .expect("lock phase should succeed");
+
+    let settle = RangeSettleComplete::new(
+        from_id,
+        pending_id,
+        to_a_account.address,
+        to_b_account.address,
+        token,
+        BigUint::from(100u32),
+        BigUint::from(1u32),
+        PubKeyHash::default(),
+        locked_curve,
+        None,
+        0,
+        from_account.nonce,
+        TimeRange::default(),
+        None,
+    );
+
+    let success = <ZkDposState as TxHandler<RangeSettleComplete>>::apply_tx(&mut tb.state, settle)
+        .expect("settlement should succeed");
+
+    assert_eq!(
+        success.updates,
+        vec![
+            (
+                pending_id,
+                AccountUpdate::UpdateBalance {
+                    old_nonce: from_account.nonce,
+                    new_nonce: from_account.nonce,
+                    balance_update: (token, BigUint::from(101u32), BigUint::zero()),
+                },
+            ),
+            (
+                to_a_id,
+                AccountUpdate::UpdateBalance {
+                    old_nonce: to_a_account.nonce,
+                    new_nonce: to_a_account.nonce,
+                    balance_update: (token, BigUint::zero(), BigUint::from(60u32)),
+                },
+            ),
+            (
+                to_b_id,
+                AccountUpdate::UpdateBalance {
+                    old_nonce: to_b_account.nonce,
+                    new_nonce: to_b_account.nonce,
+                    balance_update: (token, BigUint::zero(), BigUint::from(40u32)),
+                },
+            ),
+        ]
+    );
+}
+
+/// A `RangeSettleComplete` carrying a curve that doesn't match what was
+/// actually locked is rejected - otherwise a submitter could steer the
+/// payout split away from what the funder agreed to.
+#[test]
+fn settle_rejects_curve_mismatching_the_lock() {
+    let token = TokenId(0);
+    let mut tb = PlasmaTestBuilder::new();
+    let (from_id, from_account, _) = tb.add_account(Locked);
+    let (to_a_id, to_a_account, _) = tb.add_account(Locked);
+    let (to_b_id, to_b_account, _) = tb.add_account(Locked);
+    let (pending_id, _pending_account, _) = tb.add_account(Locked);
+    tb.set_balance(from_id, token, BigUint::from(1_000u32));
+
+    let priority_op = RangeSettle {
+        from: from_account.address,
+        to_a: to_a_account.address,
+        to_b: to_b_account.address,
+        token,
+        amount: BigUint::from(100u32),
+        fee: BigUint::from(1u32),
+        oracle_pubkey_hash: PubKeyHash::default(),
+        curve: curve(6_000),
+    };
+    tb.state
+        .apply_range_settle_op(priority_op, from_id, to_a_id, to_b_id, pending_id)
+        .expect("lock phase should succeed");
+
+    let settle = RangeSettleComplete::new(
+        from_id,
+        pending_id,
+        to_a_account.address,
+        to_b_account.address,
+        token,
+        BigUint::from(100u32),
+        BigUint::from(1u32),
+        PubKeyHash::default(),
+        // Different split than the one actually locked against.
+        curve(4_000),
+        None,
+        0,
+        from_account.nonce,
+        TimeRange::default(),
+        None,
+    );
+
+    let error = <ZkDposState as TxHandler<RangeSettleComplete>>::apply_tx(&mut tb.state, settle)
+        .expect_err("settlement should be rejected");
+    assert_eq!(
+        error.to_string(),
+        "RangeSettleComplete curve/oracle does not match the escrowed lock"
+    );
+}
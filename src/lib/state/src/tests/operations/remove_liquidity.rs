@@ -0,0 +1,135 @@
+use crate::{
+    handler::TxHandler,
+    state::ZkDposState,
+    tests::{AccountState::*, PlasmaTestBuilder},
+};
+use num::BigUint;
+use zkdpos_types::account::AccountUpdate;
+use zkdpos_types::tx::{RemoveLiquidity, TimeRange};
+use zkdpos_types::{LiquidityId, Pool, TokenId};
+
+/// Burning shares against a seeded pool returns the proportional share of
+/// both reserves (less the per-token fee) and shrinks the pool accordingly.
+#[test]
+fn remove_liquidity_returns_proportional_reserves() {
+    let token_a = TokenId(0);
+    let token_b = TokenId(1);
+    let liquidity_id = LiquidityId(0);
+    let lp_token = Pool::lp_token_id(liquidity_id);
+    let mut tb = PlasmaTestBuilder::new();
+    let (account_id, account, sk) = tb.add_account(Unlocked);
+
+    let mut pool = Pool::empty(liquidity_id, token_a, token_b);
+    pool.reserve_a = BigUint::from(10_000u32);
+    pool.reserve_b = BigUint::from(20_000u32);
+    pool.total_shares = BigUint::from(1_000u32);
+    tb.state.insert_pool(liquidity_id, pool);
+    tb.set_balance(account_id, lp_token, BigUint::from(100u32));
+
+    let fee_a = BigUint::from(5u32);
+    let remove_liquidity = RemoveLiquidity::new_signed(
+        account_id,
+        liquidity_id,
+        account.address,
+        BigUint::from(100u32),
+        BigUint::zero(),
+        BigUint::zero(),
+        token_a,
+        token_b,
+        fee_a.clone(),
+        BigUint::zero(),
+        account.nonce,
+        TimeRange::default(),
+        &sk,
+    )
+    .expect("signing should succeed");
+
+    let success =
+        <ZkDposState as TxHandler<RemoveLiquidity>>::apply_tx(&mut tb.state, remove_liquidity)
+            .expect("RemoveLiquidity should succeed");
+
+    // 100/1000 of the pool: 1000 of A, 2000 of B.
+    assert_eq!(
+        success.updates,
+        vec![
+            (
+                account_id,
+                AccountUpdate::UpdateBalance {
+                    old_nonce: account.nonce,
+                    new_nonce: account.nonce + 1,
+                    balance_update: (lp_token, BigUint::from(100u32), BigUint::zero()),
+                },
+            ),
+            (
+                account_id,
+                AccountUpdate::UpdateBalance {
+                    old_nonce: account.nonce + 1,
+                    new_nonce: account.nonce + 1,
+                    balance_update: (
+                        token_a,
+                        BigUint::zero(),
+                        BigUint::from(1_000u32) - &fee_a,
+                    ),
+                },
+            ),
+            (
+                account_id,
+                AccountUpdate::UpdateBalance {
+                    old_nonce: account.nonce + 1,
+                    new_nonce: account.nonce + 1,
+                    balance_update: (token_b, BigUint::zero(), BigUint::from(2_000u32)),
+                },
+            ),
+        ]
+    );
+
+    let pool_after = tb.state.get_pool(liquidity_id).unwrap();
+    assert_eq!(pool_after.reserve_a, BigUint::from(9_000u32));
+    assert_eq!(pool_after.reserve_b, BigUint::from(18_000u32));
+    assert_eq!(pool_after.total_shares, BigUint::from(900u32));
+}
+
+/// Burning more shares than the sender holds is rejected and leaves the
+/// pool's reserves untouched.
+#[test]
+fn remove_liquidity_insufficient_shares() {
+    let token_a = TokenId(0);
+    let token_b = TokenId(1);
+    let liquidity_id = LiquidityId(0);
+    let lp_token = Pool::lp_token_id(liquidity_id);
+    let mut tb = PlasmaTestBuilder::new();
+    let (account_id, account, sk) = tb.add_account(Unlocked);
+
+    let mut pool = Pool::empty(liquidity_id, token_a, token_b);
+    pool.reserve_a = BigUint::from(10_000u32);
+    pool.reserve_b = BigUint::from(20_000u32);
+    pool.total_shares = BigUint::from(1_000u32);
+    tb.state.insert_pool(liquidity_id, pool);
+    tb.set_balance(account_id, lp_token, BigUint::from(10u32));
+
+    let remove_liquidity = RemoveLiquidity::new_signed(
+        account_id,
+        liquidity_id,
+        account.address,
+        BigUint::from(100u32),
+        BigUint::zero(),
+        BigUint::zero(),
+        token_a,
+        token_b,
+        BigUint::from(5u32),
+        BigUint::zero(),
+        account.nonce,
+        TimeRange::default(),
+        &sk,
+    )
+    .expect("signing should succeed");
+
+    let error =
+        <ZkDposState as TxHandler<RemoveLiquidity>>::apply_tx(&mut tb.state, remove_liquidity)
+            .expect_err("RemoveLiquidity should fail");
+    assert_eq!(error.to_string(), "Not enough LP shares");
+
+    let pool_after = tb.state.get_pool(liquidity_id).unwrap();
+    assert_eq!(pool_after.reserve_a, BigUint::from(10_000u32));
+    assert_eq!(pool_after.total_shares, BigUint::from(1_000u32));
+}
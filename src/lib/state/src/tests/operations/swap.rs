@@ -0,0 +1,175 @@
+use crate::{
+    handler::TxHandler,
+    state::ZkDposState,
+    tests::{AccountState::*, PlasmaTestBuilder},
+};
+use num::{BigUint, Zero};
+use zkdpos_types::account::AccountUpdate;
+use zkdpos_types::tx::{Swap, TimeRange};
+use zkdpos_types::{LiquidityId, Pool, TokenId};
+
+/// A swap against a seeded pool debits `amount_in + fee` of `token_in` and
+/// credits exactly `Pool::swap_output`'s result of `token_out`, updating the
+/// pool's reserves by the same two amounts.
+#[test]
+fn swap_moves_reserves_and_credits_output() {
+    let token_in = TokenId(0);
+    let token_out = TokenId(1);
+    let liquidity_id = LiquidityId(0);
+    let mut tb = PlasmaTestBuilder::new();
+    let (account_id, _account, sk) = tb.add_account(Unlocked);
+    let account = tb.state.get_account(account_id).unwrap();
+
+    let mut pool = Pool::empty(liquidity_id, token_in, token_out);
+    pool.reserve_a = BigUint::from(1_000_000u32);
+    pool.reserve_b = BigUint::from(1_000_000u32);
+    pool.total_shares = BigUint::from(1_000_000u32);
+    tb.state.insert_pool(liquidity_id, pool);
+    tb.set_balance(account_id, token_in, BigUint::from(10_000u32));
+
+    let amount_in = BigUint::from(1_000u32);
+    let fee = BigUint::from(1u32);
+    let amount_out = Pool::swap_output(
+        &BigUint::from(1_000_000u32),
+        &BigUint::from(1_000_000u32),
+        &amount_in,
+    );
+
+    let swap = Swap::new_signed(
+        account_id,
+        liquidity_id,
+        token_in,
+        token_out,
+        amount_in.clone(),
+        BigUint::from(1u32),
+        fee.clone(),
+        account.nonce,
+        TimeRange::default(),
+        &sk,
+    )
+    .expect("signing should succeed");
+
+    let success = <ZkDposState as TxHandler<Swap>>::apply_tx(&mut tb.state, swap)
+        .expect("Swap should succeed");
+
+    assert_eq!(
+        success.updates,
+        vec![
+            (
+                account_id,
+                AccountUpdate::UpdateBalance {
+                    old_nonce: account.nonce,
+                    new_nonce: account.nonce + 1,
+                    balance_update: (
+                        token_in,
+                        BigUint::from(10_000u32),
+                        BigUint::from(10_000u32) - &amount_in - &fee,
+                    ),
+                },
+            ),
+            (
+                account_id,
+                AccountUpdate::UpdateBalance {
+                    old_nonce: account.nonce + 1,
+                    new_nonce: account.nonce + 1,
+                    balance_update: (token_out, BigUint::zero(), amount_out.clone()),
+                },
+            ),
+        ]
+    );
+
+    let pool_after = tb.state.get_pool(liquidity_id).unwrap();
+    assert_eq!(pool_after.reserve_a, BigUint::from(1_000_000u32) + &amount_in);
+    assert_eq!(pool_after.reserve_b, BigUint::from(1_000_000u32) - &amount_out);
+}
+
+/// A swap whose output would fall below `amount_out_min` is rejected and the
+/// pool's reserves are left untouched.
+#[test]
+fn swap_rejects_output_below_minimum() {
+    let token_in = TokenId(0);
+    let token_out = TokenId(1);
+    let liquidity_id = LiquidityId(0);
+    let mut tb = PlasmaTestBuilder::new();
+    let (account_id, _account, sk) = tb.add_account(Unlocked);
+    let account = tb.state.get_account(account_id).unwrap();
+
+    let mut pool = Pool::empty(liquidity_id, token_in, token_out);
+    pool.reserve_a = BigUint::from(1_000_000u32);
+    pool.reserve_b = BigUint::from(1_000_000u32);
+    pool.total_shares = BigUint::from(1_000_000u32);
+    tb.state.insert_pool(liquidity_id, pool);
+    tb.set_balance(account_id, token_in, BigUint::from(10_000u32));
+
+    let swap = Swap::new_signed(
+        account_id,
+        liquidity_id,
+        token_in,
+        token_out,
+        BigUint::from(1_000u32),
+        // Unreachable minimum output for this trade size.
+        BigUint::from(1_000_000u32),
+        BigUint::from(1u32),
+        account.nonce,
+        TimeRange::default(),
+        &sk,
+    )
+    .expect("signing should succeed");
+
+    let error = <ZkDposState as TxHandler<Swap>>::apply_tx(&mut tb.state, swap)
+        .expect_err("Swap should fail");
+    assert_eq!(error.to_string(), "Swap output is below the minimum accepted");
+
+    let pool_after = tb.state.get_pool(liquidity_id).unwrap();
+    assert_eq!(pool_after.reserve_a, BigUint::from(1_000_000u32));
+    assert_eq!(pool_after.reserve_b, BigUint::from(1_000_000u32));
+}
+
+/// A swap naming a `token_out` other than the pool's actual opposite token
+/// is rejected - otherwise a signer could mint an arbitrary token id from
+/// nothing while only the real pool reserves are debited.
+#[test]
+fn swap_rejects_token_out_not_matching_pool() {
+    let token_in = TokenId(0);
+    let token_out = TokenId(1);
+    let unrelated_token = TokenId(2);
+    let liquidity_id = LiquidityId(0);
+    let mut tb = PlasmaTestBuilder::new();
+    let (account_id, _account, sk) = tb.add_account(Unlocked);
+    let account = tb.state.get_account(account_id).unwrap();
+
+    let mut pool = Pool::empty(liquidity_id, token_in, token_out);
+    pool.reserve_a = BigUint::from(1_000_000u32);
+    pool.reserve_b = BigUint::from(1_000_000u32);
+    pool.total_shares = BigUint::from(1_000_000u32);
+    tb.state.insert_pool(liquidity_id, pool);
+    tb.set_balance(account_id, token_in, BigUint::from(10_000u32));
+
+    let swap = Swap::new_signed(
+        account_id,
+        liquidity_id,
+        token_in,
+        unrelated_token,
+        BigUint::from(1_000u32),
+        BigUint::zero(),
+        BigUint::from(1u32),
+        account.nonce,
+        TimeRange::default(),
+        &sk,
+    )
+    .expect("signing should succeed");
+
+    let error = <ZkDposState as TxHandler<Swap>>::apply_tx(&mut tb.state, swap)
+        .expect_err("Swap should fail");
+    assert_eq!(
+        error.to_string(),
+        "Swap token_out does not match the pool's opposite token"
+    );
+
+    let pool_after = tb.state.get_pool(liquidity_id).unwrap();
+    assert_eq!(pool_after.reserve_a, BigUint::from(1_000_000u32));
+    assert_eq!(pool_after.reserve_b, BigUint::from(1_000_000u32));
+
+    let account_after = tb.state.get_account(account_id).unwrap();
+    assert_eq!(account_after.get_balance(unrelated_token), BigUint::zero());
+}
@@ -0,0 +1,74 @@
+use crate::{
+    handler::TxHandler,
+    state::ZkDposState,
+    tests::{AccountState::*, PlasmaTestBuilder},
+};
+use num::BigUint;
+use zkdpos_types::tx::{TimeRange, Withdraw};
+use zkdpos_types::TokenId;
+
+/// A withdrawal debits `amount + fee` from the account and collects `fee`;
+/// the withdrawn amount itself isn't credited anywhere on L2.
+#[test]
+fn withdraw_debits_balance_and_collects_fee() {
+    let token = TokenId(0);
+    let mut tb = PlasmaTestBuilder::new();
+    let (account_id, account, sk) = tb.add_account(Unlocked);
+    tb.set_balance(account_id, token, BigUint::from(100u32));
+
+    let fee = BigUint::from(1u32);
+    let tx = Withdraw::new_signed(
+        account_id,
+        account.address,
+        account.address,
+        token,
+        BigUint::from(40u32),
+        fee.clone(),
+        account.nonce,
+        TimeRange::default(),
+        &sk,
+        0,
+    )
+    .expect("signing should succeed");
+
+    let success = <ZkDposState as TxHandler<Withdraw>>::apply_tx(&mut tb.state, tx)
+        .expect("Withdraw should succeed");
+    let collected_fee = success.fee.expect("withdraw should collect a fee");
+    assert_eq!(collected_fee.token, token);
+    assert_eq!(collected_fee.amount, fee);
+
+    let account_after = tb.state.get_account(account_id).unwrap();
+    assert_eq!(account_after.get_balance(token), BigUint::from(59u32));
+}
+
+/// A `Withdraw` signed for a different `chain_id` than this deployment's is
+/// rejected - otherwise a withdrawal signed for one chain could be replayed
+/// unchanged against another sharing the same account keys.
+#[test]
+fn withdraw_rejects_mismatching_chain_id() {
+    let token = TokenId(0);
+    let mut tb = PlasmaTestBuilder::new();
+    let (account_id, account, sk) = tb.add_account(Unlocked);
+    tb.set_balance(account_id, token, BigUint::from(100u32));
+
+    let tx = Withdraw::new_signed(
+        account_id,
+        account.address,
+        account.address,
+        token,
+        BigUint::from(40u32),
+        BigUint::from(1u32),
+        account.nonce,
+        TimeRange::default(),
+        &sk,
+        tb.state.chain_id.wrapping_add(1),
+    )
+    .expect("signing should succeed");
+
+    let error = <ZkDposState as TxHandler<Withdraw>>::apply_tx(&mut tb.state, tx)
+        .expect_err("Withdraw should fail");
+    assert_eq!(error.to_string(), "Withdraw chain id does not match this deployment");
+
+    let account_after = tb.state.get_account(account_id).unwrap();
+    assert_eq!(account_after.get_balance(token), BigUint::from(100u32));
+}
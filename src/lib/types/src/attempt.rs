@@ -0,0 +1,173 @@
+//! Tracking of priority operations and transactions that were seen but did not
+//! (yet, or ever) make it into a confirmed block.
+//!
+//! Before this module, a priority op that failed to parse in `TryFrom<Log>` or
+//! a transaction that failed `create_op`/`apply_op` only left behind a log line
+//! carrying an `anyhow::Error`'s `Display` output. That is enough to debug a
+//! single failure by hand, but gives an operator no way to group repeated
+//! failures by slot and reason, the way a banking-stage error sidecar would.
+//! [`OperationErrorCode`] replaces the free-form string with a stable,
+//! matchable reason, and [`PriorityOpRecord`]/[`TxAttemptRecord`] capture enough
+//! context (accounts touched, amount/fee requested, where it was first seen)
+//! to answer "why is this deposit/exchange stuck?" without re-deriving it from
+//! logs.
+
+use std::collections::HashMap;
+
+use num::BigUint;
+use serde::{Deserialize, Serialize};
+use zkdpos_basic_types::{AccountId, H256};
+use zkdpos_utils::BigUintSerdeAsRadix10Str;
+
+use crate::tx::TxHash;
+use crate::{SerialId, TokenId};
+
+/// A stable, matchable reason a priority op or transaction failed to execute.
+///
+/// Mirrors the handful of `ensure!`/`bail!` messages already used across
+/// `state::handler` and `priority_ops::parse_from_priority_queue_logs`
+/// (`"Nonce mismatch"`, `"Not enough balance"`, `"PubData length mismatch"`,
+/// ...); this is the enumerated counterpart operators can group and alert on
+/// instead of matching against free-form strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum OperationErrorCode {
+    /// The transaction's `nonce` does not match the account's current nonce.
+    NonceMismatch,
+    /// The paying account does not hold enough of the requested token.
+    InsufficientBalance,
+    /// The account is locked (its `pub_key_hash` is still the default, so no
+    /// signing key is set) and cannot authorize the operation.
+    LockedAccount,
+    /// The op code or tx type is not one this node knows how to execute.
+    UnsupportedOperation,
+    /// The L1 log's pubdata was the wrong length for its declared op type.
+    PubdataLengthMismatch,
+    /// A zkDpos or witness signature failed to verify.
+    InvalidSignature,
+    /// An account id/address referenced by the operation does not exist.
+    AccountNotFound,
+    /// Any other failure; `create_op`/`apply_op` still return the original
+    /// `anyhow::Error` to the caller, this variant just means it didn't match
+    /// one of the more specific codes above.
+    Other,
+}
+
+impl std::fmt::Display for OperationErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NonceMismatch => write!(f, "nonce mismatch"),
+            Self::InsufficientBalance => write!(f, "insufficient balance"),
+            Self::LockedAccount => write!(f, "account is locked"),
+            Self::UnsupportedOperation => write!(f, "unsupported operation"),
+            Self::PubdataLengthMismatch => write!(f, "pubdata length mismatch"),
+            Self::InvalidSignature => write!(f, "invalid signature"),
+            Self::AccountNotFound => write!(f, "account not found"),
+            Self::Other => write!(f, "other"),
+        }
+    }
+}
+
+/// How far an operation has progressed (executed, confirmed, rejected), shared by
+/// [`PriorityOpRecord`] and [`TxAttemptRecord`] so both can be grouped and
+/// persisted the same way.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttemptOutcome {
+    /// `true` once the operation has been included into a block as an
+    /// executed operation (successfully or not - see `error`).
+    pub executed: bool,
+    /// `true` once the block it was included into has been verified/confirmed
+    /// on L1. Always `false` while `executed` is `false`.
+    pub confirmed: bool,
+    /// The reason the operation was rejected, if it was. `None` while the
+    /// operation is still pending or once it has executed successfully.
+    pub error: Option<OperationErrorCode>,
+}
+
+/// Tracks a single L1 priority operation from the moment it is first parsed
+/// out of the priority queue logs through execution and confirmation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PriorityOpRecord {
+    /// The priority queue serial id, unique per priority op.
+    pub serial_id: SerialId,
+    /// Alaya block the op was first observed in.
+    pub atp_block: u64,
+    /// Hash of the Alaya block the op was first observed in.
+    pub atp_hash: H256,
+    /// Accounts touched by the op, `ZkDposOp::get_updated_account_ids`-style.
+    pub accounts: Vec<AccountId>,
+    pub token: TokenId,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub amount: BigUint,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub fee: BigUint,
+    pub outcome: AttemptOutcome,
+}
+
+/// Tracks a single L2 transaction from the moment it is submitted to the
+/// mempool through execution and confirmation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxAttemptRecord {
+    pub tx_hash: TxHash,
+    /// Accounts touched by the tx, `ZkDposOp::get_updated_account_ids`-style.
+    pub accounts: Vec<AccountId>,
+    pub token: TokenId,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub amount: BigUint,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub fee: BigUint,
+    pub outcome: AttemptOutcome,
+}
+
+/// Identifies a (operation, block, error code) triple so repeated failures of
+/// the same kind, at the same slot, can be counted instead of logged once per
+/// occurrence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct AttemptErrorKey {
+    pub serial_id: SerialId,
+    pub atp_block: u64,
+    pub error: OperationErrorCode,
+}
+
+/// Counts how many times each `(op, block, error)` triple has been observed.
+///
+/// Kept separate from the records themselves: a single stuck deposit can be
+/// re-attempted many times within the same block window, and a flood of
+/// identical records would make the "why is this stalled" question harder to
+/// answer, not easier.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AttemptErrorCounts(HashMap<AttemptErrorKey, u32>);
+
+impl AttemptErrorCounts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records one more observation of `key`, returning the updated count.
+    pub fn record(&mut self, key: AttemptErrorKey) -> u32 {
+        let count = self.0.entry(key).or_insert(0);
+        *count += 1;
+        *count
+    }
+
+    /// Returns how many times `key` has been observed so far.
+    pub fn count(&self, key: &AttemptErrorKey) -> u32 {
+        self.0.get(key).copied().unwrap_or(0)
+    }
+}
+
+/// Persists batches of attempt records. Kept as a trait rather than a
+/// concrete implementation since this crate has no storage backend of its
+/// own; a storage crate implements it against whatever database it uses.
+pub trait AttemptRecordStorage {
+    /// Persists (inserts or updates) a batch of priority op records.
+    fn save_priority_op_records(
+        &mut self,
+        records: &[PriorityOpRecord],
+    ) -> Result<(), anyhow::Error>;
+
+    /// Persists (inserts or updates) a batch of transaction attempt records.
+    fn save_tx_attempt_records(
+        &mut self,
+        records: &[TxAttemptRecord],
+    ) -> Result<(), anyhow::Error>;
+}
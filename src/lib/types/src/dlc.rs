@@ -0,0 +1,420 @@
+//! Digit-prefix decomposition of an integer interval, used by oracle-attested
+//! conditional transactions (e.g. `Exchange::condition`) to encode an admissible
+//! outcome range compactly.
+//!
+//! An outcome is represented as a fixed-length, most-significant-digit-first
+//! array in some `base`. A *digit prefix* of length `k <= num_digits` stands for
+//! every outcome whose leading `k` digits equal it, i.e. a subtree of the
+//! base-ary digit trie. [`decompose_range`] covers `[start, end]` with a small
+//! set of such prefixes so a verifier (or, eventually, the circuit) only has to
+//! check that the attested outcome's digits match one of a handful of prefixes,
+//! instead of comparing against `start`/`end` directly.
+
+use anyhow::ensure;
+use parity_crypto::Keccak256;
+use serde::{Deserialize, Serialize};
+
+use crate::account::PubKeyHash;
+
+/// A digit prefix, most-significant digit first. An outcome (itself decomposed
+/// with [`to_digits`]) belongs to the group this prefix represents iff its
+/// leading `prefix.len()` digits equal `prefix`.
+pub type DigitPrefix = Vec<u64>;
+
+/// Decomposes `value` into `num_digits` digits in `base`, most-significant first.
+///
+/// # Panics
+///
+/// Panics if `value` doesn't fit in `num_digits` digits of the given `base`.
+pub fn to_digits(mut value: u64, base: u64, num_digits: usize) -> Vec<u64> {
+    let mut digits = vec![0u64; num_digits];
+    for i in (0..num_digits).rev() {
+        digits[i] = value % base;
+        value /= base;
+    }
+    assert_eq!(value, 0, "value does not fit in num_digits digits of base");
+    digits
+}
+
+/// `true` if `outcome`'s digits (in `base`, `num_digits` wide) start with `prefix`.
+pub fn outcome_matches_prefix(outcome: u64, base: u64, num_digits: usize, prefix: &[u64]) -> bool {
+    let digits = to_digits(outcome, base, num_digits);
+    digits.starts_with(prefix)
+}
+
+/// Covers every integer in the inclusive interval `[start, end]` with a set of
+/// disjoint digit prefixes over `num_digits`-digit, base-`base` numbers. The set
+/// is not always the smallest possible: sibling front/back groups that together
+/// fill their parent subtree are not merged into the parent's shorter prefix.
+///
+/// The decomposition is built out of three kinds of groups, split at `L`, the
+/// length of the common digit prefix of `start` and `end`:
+/// - a "front" group per digit position below `L`, pinned to `start`'s own
+///   digits above that position and covering every digit strictly greater than
+///   `start`'s at that position (plus the singleton `start` itself, which no
+///   front group includes since each requires a strictly greater digit);
+/// - a "back" group per digit position below `L`, symmetric, pinned to `end`
+///   and covering every digit strictly less than `end`'s (plus the singleton
+///   `end` itself);
+/// - a "middle" group for every digit value strictly between `start` and `end`'s
+///   digit at position `L`, covering that entire subtree.
+///
+/// Every integer in `[start, end]` matches exactly one returned prefix.
+pub fn decompose_range(
+    start: u64,
+    end: u64,
+    base: u64,
+    num_digits: usize,
+) -> anyhow::Result<Vec<DigitPrefix>> {
+    ensure!(base >= 2, "base must be at least 2");
+    ensure!(num_digits >= 1, "num_digits must be at least 1");
+    ensure!(start <= end, "start must not be greater than end");
+    ensure!(
+        base.checked_pow(num_digits as u32).map_or(true, |max| end < max),
+        "end does not fit in num_digits digits of base"
+    );
+
+    let start_digits = to_digits(start, base, num_digits);
+    let end_digits = to_digits(end, base, num_digits);
+
+    if start_digits == end_digits {
+        return Ok(vec![start_digits]);
+    }
+
+    let common_prefix_len = start_digits
+        .iter()
+        .zip(end_digits.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut groups = Vec::new();
+
+    // Front groups: pinned to `start` above position `a`, wildcard over digits
+    // strictly greater than `start`'s at position `a`, free below `a`.
+    for a in (common_prefix_len + 1..num_digits).rev() {
+        for digit in (start_digits[a] + 1)..base {
+            let mut prefix = start_digits[..a].to_vec();
+            prefix.push(digit);
+            groups.push(prefix);
+        }
+    }
+    groups.push(start_digits.clone());
+
+    // Back groups: symmetric, pinned to `end`, wildcard over digits strictly
+    // less than `end`'s at position `a`.
+    for a in (common_prefix_len + 1..num_digits).rev() {
+        for digit in 0..end_digits[a] {
+            let mut prefix = end_digits[..a].to_vec();
+            prefix.push(digit);
+            groups.push(prefix);
+        }
+    }
+    groups.push(end_digits.clone());
+
+    // Middle groups: the digit values strictly between `start` and `end` at the
+    // position where they first diverge cover their entire subtree outright.
+    for digit in (start_digits[common_prefix_len] + 1)..end_digits[common_prefix_len] {
+        let mut prefix = start_digits[..common_prefix_len].to_vec();
+        prefix.push(digit);
+        groups.push(prefix);
+    }
+
+    Ok(groups)
+}
+
+/// Base and digit width concentrated-liquidity price bands are decomposed in
+/// (see [`decompose_half_open_range`]). Fixed protocol-wide so the operator
+/// and the commitment circuit always agree on how a band's prefix set is
+/// derived from its `p_low`/`p_high` bounds without having to carry `base`/
+/// `num_digits` in every transaction. `PRICE_RANGE_BASE.pow(PRICE_RANGE_DIGITS)`
+/// is 2^64, one more than `u64::MAX`, so every representable price fits.
+pub const PRICE_RANGE_BASE: u64 = 16;
+pub const PRICE_RANGE_DIGITS: usize = 16;
+
+/// Covers the half-open interval `[start, end)` with a minimal set of
+/// power-of-`base`-aligned digit prefixes, by greedily peeling off the
+/// largest aligned block that both starts at the current position and still
+/// fits under `end`.
+///
+/// Unlike [`decompose_range`] (which covers an *inclusive* `[start, end]` by
+/// splitting into front/back/middle groups around the two endpoints), this
+/// walks forward from `start` one aligned block at a time: at each step the
+/// block size is the largest power of `base` that evenly divides the current
+/// position and doesn't overshoot `end`. This produces `O(num_digits)`
+/// prefixes per band, the same bound as [`decompose_range`], via a shape
+/// better suited to a single one-sided price band (`p_low` inclusive,
+/// `p_high` exclusive) than a two-sided outcome interval.
+pub fn decompose_half_open_range(
+    start: u64,
+    end: u64,
+    base: u64,
+    num_digits: usize,
+) -> anyhow::Result<Vec<DigitPrefix>> {
+    ensure!(base >= 2, "base must be at least 2");
+    ensure!(num_digits >= 1, "num_digits must be at least 1");
+    ensure!(start <= end, "start must not be greater than end");
+    ensure!(
+        base.checked_pow(num_digits as u32).map_or(true, |max| end <= max),
+        "end does not fit in num_digits digits of base"
+    );
+
+    let mut prefixes = Vec::new();
+    let mut cursor = start;
+    while cursor < end {
+        let remaining = end - cursor;
+        // The largest block size `base^level` that `cursor` is aligned to and
+        // that still fits within `remaining`, found by growing `level` until
+        // either bound would be violated.
+        let mut level = 0u32;
+        while level < num_digits as u32 {
+            let block_size = match base.checked_pow(level + 1) {
+                Some(block_size) => block_size,
+                // Larger than any `u64`, so it can't fit within `remaining` either.
+                None => break,
+            };
+            if cursor % block_size != 0 || block_size > remaining {
+                break;
+            }
+            level += 1;
+        }
+        let block_size = base.pow(level);
+        let prefix_len = num_digits - level as usize;
+        let digits = to_digits(cursor, base, num_digits);
+        prefixes.push(digits[..prefix_len].to_vec());
+        cursor += block_size;
+    }
+    Ok(prefixes)
+}
+
+/// One step of a settlement payout curve: every outcome whose digits start
+/// with `prefix` pays `payout_a_bp` basis points (out of 10000) of the pot to
+/// party A, with the remainder going to party B. See [`decompose_curve`] for
+/// how a curve's step function is turned into a set of these.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RangeSettleCondition {
+    pub prefix: DigitPrefix,
+    pub payout_a_bp: u16,
+}
+
+/// A step-function payout curve for an oracle-attested range settlement (see
+/// `RangeSettleOp`): maps an outcome `v` in `[0, base^num_digits)` to a split
+/// of the settled pot between two parties, encoded as a set of digit-prefix
+/// [`RangeSettleCondition`]s instead of one condition per possible outcome.
+/// `refund_payout_a_bp` is the split used if the attested outcome (or the
+/// lack of one, past the deadline) matches none of `conditions`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RangeSettleCurve {
+    pub base: u64,
+    pub num_digits: usize,
+    pub conditions: Vec<RangeSettleCondition>,
+    pub refund_payout_a_bp: u16,
+}
+
+impl RangeSettleCurve {
+    /// The `payout_a_bp` of the first condition whose prefix matches
+    /// `outcome`'s digits, or `None` if no condition matches (the caller
+    /// should then fall back to `refund_payout_a_bp`).
+    pub fn payout_bp_for_outcome(&self, outcome: u64) -> Option<u16> {
+        self.conditions
+            .iter()
+            .find(|condition| {
+                outcome_matches_prefix(outcome, self.base, self.num_digits, &condition.prefix)
+            })
+            .map(|condition| condition.payout_a_bp)
+    }
+
+    /// A deterministic commitment to this curve, the same way
+    /// [`super::tx::AccountSignerSet::commitment`] commits to a signer set:
+    /// stands in for a Merkle or polynomial commitment a production circuit
+    /// would use, hashed instead since this crate snapshot has no such
+    /// primitive on hand. Two curves with the same `base`/`num_digits`/
+    /// `refund_payout_a_bp`/conditions (in the same order) commit to the same
+    /// hash; anything else commits to a different one.
+    pub fn commitment(&self) -> PubKeyHash {
+        let mut preimage = Vec::new();
+        preimage.extend_from_slice(&self.base.to_be_bytes());
+        preimage.extend_from_slice(&(self.num_digits as u64).to_be_bytes());
+        preimage.extend_from_slice(&self.refund_payout_a_bp.to_be_bytes());
+        for condition in &self.conditions {
+            preimage.push(condition.prefix.len() as u8);
+            for digit in &condition.prefix {
+                preimage.extend_from_slice(&digit.to_be_bytes());
+            }
+            preimage.extend_from_slice(&condition.payout_a_bp.to_be_bytes());
+        }
+        let hash = preimage.keccak256();
+        PubKeyHash::from_bytes(&hash[hash.len() - 20..])
+            .expect("keccak256 output truncated to 20 bytes is always a valid PubKeyHash")
+    }
+}
+
+/// Builds a curve's [`RangeSettleCondition`]s from its step function, given as
+/// the contiguous outcome intervals sharing one payout: `(lo, hi, payout_a_bp)`
+/// with inclusive `lo..=hi`. Each interval is covered independently with
+/// [`decompose_range`], so it contributes that function's set of digit prefixes
+/// fully inside it; the caller is responsible for the intervals themselves
+/// being disjoint and jointly exhaustive over `[0, base^num_digits)`; that
+/// isn't re-derived here, just relied on.
+pub fn decompose_curve(
+    segments: &[(u64, u64, u16)],
+    base: u64,
+    num_digits: usize,
+) -> anyhow::Result<Vec<RangeSettleCondition>> {
+    let mut conditions = Vec::new();
+    for &(lo, hi, payout_a_bp) in segments {
+        for prefix in decompose_range(lo, hi, base, num_digits)? {
+            conditions.push(RangeSettleCondition { prefix, payout_a_bp });
+        }
+    }
+    Ok(conditions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every integer in `[start, end]` matches exactly one group, and no
+    /// integer outside the interval matches any group: the exhaustive,
+    /// small-scale check that the decomposition is both a complete and a
+    /// disjoint covering.
+    fn assert_exact_cover(start: u64, end: u64, base: u64, num_digits: usize) {
+        let groups = decompose_range(start, end, base, num_digits).unwrap();
+        let max = base.pow(num_digits as u32);
+        for value in 0..max {
+            let matches = groups
+                .iter()
+                .filter(|prefix| outcome_matches_prefix(value, base, num_digits, prefix))
+                .count();
+            if (start..=end).contains(&value) {
+                assert_eq!(matches, 1, "{} should match exactly one group", value);
+            } else {
+                assert_eq!(matches, 0, "{} should match no group", value);
+            }
+        }
+    }
+
+    #[test]
+    fn single_value_interval_is_one_group() {
+        let groups = decompose_range(42, 42, 10, 3).unwrap();
+        assert_eq!(groups, vec![vec![0, 4, 2]]);
+    }
+
+    #[test]
+    fn exhaustive_cover_small_base() {
+        assert_exact_cover(3, 3, 4, 3);
+        assert_exact_cover(3, 60, 4, 3);
+        assert_exact_cover(0, 63, 4, 3);
+        assert_exact_cover(17, 17, 4, 3);
+        assert_exact_cover(5, 5, 2, 5);
+        assert_exact_cover(0, 31, 2, 5);
+        assert_exact_cover(9, 22, 10, 2);
+    }
+
+    #[test]
+    fn rejects_end_outside_representable_range() {
+        assert!(decompose_range(0, 1000, 10, 2).is_err());
+    }
+
+    #[test]
+    fn rejects_start_greater_than_end() {
+        assert!(decompose_range(5, 1, 10, 2).is_err());
+    }
+
+    /// Every integer in the half-open `[start, end)` matches exactly one
+    /// prefix from [`decompose_half_open_range`], and nothing outside it does.
+    fn assert_exact_half_open_cover(start: u64, end: u64, base: u64, num_digits: usize) {
+        let prefixes = decompose_half_open_range(start, end, base, num_digits).unwrap();
+        let max = base.pow(num_digits as u32);
+        for value in 0..max {
+            let matches = prefixes
+                .iter()
+                .filter(|prefix| outcome_matches_prefix(value, base, num_digits, prefix))
+                .count();
+            if (start..end).contains(&value) {
+                assert_eq!(matches, 1, "{} should match exactly one prefix", value);
+            } else {
+                assert_eq!(matches, 0, "{} should match no prefix", value);
+            }
+        }
+    }
+
+    #[test]
+    fn half_open_empty_range_has_no_prefixes() {
+        assert_eq!(decompose_half_open_range(5, 5, 10, 3).unwrap(), Vec::<DigitPrefix>::new());
+    }
+
+    #[test]
+    fn half_open_aligned_block_is_one_prefix() {
+        let prefixes = decompose_half_open_range(0, 100, 10, 3).unwrap();
+        assert_eq!(prefixes, vec![vec![0]]);
+    }
+
+    #[test]
+    fn half_open_exhaustive_cover_small_base() {
+        assert_exact_half_open_cover(0, 64, 4, 3);
+        assert_exact_half_open_cover(3, 60, 4, 3);
+        assert_exact_half_open_cover(17, 18, 4, 3);
+        assert_exact_half_open_cover(0, 32, 2, 5);
+        assert_exact_half_open_cover(9, 23, 10, 2);
+    }
+
+    #[test]
+    fn half_open_rejects_end_outside_representable_range() {
+        assert!(decompose_half_open_range(0, 1001, 10, 2).is_err());
+    }
+
+    #[test]
+    fn curve_payout_matches_the_owning_segment() {
+        let conditions = decompose_curve(&[(0, 39, 10000), (40, 63, 6000)], 4, 3).unwrap();
+        let curve = RangeSettleCurve {
+            base: 4,
+            num_digits: 3,
+            conditions,
+            refund_payout_a_bp: 5000,
+        };
+        for value in 0..=39u64 {
+            assert_eq!(curve.payout_bp_for_outcome(value), Some(10000));
+        }
+        for value in 40..=63u64 {
+            assert_eq!(curve.payout_bp_for_outcome(value), Some(6000));
+        }
+    }
+
+    #[test]
+    fn curve_payout_is_none_outside_every_segment() {
+        let conditions = decompose_curve(&[(0, 10, 10000)], 4, 3).unwrap();
+        let curve = RangeSettleCurve {
+            base: 4,
+            num_digits: 3,
+            conditions,
+            refund_payout_a_bp: 5000,
+        };
+        assert_eq!(curve.payout_bp_for_outcome(11), None);
+    }
+
+    #[test]
+    fn curve_commitment_is_deterministic_and_sensitive_to_payouts() {
+        let conditions_a = decompose_curve(&[(0, 63, 10000)], 4, 3).unwrap();
+        let conditions_b = decompose_curve(&[(0, 63, 9999)], 4, 3).unwrap();
+        let curve_a = RangeSettleCurve {
+            base: 4,
+            num_digits: 3,
+            conditions: conditions_a.clone(),
+            refund_payout_a_bp: 5000,
+        };
+        let curve_a_again = RangeSettleCurve {
+            base: 4,
+            num_digits: 3,
+            conditions: conditions_a,
+            refund_payout_a_bp: 5000,
+        };
+        let curve_b = RangeSettleCurve {
+            base: 4,
+            num_digits: 3,
+            conditions: conditions_b,
+            refund_payout_a_bp: 5000,
+        };
+        assert_eq!(curve_a.commitment(), curve_a_again.commitment());
+        assert_ne!(curve_a.commitment(), curve_b.commitment());
+    }
+}
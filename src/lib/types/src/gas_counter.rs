@@ -2,8 +2,13 @@
 //! the transactions that server sends to the Alaya network.
 //! Server uses this module to ensure that generated transactions
 //! won't run out of the gas and won't trespass the block gas limit.
+// Built-in deps
+use std::collections::HashMap;
+// External deps
+use serde::{Deserialize, Serialize};
 // Workspace deps
 use zkdpos_basic_types::*;
+use zkdpos_crypto::params::CHUNK_BYTES;
 // Local deps
 use crate::{config::MAX_WITHDRAWALS_TO_COMPLETE_IN_A_CALL, Block, ZkDposOp};
 
@@ -12,54 +17,51 @@ use crate::{config::MAX_WITHDRAWALS_TO_COMPLETE_IN_A_CALL, Block, ZkDposOp};
 /// but at the same time it should not exceed the block gas limit.
 pub const TX_GAS_LIMIT: u64 = 4_000_000;
 
-#[derive(Debug)]
-pub struct CommitCost;
-
-impl CommitCost {
-    // Below are costs of processing every kind of operation
-    // in `commitBlock` contract call.
-    //
-    // These values are estimated using the `gas_price_test` in `testkit`.
-
-    // TODO: overvalued for quick fix of tx fails (ZKS-109).
-    pub const BASE_COST: u64 = 40_000;
-    pub const DEPOSIT_COST: u64 = 7_000;
-    pub const OLD_CHANGE_PUBKEY_COST_OFFCHAIN: u64 = 15_000;
-    pub const CHANGE_PUBKEY_COST_OFFCHAIN: u64 = 11_050;
-    pub const CHANGE_PUBKEY_COST_ONCHAIN: u64 = 4_000;
-    pub const TRANSFER_COST: u64 = 250;
-    pub const EXCHANGE_COST: u64 = 250;
-    pub const ADDLIQUIDITY_COST: u64 = 250;
-    pub const REMOVELIQUIDITY_COST: u64 = 250;
-    pub const TRANSFER_TO_NEW_COST: u64 = 780;
-    pub const FULL_EXIT_COST: u64 = 7_000;
-    pub const WITHDRAW_COST: u64 = 3_500;
-    pub const FORCED_EXIT_COST: u64 = Self::WITHDRAW_COST; // TODO: Verify value (ZKS-109).
-
-    pub fn base_cost() -> U256 {
-        U256::from(Self::BASE_COST)
+/// Per-operation-type gas cost, shared by the commit and verify sides of a
+/// [`GasCostTable`]. Values are estimated using the `gas_price_test` in `testkit`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpCosts {
+    pub base_cost: u64,
+    pub deposit: u64,
+    pub old_change_pubkey_offchain: u64,
+    pub change_pubkey_offchain: u64,
+    pub change_pubkey_onchain: u64,
+    pub transfer: u64,
+    pub exchange: u64,
+    pub add_liquidity: u64,
+    pub remove_liquidity: u64,
+    pub swap: u64,
+    pub transfer_to_new: u64,
+    pub full_exit: u64,
+    pub withdraw: u64,
+    pub forced_exit: u64,
+}
+
+impl OpCosts {
+    pub fn base_cost(&self) -> U256 {
+        U256::from(self.base_cost)
     }
 
-    pub fn op_cost(op: &ZkDposOp) -> U256 {
-        // let x = ChangePubKeyAtpAuthDa;
+    pub fn op_cost(&self, op: &ZkDposOp) -> U256 {
         let cost = match op {
             ZkDposOp::Noop(_) => 0,
-            ZkDposOp::Deposit(_) => Self::DEPOSIT_COST,
+            ZkDposOp::Deposit(_) => self.deposit,
             ZkDposOp::ChangePubKeyOffchain(change_pubkey) => {
                 if change_pubkey.tx.is_ecdsa() {
-                    Self::CHANGE_PUBKEY_COST_OFFCHAIN
+                    self.change_pubkey_offchain
                 } else {
-                    Self::CHANGE_PUBKEY_COST_ONCHAIN
+                    self.change_pubkey_onchain
                 }
             }
-            ZkDposOp::Transfer(_) => Self::TRANSFER_COST,
-            ZkDposOp::Exchange(_) => Self::EXCHANGE_COST,
-            ZkDposOp::AddLiquidity(_) => Self::ADDLIQUIDITY_COST,
-            ZkDposOp::RemoveLiquidity(_) => Self::REMOVELIQUIDITY_COST, 
-            ZkDposOp::TransferToNew(_) => Self::TRANSFER_TO_NEW_COST,
-            ZkDposOp::FullExit(_) => Self::FULL_EXIT_COST,
-            ZkDposOp::Withdraw(_) => Self::WITHDRAW_COST,
-            ZkDposOp::ForcedExit(_) => Self::FORCED_EXIT_COST,
+            ZkDposOp::Transfer(_) => self.transfer,
+            ZkDposOp::Exchange(_) => self.exchange,
+            ZkDposOp::AddLiquidity(_) => self.add_liquidity,
+            ZkDposOp::RemoveLiquidity(_) => self.remove_liquidity,
+            ZkDposOp::Swap(_) => self.swap,
+            ZkDposOp::TransferToNew(_) => self.transfer_to_new,
+            ZkDposOp::FullExit(_) => self.full_exit,
+            ZkDposOp::Withdraw(_) => self.withdraw,
+            ZkDposOp::ForcedExit(_) => self.forced_exit,
             ZkDposOp::Close(_) => unreachable!("Close operations are disabled"),
         };
 
@@ -67,125 +69,350 @@ impl CommitCost {
     }
 }
 
-#[derive(Debug)]
-pub struct VerifyCost;
-
-impl VerifyCost {
-    // Below are costs of processing every kind of operation
-    // in `verifyBlock` contract call.
-    //
-    // These values are estimated using the `gas_price_test` in `testkit`.
-
-    // TODO: overvalued for quick fix of tx fails (ZKS-109).
-    pub const BASE_COST: u64 = 10_000;
-    pub const DEPOSIT_COST: u64 = 50;
-    pub const CHANGE_PUBKEY_COST: u64 = 0;
-    pub const TRANSFER_COST: u64 = 0;
-    pub const EXCHANGE_COST: u64 = 0;
-    pub const ADDLIQUIDITY_COST: u64 = 0;
-    pub const REMOVELIQUIDITY_COST: u64 = 0;
-    pub const TRANSFER_TO_NEW_COST: u64 = 0;
-    pub const FULL_EXIT_COST: u64 = 30_000;
-    pub const WITHDRAW_COST: u64 = 48_000;
-    pub const FORCED_EXIT_COST: u64 = Self::WITHDRAW_COST; // TODO: Verify value (ZKS-109).
-
-    pub fn base_cost() -> U256 {
-        U256::from(Self::BASE_COST)
+/// Serialized public-data byte count `op` will contribute to the block committed
+/// to L1, matching its on-chain pubdata encoding (see [`ZkDposOp::chunks`]). Unlike
+/// the [`OpCosts`] dimensions, this is a structural property of the operation, not a
+/// tunable price, so it isn't part of [`GasCostTable`].
+pub fn pubdata_cost(op: &ZkDposOp) -> U256 {
+    U256::from(op.chunks() * CHUNK_BYTES)
+}
+
+/// Runtime-loadable table of gas costs, replacing the old hardcoded `CommitCost`/
+/// `VerifyCost` constants so corrected costs can be shipped via config instead of a
+/// binary release. Tagged with `protocol_version` so the server can key the table
+/// that was active for a given block, re-pricing historical blocks with the table
+/// that produced them rather than whatever table is currently loaded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GasCostTable {
+    pub protocol_version: u32,
+    pub commit: OpCosts,
+    pub verify: OpCosts,
+    /// Base cost of `completeWithdrawals` contract method call.
+    pub complete_withdrawals_base_cost: u64,
+    /// Cost of processing one withdraw operation in `completeWithdrawals` contract call.
+    pub complete_withdrawals_cost: u64,
+    /// Some ERС20 tokens may require a lot of gas to withdrawals.
+    pub complete_withdrawals_erc20_cost: u64,
+    /// Base cost of an aggregated `commitBlocks` contract call.
+    pub base_commit_blocks_tx_cost: u64,
+    /// Base cost of an aggregated `executeBlocks` contract call.
+    pub base_execute_blocks_tx_cost: u64,
+    /// Base cost of an aggregated `proofBlocks` contract call.
+    pub base_proof_blocks_tx_cost: u64,
+}
+
+impl GasCostTable {
+    /// The built-in default table, holding the values this module has historically
+    /// hardcoded (many still carrying "overvalued for quick fix" TODOs, ZKS-109).
+    pub fn builtin() -> Self {
+        Self {
+            protocol_version: 0,
+            commit: OpCosts {
+                // TODO: overvalued for quick fix of tx fails (ZKS-109).
+                base_cost: 40_000,
+                deposit: 7_000,
+                old_change_pubkey_offchain: 15_000,
+                change_pubkey_offchain: 11_050,
+                change_pubkey_onchain: 4_000,
+                transfer: 250,
+                exchange: 250,
+                add_liquidity: 250,
+                remove_liquidity: 250,
+                swap: 250,
+                transfer_to_new: 780,
+                full_exit: 7_000,
+                withdraw: 3_500,
+                forced_exit: 3_500, // TODO: Verify value (ZKS-109).
+            },
+            verify: OpCosts {
+                // TODO: overvalued for quick fix of tx fails (ZKS-109).
+                base_cost: 10_000,
+                deposit: 50,
+                old_change_pubkey_offchain: 0,
+                change_pubkey_offchain: 0,
+                change_pubkey_onchain: 0,
+                transfer: 0,
+                exchange: 0,
+                add_liquidity: 0,
+                remove_liquidity: 0,
+                swap: 0,
+                transfer_to_new: 0,
+                full_exit: 30_000,
+                withdraw: 48_000,
+                forced_exit: 48_000, // TODO: Verify value (ZKS-109).
+            },
+            complete_withdrawals_base_cost: 30_307,
+            complete_withdrawals_cost: 41_641,
+            complete_withdrawals_erc20_cost: 200_000,
+            base_commit_blocks_tx_cost: 450_000,
+            base_execute_blocks_tx_cost: 450_000,
+            base_proof_blocks_tx_cost: 1_500_000,
+        }
     }
+}
 
-    pub fn op_cost(op: &ZkDposOp) -> U256 {
-        let cost = match op {
-            ZkDposOp::Noop(_) => 0,
-            ZkDposOp::Deposit(_) => Self::DEPOSIT_COST,
-            ZkDposOp::ChangePubKeyOffchain(_) => Self::CHANGE_PUBKEY_COST,
-            ZkDposOp::Transfer(_) => Self::TRANSFER_COST,
-            ZkDposOp::Exchange(_) => Self::EXCHANGE_COST,
-            ZkDposOp::AddLiquidity(_) => Self::ADDLIQUIDITY_COST,
-            ZkDposOp::RemoveLiquidity(_) => Self::REMOVELIQUIDITY_COST,            
-            ZkDposOp::TransferToNew(_) => Self::TRANSFER_TO_NEW_COST,
-            ZkDposOp::FullExit(_) => Self::FULL_EXIT_COST,
-            ZkDposOp::Withdraw(_) => Self::WITHDRAW_COST,
-            ZkDposOp::ForcedExit(_) => Self::FORCED_EXIT_COST,
-            ZkDposOp::Close(_) => unreachable!("Close operations are disabled"),
-        };
+impl Default for GasCostTable {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
 
-        U256::from(cost)
+/// Approximate L1 gas cost of a single non-zero calldata byte, per the Alaya gas
+/// schedule. Used to convert [`pubdata_cost`]'s byte count into a gas figure
+/// comparable to `TX_GAS_LIMIT`.
+pub const PUBDATA_GAS_PER_BYTE: u64 = 16;
+
+/// Maximum L1 gas we're willing to spend posting a single block's public data.
+/// Blocks dominated by large operations (e.g. `ChangePubKey`) can run into Alaya's
+/// calldata-cost-per-byte long before the compute gas dimensions above do.
+pub const PUBDATA_GAS_LIMIT: u64 = TX_GAS_LIMIT;
+
+/// Maximum gas cost we're willing to attribute to writes touching a single account
+/// within one block. Without this, a single hot account (e.g. a market maker sending
+/// many operations) could dominate a block and starve out everyone else.
+pub const ACCOUNT_GAS_LIMIT: u64 = 600_000;
+
+/// The dimension of [`GasCounter`] that would be exceeded by adding an operation,
+/// named so that the caller (e.g. `state_keeper`) can tell why a block was sealed
+/// without having to re-derive the costs itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GasCounterError {
+    /// Adding the operation would push the commit cost above [`TX_GAS_LIMIT`].
+    WouldExceedCommitLimit,
+    /// Adding the operation would push the verify cost above [`TX_GAS_LIMIT`].
+    WouldExceedVerifyLimit,
+    /// Adding the operation would push the accumulated L1 pubdata gas above
+    /// [`PUBDATA_GAS_LIMIT`].
+    WouldExceedPubdataLimit,
+    /// Adding the operation would push the cost attributed to `AccountId` above
+    /// [`ACCOUNT_GAS_LIMIT`].
+    WouldExceedAccountLimit(AccountId),
+}
+
+impl std::fmt::Display for GasCounterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::WouldExceedCommitLimit => write!(f, "Operation would exceed the commit gas limit"),
+            Self::WouldExceedVerifyLimit => write!(f, "Operation would exceed the verify gas limit"),
+            Self::WouldExceedPubdataLimit => {
+                write!(f, "Operation would exceed the L1 pubdata gas limit")
+            }
+            Self::WouldExceedAccountLimit(account_id) => write!(
+                f,
+                "Operation would exceed the per-account gas limit for account {:?}",
+                account_id
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GasCounterError {}
+
+/// Returned by the `execute_fn` closure passed to [`GasCounter::estimate_block_gas`]
+/// when a dry run of the commit/verify call runs out of gas at the candidate limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutOfGas;
+
+impl std::fmt::Display for OutOfGas {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Execution ran out of gas at the candidate limit")
     }
 }
 
-/// `GasCounter` is an entity capable of counting the estimated gas cost of an
-/// upcoming transaction. It watches for the total gas cost of either commit
-/// or withdraw operation to not exceed the reasonable gas limit amount.
+impl std::error::Error for OutOfGas {}
+
+/// Ordering used by [`GasCounter::pack_greedy`] when selecting which candidate
+/// operations to include in a block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrioritizationStrategy {
+    /// Highest absolute fee first.
+    ByFee,
+    /// Highest fee per unit of commit gas first.
+    ByFeePerGas,
+    /// Arrival order, unchanged.
+    Fifo,
+}
+
+impl Default for PrioritizationStrategy {
+    fn default() -> Self {
+        Self::ByFeePerGas
+    }
+}
+
+/// `GasCounter` is an entity capable of counting the estimated resource cost of an
+/// upcoming transaction across several independent dimensions: commit gas, verify
+/// gas, L1 pubdata gas, and per-account write cost. It watches for any one of these
+/// dimensions to not exceed its reasonable limit.
 /// It is used by `state_keeper` module to seal the block once we're not able
 /// to safely insert any more transactions.
 ///
-/// The estimation process is based on the pre-calculated "base cost" of operation
-/// (basically, cost of processing an empty block), and the added cost of all the
-/// operations in that block.
-///
-/// These estimated costs were calculated using the `gas_price_test` from `testkit`.
+/// Costs are looked up from a borrowed [`GasCostTable`] rather than baked in as
+/// constants, so the server can recalibrate them from config at startup, and key
+/// historical blocks to the table that was active (by `protocol_version`) when they
+/// were produced.
 #[derive(Debug, Clone)]
-pub struct GasCounter {
+pub struct GasCounter<'a> {
+    table: &'a GasCostTable,
     commit_cost: U256,
     verify_cost: U256,
+    pubdata_bytes: u64,
+    account_costs: HashMap<AccountId, u64>,
 }
 
-impl Default for GasCounter {
-    fn default() -> Self {
+impl<'a> GasCounter<'a> {
+    pub fn new(table: &'a GasCostTable) -> Self {
         Self {
-            commit_cost: CommitCost::base_cost(),
-            verify_cost: VerifyCost::base_cost(),
+            table,
+            commit_cost: table.commit.base_cost(),
+            verify_cost: table.verify.base_cost(),
+            pubdata_bytes: 0,
+            account_costs: HashMap::new(),
         }
     }
-}
-
-#[derive(Debug)]
-pub struct WrongTransaction;
 
-impl std::fmt::Display for WrongTransaction {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Wrong transaction in gas counter")
+    /// The table this counter is pricing operations against.
+    pub fn table(&self) -> &'a GasCostTable {
+        self.table
     }
-}
 
-impl std::error::Error for WrongTransaction {}
+    /// Checks whether `op` can be added to the block without exceeding any of the
+    /// tracked dimensions, without mutating any state. Returns the first dimension
+    /// that would overflow, in the order: commit, verify, pubdata, per-account.
+    pub fn would_fit(&self, op: &ZkDposOp) -> Result<(), GasCounterError> {
+        let new_commit_cost = self.commit_cost + self.table.commit.op_cost(op);
+        if Self::scale_up(new_commit_cost) > U256::from(TX_GAS_LIMIT) {
+            return Err(GasCounterError::WouldExceedCommitLimit);
+        }
 
-impl GasCounter {
-    /// Base cost of `completeWithdrawals` contract method call.
-    pub const COMPLETE_WITHDRAWALS_BASE_COST: u64 = 30_307;
-    /// Cost of processing one withdraw operation in `completeWithdrawals` contract call.
-    pub const COMPLETE_WITHDRAWALS_COST: u64 = 41_641;
-    /// Some ERС20 tokens may require a lot of gas to withdrawals.
-    pub const COMPLETE_WITHDRAWALS_ERC20_COST: u64 = 200_000;
+        let new_verify_cost = self.verify_cost + self.table.verify.op_cost(op);
+        if Self::scale_up(new_verify_cost) > U256::from(TX_GAS_LIMIT) {
+            return Err(GasCounterError::WouldExceedVerifyLimit);
+        }
+
+        let new_pubdata_bytes = self.pubdata_bytes + pubdata_cost(op).as_u64();
+        let new_pubdata_gas = new_pubdata_bytes * PUBDATA_GAS_PER_BYTE;
+        if Self::scale_up_u64(new_pubdata_gas) > PUBDATA_GAS_LIMIT {
+            return Err(GasCounterError::WouldExceedPubdataLimit);
+        }
 
-    /// constants for gas limit calculation of aggregated operations
-    pub const BASE_COMMIT_BLOCKS_TX_COST: usize = 450_000;
-    pub const BASE_EXECUTE_BLOCKS_TX_COST: usize = 450_000;
-    pub const BASE_PROOF_BLOCKS_TX_COST: usize = 1_500_000;
+        let op_account_cost = self.table.commit.op_cost(op).as_u64();
+        for account_id in op.get_updated_account_ids() {
+            let new_account_cost =
+                self.account_costs.get(&account_id).copied().unwrap_or(0) + op_account_cost;
+            if Self::scale_up_u64(new_account_cost) > ACCOUNT_GAS_LIMIT {
+                return Err(GasCounterError::WouldExceedAccountLimit(account_id));
+            }
+        }
 
-    pub fn new() -> Self {
-        Self::default()
+        Ok(())
     }
 
     /// Adds the cost of the operation to the gas counter.
     ///
-    /// Returns `Ok(())` if transaction fits, and returns `Err(())` if
-    /// the block must be sealed without this transaction.
-    pub fn add_op(&mut self, op: &ZkDposOp) -> Result<(), WrongTransaction> {
-        let new_commit_cost = self.commit_cost + CommitCost::op_cost(op);
-        if Self::scale_up(new_commit_cost) > U256::from(TX_GAS_LIMIT) {
-            return Err(WrongTransaction);
+    /// Returns `Ok(())` if the operation fits in every tracked dimension and commits
+    /// the updated costs, and returns `Err` naming the first dimension that would be
+    /// exceeded if the block must be sealed without this transaction.
+    pub fn add_op(&mut self, op: &ZkDposOp) -> Result<(), GasCounterError> {
+        self.would_fit(op)?;
+
+        self.commit_cost += self.table.commit.op_cost(op);
+        self.verify_cost += self.table.verify.op_cost(op);
+        self.pubdata_bytes += pubdata_cost(op).as_u64();
+
+        let op_account_cost = self.table.commit.op_cost(op).as_u64();
+        for account_id in op.get_updated_account_ids() {
+            *self.account_costs.entry(account_id).or_insert(0) += op_account_cost;
         }
 
-        let new_verify_cost = self.verify_cost + VerifyCost::op_cost(op);
-        if Self::scale_up(new_verify_cost) > U256::from(TX_GAS_LIMIT) {
-            return Err(WrongTransaction);
+        Ok(())
+    }
+
+    /// Number of bisection rounds [`Self::estimate_block_gas`] will run before giving up,
+    /// guarding against a non-monotonic `execute_fn` spinning forever.
+    pub const ESTIMATE_MAX_ITERATIONS: u32 = 64;
+    /// [`Self::estimate_block_gas`] stops bisecting once the search bracket has narrowed
+    /// to within this many gas units.
+    pub const ESTIMATE_TOLERANCE: u64 = 2_000;
+
+    /// Finds the minimal gas limit that still lets `ops` commit/verify successfully,
+    /// similar to `eth_estimateGas`. Starts from the table's `op_cost` sum as a lower
+    /// bound and `TX_GAS_LIMIT` as an upper bound, then binary-searches: `execute_fn`
+    /// dry-runs the commit/verify call at a candidate limit, and the search narrows
+    /// towards the smallest limit for which it returns `Ok`.
+    ///
+    /// `execute_fn` must be monotonic (success at `G` implies success at any `G' > G`);
+    /// a non-monotonic closure won't cause an infinite loop, since the search is capped
+    /// at [`Self::ESTIMATE_MAX_ITERATIONS`] rounds, but the result may simply be wrong.
+    ///
+    /// The returned value is scaled up by the usual 30% headroom.
+    pub fn estimate_block_gas(
+        &self,
+        ops: &[ZkDposOp],
+        execute_fn: impl Fn(u64) -> Result<(), OutOfGas>,
+    ) -> u64 {
+        let mut low = self.table.commit.base_cost
+            + ops
+                .iter()
+                .map(|op| self.table.commit.op_cost(op).as_u64())
+                .sum::<u64>();
+        let mut high = TX_GAS_LIMIT;
+
+        for _ in 0..Self::ESTIMATE_MAX_ITERATIONS {
+            if high <= low || high - low <= Self::ESTIMATE_TOLERANCE {
+                break;
+            }
+
+            let mid = low + (high - low) / 2;
+            match execute_fn(mid) {
+                Ok(()) => high = mid,
+                Err(OutOfGas) => low = mid,
+            }
         }
 
-        self.commit_cost = new_commit_cost;
-        self.verify_cost = new_verify_cost;
+        Self::scale_up_u64(high)
+    }
 
-        Ok(())
+    /// Greedily selects a fee-maximizing subset of `candidates` (each paired with its
+    /// fee) that fits within the commit/verify/pubdata/per-account limits tracked from
+    /// `self`'s current state. Orders candidates per `strategy`, then walks them once:
+    /// a candidate that doesn't fit is skipped rather than aborting the whole pass, so
+    /// smaller profitable operations queued behind a large one can still be packed.
+    ///
+    /// Returns the selected operations, in selection order, plus the resulting
+    /// `GasCounter` state.
+    pub fn pack_greedy<'b>(
+        &self,
+        candidates: &[(&'b ZkDposOp, u64)],
+        strategy: PrioritizationStrategy,
+    ) -> (Vec<&'b ZkDposOp>, GasCounter<'a>) {
+        let mut ordered: Vec<&(&'b ZkDposOp, u64)> = candidates.iter().collect();
+        match strategy {
+            PrioritizationStrategy::ByFee => ordered.sort_by(|a, b| b.1.cmp(&a.1)),
+            PrioritizationStrategy::ByFeePerGas => ordered.sort_by(|a, b| {
+                self.fee_per_gas(a.0, a.1)
+                    .partial_cmp(&self.fee_per_gas(b.0, b.1))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .reverse()
+            }),
+            PrioritizationStrategy::Fifo => {}
+        }
+
+        let mut counter = self.clone();
+        let mut selected = Vec::new();
+        for (op, _fee) in ordered {
+            if counter.add_op(op).is_ok() {
+                selected.push(*op);
+            }
+        }
+
+        (selected, counter)
+    }
+
+    /// Fee per unit of commit gas, used to order candidates under
+    /// [`PrioritizationStrategy::ByFeePerGas`].
+    fn fee_per_gas(&self, op: &ZkDposOp, fee: u64) -> f64 {
+        let gas = self.table.commit.op_cost(op).as_u64().max(1);
+        fee as f64 / gas as f64
     }
 
     pub fn commit_gas_limit(&self) -> U256 {
@@ -196,26 +423,33 @@ impl GasCounter {
         self.verify_cost * U256::from(130) / U256::from(100)
     }
 
-    pub fn complete_withdrawals_gas_limit() -> U256 {
+    /// Raw accumulated public-data byte count, independent of the L1 gas conversion,
+    /// for callers (fee logic, `state_keeper`) that need to reason about pubdata size
+    /// on its own.
+    pub fn pubdata_bytes(&self) -> u64 {
+        self.pubdata_bytes
+    }
+
+    pub fn complete_withdrawals_gas_limit(table: &GasCostTable) -> U256 {
         // Currently we always complete a constant amount of withdrawals in the contract call, so the upper limit
         // is predictable.
-        let approx_limit = U256::from(Self::COMPLETE_WITHDRAWALS_BASE_COST)
+        let approx_limit = U256::from(table.complete_withdrawals_base_cost)
             + U256::from(MAX_WITHDRAWALS_TO_COMPLETE_IN_A_CALL)
-                * U256::from(Self::COMPLETE_WITHDRAWALS_ERC20_COST);
+                * U256::from(table.complete_withdrawals_erc20_cost);
 
         // We scale this value up nevertheless, just in case.
         Self::scale_up(approx_limit)
     }
 
-    pub fn commit_gas_limit_aggregated(blocks: &[Block]) -> U256 {
-        U256::from(Self::BASE_COMMIT_BLOCKS_TX_COST)
+    pub fn commit_gas_limit_aggregated(table: &GasCostTable, blocks: &[Block]) -> U256 {
+        U256::from(table.base_commit_blocks_tx_cost)
             + blocks
                 .iter()
                 .fold(U256::zero(), |acc, block| acc + block.commit_gas_limit)
     }
 
-    pub fn execute_gas_limit_aggregated(blocks: &[Block]) -> U256 {
-        U256::from(Self::BASE_EXECUTE_BLOCKS_TX_COST)
+    pub fn execute_gas_limit_aggregated(table: &GasCostTable, blocks: &[Block]) -> U256 {
+        U256::from(table.base_execute_blocks_tx_cost)
             + blocks
                 .iter()
                 .fold(U256::zero(), |acc, block| acc + block.verify_gas_limit)
@@ -225,6 +459,11 @@ impl GasCounter {
     fn scale_up(value: U256) -> U256 {
         value * U256::from(130) / U256::from(100)
     }
+
+    /// Same as [`Self::scale_up`], for dimensions tracked as plain `u64` rather than `U256`.
+    fn scale_up_u64(value: u64) -> u64 {
+        value * 130 / 100
+    }
 }
 
 #[cfg(test)]
@@ -241,6 +480,8 @@ mod tests {
 
     #[test]
     fn commit_and_verify_cost() {
+        let table = GasCostTable::builtin();
+
         let change_pubkey_op = ChangePubKeyOp {
             tx: ChangePubKey::new(
                 AccountId(1),
@@ -254,6 +495,7 @@ mod tests {
                 None,
             ),
             account_id: AccountId(1),
+            threshold: 0,
         };
         let deposit_op = DepositOp {
             priority_op: Deposit {
@@ -327,6 +569,7 @@ mod tests {
                 Nonce(0),
                 Default::default(),
                 None,
+                0,
             ),
             account_id: AccountId(1),
         };
@@ -334,58 +577,51 @@ mod tests {
         let test_vector_commit = vec![
             (
                 ZkDposOp::from(change_pubkey_op.clone()),
-                CommitCost::CHANGE_PUBKEY_COST_ONCHAIN,
-            ),
-            (ZkDposOp::from(deposit_op.clone()), CommitCost::DEPOSIT_COST),
-            (
-                ZkDposOp::from(transfer_op.clone()),
-                CommitCost::TRANSFER_COST,
+                table.commit.change_pubkey_onchain,
             ),
+            (ZkDposOp::from(deposit_op.clone()), table.commit.deposit),
+            (ZkDposOp::from(transfer_op.clone()), table.commit.transfer),
             (
                 ZkDposOp::from(transfer_to_new_op.clone()),
-                CommitCost::TRANSFER_TO_NEW_COST,
+                table.commit.transfer_to_new,
             ),
             (ZkDposOp::from(noop_op.clone()), 0),
-            (
-                ZkDposOp::from(full_exit_op.clone()),
-                CommitCost::FULL_EXIT_COST,
-            ),
+            (ZkDposOp::from(full_exit_op.clone()), table.commit.full_exit),
             (
                 ZkDposOp::from(forced_exit_op.clone()),
-                CommitCost::FORCED_EXIT_COST,
-            ),
-            (
-                ZkDposOp::from(withdraw_op.clone()),
-                CommitCost::WITHDRAW_COST,
+                table.commit.forced_exit,
             ),
+            (ZkDposOp::from(withdraw_op.clone()), table.commit.withdraw),
         ];
         let test_vector_verify = vec![
             (
                 ZkDposOp::from(change_pubkey_op),
-                VerifyCost::CHANGE_PUBKEY_COST,
+                table.verify.change_pubkey_onchain,
             ),
-            (ZkDposOp::from(deposit_op), VerifyCost::DEPOSIT_COST),
-            (ZkDposOp::from(transfer_op), VerifyCost::TRANSFER_COST),
+            (ZkDposOp::from(deposit_op), table.verify.deposit),
+            (ZkDposOp::from(transfer_op), table.verify.transfer),
             (
                 ZkDposOp::from(transfer_to_new_op),
-                VerifyCost::TRANSFER_TO_NEW_COST,
+                table.verify.transfer_to_new,
             ),
             (ZkDposOp::from(noop_op), 0),
-            (ZkDposOp::from(full_exit_op), VerifyCost::FULL_EXIT_COST),
-            (ZkDposOp::from(forced_exit_op), VerifyCost::FORCED_EXIT_COST),
-            (ZkDposOp::from(withdraw_op), VerifyCost::WITHDRAW_COST),
+            (ZkDposOp::from(full_exit_op), table.verify.full_exit),
+            (ZkDposOp::from(forced_exit_op), table.verify.forced_exit),
+            (ZkDposOp::from(withdraw_op), table.verify.withdraw),
         ];
 
         for (op, expected_cost) in test_vector_commit {
-            assert_eq!(CommitCost::op_cost(&op), U256::from(expected_cost));
+            assert_eq!(table.commit.op_cost(&op), U256::from(expected_cost));
         }
         for (op, expected_cost) in test_vector_verify {
-            assert_eq!(VerifyCost::op_cost(&op), U256::from(expected_cost));
+            assert_eq!(table.verify.op_cost(&op), U256::from(expected_cost));
         }
     }
 
     #[test]
     fn gas_counter() {
+        let table = GasCostTable::builtin();
+
         let change_pubkey_op = ChangePubKeyOp {
             tx: ChangePubKey::new(
                 AccountId(1),
@@ -399,18 +635,19 @@ mod tests {
                 None,
             ),
             account_id: AccountId(1),
+            threshold: 0,
         };
         let zkdpos_op = ZkDposOp::from(change_pubkey_op);
 
-        let mut gas_counter = GasCounter::new();
+        let mut gas_counter = GasCounter::new(&table);
 
-        assert_eq!(gas_counter.commit_cost, U256::from(CommitCost::BASE_COST));
-        assert_eq!(gas_counter.verify_cost, U256::from(VerifyCost::BASE_COST));
+        assert_eq!(gas_counter.commit_cost, U256::from(table.commit.base_cost));
+        assert_eq!(gas_counter.verify_cost, U256::from(table.verify.base_cost));
 
         // Verify cost is 0, thus amount of operations is determined by the commit cost.
         let amount_ops_in_block = (U256::from(TX_GAS_LIMIT)
             - GasCounter::scale_up(gas_counter.commit_cost))
-            / GasCounter::scale_up(U256::from(CommitCost::CHANGE_PUBKEY_COST_ONCHAIN));
+            / GasCounter::scale_up(U256::from(table.commit.change_pubkey_onchain));
 
         for _ in 0..amount_ops_in_block.as_u64() {
             gas_counter
@@ -419,12 +656,12 @@ mod tests {
         }
 
         // Expected gas limit is (base_cost + n_ops * op_cost) * 1.3
-        let expected_commit_limit = (U256::from(CommitCost::BASE_COST)
-            + amount_ops_in_block * U256::from(CommitCost::CHANGE_PUBKEY_COST_ONCHAIN))
+        let expected_commit_limit = (U256::from(table.commit.base_cost)
+            + amount_ops_in_block * U256::from(table.commit.change_pubkey_onchain))
             * U256::from(130)
             / U256::from(100);
-        let expected_verify_limit = (U256::from(VerifyCost::BASE_COST)
-            + amount_ops_in_block * U256::from(VerifyCost::CHANGE_PUBKEY_COST))
+        let expected_verify_limit = (U256::from(table.verify.base_cost)
+            + amount_ops_in_block * U256::from(table.verify.change_pubkey_onchain))
             * U256::from(130)
             / U256::from(100);
         assert_eq!(gas_counter.commit_gas_limit(), expected_commit_limit);
@@ -11,7 +11,7 @@
 //! - **priority operations**: operations of zkDpos network which are triggered by
 //!   invoking the zkDpos smart contract method in L1. These operations are disovered by
 //!   the zkDpos server and included into the block just like L2 transactions.
-//!   Currently includes [`Deposit`] and [`FullExit`].
+//!   Currently includes [`Deposit`], [`FullExit`], [`Conditional`] and [`RangeSettle`].
 //!   All the priority operations form an enum named [`ZkDposPriorityOp`].
 //! - **operations**: a superset of [`ZkDposTx`] and [`ZkDposPriorityOp`]
 //!   All the operations are included into an enum named [`ZkDposOp`]. This enum contains
@@ -27,6 +27,8 @@
 //! [`ZkDposTx`]: ./tx/enum.ZkDposTx.html
 //! [`Deposit`]: ./priority_ops/struct.Deposit.html
 //! [`FullExit`]: ./priority_ops/struct.FullExit.html
+//! [`Conditional`]: ./priority_ops/struct.Conditional.html
+//! [`RangeSettle`]: ./priority_ops/struct.RangeSettle.html
 //! [`ZkDposPriorityOp`]: ./priority_ops/enum.ZkDposPriorityOp.html
 //! [`ZkDposOp`]: ./operations/enum.ZkDposOp.html
 //! [`Exchange`]: ./tx/struct.Exchange.html
@@ -39,17 +41,22 @@
 
 pub mod account;
 pub mod aggregated_operations;
+pub mod attempt;
 pub mod block;
 pub mod config;
 pub mod alaya;
+pub mod dlc;
 pub mod fee;
 pub mod gas_counter;
 pub mod helpers;
 pub mod mempool;
 pub mod network;
 pub mod operations;
+pub mod oracle;
+pub mod pool;
 pub mod priority_ops;
 pub mod prover;
+pub mod token_limits;
 pub mod tokens;
 pub mod tx;
 mod utils;
@@ -58,15 +65,28 @@ mod utils;
 // mod tests;
 
 pub use self::account::{Account, AccountUpdate, PubKeyHash};
+pub use self::attempt::{
+    AttemptErrorCounts, AttemptErrorKey, AttemptOutcome, AttemptRecordStorage, OperationErrorCode,
+    PriorityOpRecord, TxAttemptRecord,
+};
 pub use self::block::{ExecutedOperations, ExecutedPriorityOp, ExecutedTx};
 pub use self::fee::{BatchFee, Fee, OutputFeeType};
 pub use self::operations::{
-    ChangePubKeyOp, DepositOp, ForcedExitOp, FullExitOp, TransferOp, TransferToNewOp, WithdrawOp, ExchangeOp, AddLiquidityOp, RemoveLiquidityOp, 
-    ZkDposOp,
+    AdvanceNonceOp, ChangePubKeyOp, ConditionalOp, ConditionalSettleOp, ConditionalTransferOp,
+    DepositOp, EscrowOp, ForcedExitOp, FullExitOp, TransferOp, TransferToNewOp, WithdrawOp,
+    ExchangeOp, AddLiquidityOp, RemoveLiquidityOp, SwapOp, OrderMatchOp, RangeSettleOp,
+    RangeSettleCompleteOp, GrantDelegateOp, ZkDposOp,
 };
-pub use self::priority_ops::{Deposit, FullExit, PriorityOp, ZkDposPriorityOp};
+pub use self::oracle::{OracleError, PriceAttestation, PriceOracleConfig};
+pub use self::pool::{Fraction, Pool};
+pub use self::priority_ops::{Conditional, Deposit, FullExit, PriorityOp, RangeSettle, ZkDposPriorityOp};
+pub use self::token_limits::{TokenLimit, TokenLimits};
 pub use self::tokens::{Token, TokenGenesisListItem, TokenLike, TokenPrice, TxFeeTypes};
-pub use self::tx::{ForcedExit, SignedZkDposTx, Transfer, Withdraw, Exchange, AddLiquidity, RemoveLiquidity, ZkDposTx};
+pub use self::tx::{
+    AdvanceNonce, ForcedExit, SignedZkDposTx, Transfer, Withdraw, Exchange, AddLiquidity,
+    RemoveLiquidity, Swap, EscrowTransfer, ConditionalSettle, ConditionalTransfer, OrderMatch,
+    Order, PredicateLeaf, PredicateNode, RangeSettleComplete, GrantDelegate, VerifiedTx, ZkDposTx,
+};
 
 #[doc(hidden)]
 pub use self::{operations::CloseOp, tx::Close};
@@ -1,8 +1,30 @@
+use serde::{Deserialize, Serialize};
+
 use super::{
     tx::{TxAtpSignature, TxHash},
     SignedZkDposTx,
 };
 
+/// Version tag of the serialized transaction envelope.
+///
+/// `Legacy` is the format that has always been used: a `SignedTxVariant` is either
+/// a bare transaction or a batch, with no explicit version marker, and it must
+/// keep serializing/hashing byte-identically to preserve existing signatures.
+/// `V1` reserves room for the extended fields (e.g. batch metadata) that a future
+/// protocol upgrade may add; it is accepted only when explicitly enabled, so
+/// rolling it out never forces a hard fork on clients that don't opt in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TxVersion {
+    Legacy,
+    V1,
+}
+
+impl Default for TxVersion {
+    fn default() -> Self {
+        Self::Legacy
+    }
+}
+
 /// A collection of transactions that must be executed together.
 /// All the transactions in the batch must be included into the same block,
 /// and either succeed or fail all together.
@@ -15,15 +37,19 @@ pub struct SignedTxsBatch {
 
 /// A wrapper around possible atomic block elements: it can be either
 /// a single transaction, or the transactions batch.
+///
+/// Every variant carries a [`TxVersion`]; it defaults to `Legacy` and has no effect
+/// on `hashes()`/`get_transactions()`, which remain version-agnostic so callers
+/// that don't care about the envelope format keep working unchanged.
 #[derive(Debug, Clone)]
 pub enum SignedTxVariant {
-    Tx(SignedZkDposTx),
-    Batch(SignedTxsBatch),
+    Tx(SignedZkDposTx, TxVersion),
+    Batch(SignedTxsBatch, TxVersion),
 }
 
 impl From<SignedZkDposTx> for SignedTxVariant {
     fn from(tx: SignedZkDposTx) -> Self {
-        Self::Tx(tx)
+        Self::Tx(tx, TxVersion::Legacy)
     }
 }
 
@@ -33,24 +59,58 @@ impl SignedTxVariant {
         batch_id: i64,
         atp_signatures: Vec<TxAtpSignature>,
     ) -> Self {
-        Self::Batch(SignedTxsBatch {
-            txs,
-            batch_id,
-            atp_signatures,
-        })
+        Self::Batch(
+            SignedTxsBatch {
+                txs,
+                batch_id,
+                atp_signatures,
+            },
+            TxVersion::Legacy,
+        )
+    }
+
+    /// Builds a variant tagged with an explicit [`TxVersion`]. Use this instead of
+    /// `From`/`batch` when accepting a non-legacy envelope.
+    pub fn with_version(self, version: TxVersion) -> Self {
+        match self {
+            Self::Tx(tx, _) => Self::Tx(tx, version),
+            Self::Batch(batch, _) => Self::Batch(batch, version),
+        }
+    }
+
+    pub fn version(&self) -> TxVersion {
+        match self {
+            Self::Tx(_, version) => *version,
+            Self::Batch(_, version) => *version,
+        }
+    }
+
+    /// Checks the variant's version is acceptable for inclusion into a block.
+    ///
+    /// Non-legacy envelopes are rejected unless `allow_non_legacy` is set, which lets
+    /// the mempool/block-builder gate the new format behind a runtime feature flag
+    /// until it is activated network-wide.
+    pub fn check_version_allowed(&self, allow_non_legacy: bool) -> Result<(), anyhow::Error> {
+        if self.version() != TxVersion::Legacy && !allow_non_legacy {
+            anyhow::bail!(
+                "Transaction envelope version {:?} is not enabled on this network",
+                self.version()
+            );
+        }
+        Ok(())
     }
 
     pub fn hashes(&self) -> Vec<TxHash> {
         match self {
-            Self::Tx(tx) => vec![tx.hash()],
-            Self::Batch(batch) => batch.txs.iter().map(|tx| tx.hash()).collect(),
+            Self::Tx(tx, _) => vec![tx.hash()],
+            Self::Batch(batch, _) => batch.txs.iter().map(|tx| tx.hash()).collect(),
         }
     }
 
     pub fn get_transactions(&self) -> Vec<SignedZkDposTx> {
         match self {
-            Self::Tx(tx) => vec![tx.clone()],
-            Self::Batch(batch) => batch.txs.clone(),
+            Self::Tx(tx, _) => vec![tx.clone()],
+            Self::Batch(batch, _) => batch.txs.clone(),
         }
     }
 }
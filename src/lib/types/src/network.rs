@@ -30,6 +30,11 @@ pub enum Network {
     Unknown,
     /// Test network for testkit purposes
     Test,
+    /// Custom/self-hosted Alaya & zkDpos network with a user-provided chain ID.
+    Custom {
+        /// Chain ID of the self-hosted network.
+        chain_id: u32,
+    },
 }
 
 impl FromStr for Network {
@@ -43,7 +48,15 @@ impl FromStr for Network {
             "ropsten" => Self::Ropsten,
             "localhost" => Self::Localhost,
             "test" => Self::Test,
-            another => return Err(another.to_owned()),
+            another => {
+                if let Some(chain_id) = another.strip_prefix("custom:") {
+                    let chain_id = chain_id
+                        .parse::<u32>()
+                        .map_err(|_| another.to_owned())?;
+                    return Ok(Self::Custom { chain_id });
+                }
+                return Err(another.to_owned());
+            }
         })
     }
 }
@@ -58,6 +71,7 @@ impl fmt::Display for Network {
             Self::Localhost => write!(f, "localhost"),
             Self::Unknown => write!(f, "unknown"),
             Self::Test => write!(f, "test"),
+            Self::Custom { chain_id } => write!(f, "custom:{}", chain_id),
         }
     }
 }
@@ -71,8 +85,9 @@ impl Network {
             Network::Ropsten => 3,
             Network::Rinkeby => 4,
             Network::Localhost => 9,
-            Network::Unknown => panic!("Unknown chain ID"),
-            Network::Test => panic!("Test chain ID"),
+            Network::Unknown => 0,
+            Network::Test => 0,
+            Network::Custom { chain_id } => chain_id,
         }
     }
 }
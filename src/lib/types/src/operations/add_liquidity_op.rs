@@ -2,7 +2,7 @@ use crate::{
     helpers::{pack_fee_amount, pack_token_amount, unpack_fee_amount, unpack_token_amount},
     AddLiquidity,
 };
-use crate::{AccountId, Address, Nonce, LiquidityId, TokenId};
+use crate::{AccountId, Address, LiquidityId, Nonce, TokenId};
 use anyhow::{ensure, format_err};
 use serde::{Deserialize, Serialize};
 use zkdpos_crypto::params::{
@@ -20,20 +20,25 @@ pub struct AddLiquidityOp {
 }
 
 impl AddLiquidityOp {
-    pub const CHUNKS: usize = 2;
-    pub const OP_CODE: u8 = 0x05;
+    pub const CHUNKS: usize = 6;
+    pub const OP_CODE: u8 = 0x0a;
 
     pub(crate) fn get_public_data(&self) -> Vec<u8> {
         let mut data = Vec::new();
         data.push(Self::OP_CODE); // opcode
         data.extend_from_slice(&self.from.to_be_bytes());
         data.extend_from_slice(&self.to.to_be_bytes());
+        data.extend_from_slice(&self.tx.liquidity_id.to_be_bytes());
+        data.extend_from_slice(&self.tx.token_a.to_be_bytes());
+        data.extend_from_slice(&self.tx.token_b.to_be_bytes());
         data.extend_from_slice(&pack_token_amount(&self.tx.amount_a_desired));
         data.extend_from_slice(&pack_token_amount(&self.tx.amount_b_desired));
         data.extend_from_slice(&pack_token_amount(&self.tx.amount_a_min));
         data.extend_from_slice(&pack_token_amount(&self.tx.amount_b_min));
         data.extend_from_slice(&pack_fee_amount(&self.tx.fee_a));
         data.extend_from_slice(&pack_fee_amount(&self.tx.fee_b));
+        data.extend_from_slice(&self.tx.p_low.to_be_bytes());
+        data.extend_from_slice(&self.tx.p_high.to_be_bytes());
         data.resize(Self::CHUNKS * CHUNK_BYTES, 0x00);
         data
     }
@@ -41,61 +46,73 @@ impl AddLiquidityOp {
     pub fn from_public_data(bytes: &[u8]) -> Result<Self, anyhow::Error> {
         ensure!(
             bytes.len() == Self::CHUNKS * CHUNK_BYTES,
-            "Wrong bytes length for remove liquidity pubdata"
+            "Wrong bytes length for add liquidity pubdata"
         );
 
         let from_offset = 1;
-        let token_id_offset = from_offset + ACCOUNT_ID_BIT_WIDTH / 8;
-        let to_offset = token_id_offset + TOKEN_BIT_WIDTH / 8;
-        let amount_offset = to_offset + ACCOUNT_ID_BIT_WIDTH / 8;
-        let fee_offset =
-            amount_offset + (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8;
+        let to_offset = from_offset + ACCOUNT_ID_BIT_WIDTH / 8;
+        let liquidity_id_offset = to_offset + ACCOUNT_ID_BIT_WIDTH / 8;
+        let token_a_offset = liquidity_id_offset + TOKEN_BIT_WIDTH / 8;
+        let token_b_offset = token_a_offset + TOKEN_BIT_WIDTH / 8;
+        let amount_a_desired_offset = token_b_offset + TOKEN_BIT_WIDTH / 8;
+        let amount_b_desired_offset =
+            amount_a_desired_offset + (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8;
+        let amount_a_min_offset =
+            amount_b_desired_offset + (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8;
+        let amount_b_min_offset =
+            amount_a_min_offset + (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8;
+        let fee_a_offset =
+            amount_b_min_offset + (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8;
+        let fee_b_offset = fee_a_offset + (FEE_EXPONENT_BIT_WIDTH + FEE_MANTISSA_BIT_WIDTH) / 8;
+        let p_low_offset = fee_b_offset + (FEE_EXPONENT_BIT_WIDTH + FEE_MANTISSA_BIT_WIDTH) / 8;
+        let p_high_offset = p_low_offset + 8;
 
-        // let from_address = Address::zero(); // From pubdata its unknown
-        let to_address = Address::zero(); // From pubdata its unknown
-        let liquidity_id =
-            u16::from_bytes(&bytes[token_id_offset..token_id_offset + TOKEN_BIT_WIDTH / 8])
-                .ok_or_else(|| {
-                    format_err!("Cant get liquidity id from remove liquidity pubdata")
-                })?;
-        let token =
-            u16::from_bytes(&bytes[token_id_offset..token_id_offset + TOKEN_BIT_WIDTH / 8])
-                .ok_or_else(|| {
-                    format_err!("Cant get liquidity id from remove liquidity pubdata")
-                })?;
+        let to_address = Address::zero(); // From pubdata it is unknown
+        let liquidity_id = u16::from_bytes(
+            &bytes[liquidity_id_offset..liquidity_id_offset + TOKEN_BIT_WIDTH / 8],
+        )
+        .ok_or_else(|| format_err!("Cant get liquidity id from add liquidity pubdata"))?;
+        let token_a = u16::from_bytes(&bytes[token_a_offset..token_a_offset + TOKEN_BIT_WIDTH / 8])
+            .ok_or_else(|| format_err!("Cant get token a from add liquidity pubdata"))?;
+        let token_b = u16::from_bytes(&bytes[token_b_offset..token_b_offset + TOKEN_BIT_WIDTH / 8])
+            .ok_or_else(|| format_err!("Cant get token b from add liquidity pubdata"))?;
         let amount_a_desired = unpack_token_amount(
-            &bytes[amount_offset
-                ..amount_offset + (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8],
+            &bytes[amount_a_desired_offset
+                ..amount_a_desired_offset + (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8],
         )
-        .ok_or_else(|| format_err!("Cant get amount_a_desired from remove liquidity pubdata"))?;
+        .ok_or_else(|| format_err!("Cant get amount_a_desired from add liquidity pubdata"))?;
         let amount_b_desired = unpack_token_amount(
-            &bytes[amount_offset
-                ..amount_offset + (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8],
+            &bytes[amount_b_desired_offset
+                ..amount_b_desired_offset + (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8],
         )
-        .ok_or_else(|| format_err!("Cant get amount_b_desired from remove liquidity pubdata"))?;
+        .ok_or_else(|| format_err!("Cant get amount_b_desired from add liquidity pubdata"))?;
         let amount_a_min = unpack_token_amount(
-            &bytes[amount_offset
-                ..amount_offset + (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8],
+            &bytes[amount_a_min_offset
+                ..amount_a_min_offset + (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8],
         )
-        .ok_or_else(|| format_err!("Cant get amount_a_min from remove liquidity pubdata"))?;
+        .ok_or_else(|| format_err!("Cant get amount_a_min from add liquidity pubdata"))?;
         let amount_b_min = unpack_token_amount(
-            &bytes[amount_offset
-                ..amount_offset + (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8],
+            &bytes[amount_b_min_offset
+                ..amount_b_min_offset + (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8],
         )
-        .ok_or_else(|| format_err!("Cant get amount_b_min from remove liquidity pubdata"))?;
+        .ok_or_else(|| format_err!("Cant get amount_b_min from add liquidity pubdata"))?;
         let fee_a = unpack_fee_amount(
-            &bytes[fee_offset..fee_offset + (FEE_EXPONENT_BIT_WIDTH + FEE_MANTISSA_BIT_WIDTH) / 8],
+            &bytes[fee_a_offset..fee_a_offset + (FEE_EXPONENT_BIT_WIDTH + FEE_MANTISSA_BIT_WIDTH) / 8],
         )
-        .ok_or_else(|| format_err!("Cant get fee a from remove liquidity pubdata"))?;
+        .ok_or_else(|| format_err!("Cant get fee a from add liquidity pubdata"))?;
         let fee_b = unpack_fee_amount(
-            &bytes[fee_offset..fee_offset + (FEE_EXPONENT_BIT_WIDTH + FEE_MANTISSA_BIT_WIDTH) / 8],
+            &bytes[fee_b_offset..fee_b_offset + (FEE_EXPONENT_BIT_WIDTH + FEE_MANTISSA_BIT_WIDTH) / 8],
         )
-        .ok_or_else(|| format_err!("Cant get fee b from remove liquidity pubdata"))?;
+        .ok_or_else(|| format_err!("Cant get fee b from add liquidity pubdata"))?;
+        let p_low = u64::from_bytes(&bytes[p_low_offset..p_low_offset + 8])
+            .ok_or_else(|| format_err!("Cant get p_low from add liquidity pubdata"))?;
+        let p_high = u64::from_bytes(&bytes[p_high_offset..p_high_offset + 8])
+            .ok_or_else(|| format_err!("Cant get p_high from add liquidity pubdata"))?;
         let nonce = 0; // It is unknown from pubdata
         let from_id = u32::from_bytes(&bytes[from_offset..from_offset + ACCOUNT_ID_BIT_WIDTH / 8])
-            .ok_or_else(|| format_err!("Cant get from account id from remove liquidity pubdata"))?;
+            .ok_or_else(|| format_err!("Cant get from account id from add liquidity pubdata"))?;
         let to_id = u32::from_bytes(&bytes[to_offset..to_offset + ACCOUNT_ID_BIT_WIDTH / 8])
-            .ok_or_else(|| format_err!("Cant get to account id from remove liquidity pubdata"))?;
+            .ok_or_else(|| format_err!("Cant get to account id from add liquidity pubdata"))?;
         let time_range = Default::default();
 
         Ok(Self {
@@ -107,9 +124,12 @@ impl AddLiquidityOp {
                 amount_b_desired,
                 amount_a_min,
                 amount_b_min,
-                TokenId(token),
+                TokenId(token_a),
+                TokenId(token_b),
                 fee_a,
                 fee_b,
+                p_low,
+                p_high,
                 Nonce(nonce),
                 time_range,
                 None,
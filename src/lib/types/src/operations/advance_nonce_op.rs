@@ -0,0 +1,84 @@
+use crate::{helpers::{pack_fee_amount, unpack_fee_amount}, AccountId, AdvanceNonce, Nonce, TokenId};
+use anyhow::{ensure, format_err};
+use serde::{Deserialize, Serialize};
+use zkdpos_basic_types::{Address, H256};
+use zkdpos_crypto::params::{
+    ACCOUNT_ID_BIT_WIDTH, CHUNK_BYTES, FEE_EXPONENT_BIT_WIDTH, FEE_MANTISSA_BIT_WIDTH,
+    TOKEN_BIT_WIDTH,
+};
+use zkdpos_crypto::primitives::FromBytes;
+
+/// Advance-nonce operation: rotates `account_id`'s durable nonce from
+/// `tx.expected_durable_nonce` to `new_durable_nonce`. See [`AdvanceNonce`]
+/// for the durable-nonce mechanism itself, and
+/// [`ZkDposOp`](./operations/enum.ZkDposOp.html) for its place among other
+/// operations.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdvanceNonceOp {
+    pub tx: AdvanceNonce,
+    pub account_id: AccountId,
+    /// The durable nonce resolved at `create_op` time via
+    /// `next_durable_nonce(tx.expected_durable_nonce, current_block_number)`.
+    pub new_durable_nonce: H256,
+}
+
+impl AdvanceNonceOp {
+    pub const CHUNKS: usize = 5;
+    pub const OP_CODE: u8 = 0x14;
+
+    pub(crate) fn get_public_data(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.push(Self::OP_CODE); // opcode
+        data.extend_from_slice(&self.account_id.to_be_bytes());
+        data.extend_from_slice(self.new_durable_nonce.as_bytes());
+        data.extend_from_slice(&self.tx.fee_token.to_be_bytes());
+        data.extend_from_slice(&pack_fee_amount(&self.tx.fee));
+        data.resize(Self::CHUNKS * CHUNK_BYTES, 0x00);
+        data
+    }
+
+    pub fn from_public_data(bytes: &[u8]) -> Result<Self, anyhow::Error> {
+        ensure!(
+            bytes.len() == Self::CHUNKS * CHUNK_BYTES,
+            "Wrong bytes length for advance nonce pubdata"
+        );
+
+        let account_id_offset = 1;
+        let new_durable_nonce_offset = account_id_offset + ACCOUNT_ID_BIT_WIDTH / 8;
+        let fee_token_offset = new_durable_nonce_offset + 32;
+        let fee_offset = fee_token_offset + TOKEN_BIT_WIDTH / 8;
+        let end_offset = fee_offset + (FEE_EXPONENT_BIT_WIDTH + FEE_MANTISSA_BIT_WIDTH) / 8;
+
+        let account_id = u32::from_bytes(&bytes[account_id_offset..new_durable_nonce_offset])
+            .ok_or_else(|| format_err!("Cant get account id from advance nonce pubdata"))?;
+        let new_durable_nonce =
+            H256::from_slice(&bytes[new_durable_nonce_offset..fee_token_offset]);
+        let fee_token = u16::from_bytes(&bytes[fee_token_offset..fee_offset])
+            .ok_or_else(|| format_err!("Cant get fee token from advance nonce pubdata"))?;
+        let fee = unpack_fee_amount(&bytes[fee_offset..end_offset])
+            .ok_or_else(|| format_err!("Cant get fee from advance nonce pubdata"))?;
+
+        // Neither the expected (pre-rotation) durable nonce nor the sequential
+        // nonce authorizing the tx are committed to this fixed-offset pubdata,
+        // only the resolved `new_durable_nonce` outcome - same convention as
+        // `RangeSettleCompleteOp` only committing the resolved payout split.
+        Ok(Self {
+            tx: AdvanceNonce::new(
+                AccountId(account_id),
+                Address::zero(), // From pubdata it is unknown
+                H256::zero(),    // The expected (pre-rotation) value isn't committed to pubdata
+                TokenId(fee_token),
+                fee,
+                Nonce(0), // It is unknown from pubdata
+                Default::default(),
+                None,
+            ),
+            account_id: AccountId(account_id),
+            new_durable_nonce,
+        })
+    }
+
+    pub fn get_updated_account_ids(&self) -> Vec<AccountId> {
+        vec![self.account_id]
+    }
+}
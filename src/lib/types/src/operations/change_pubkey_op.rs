@@ -15,10 +15,15 @@ use zkdpos_crypto::primitives::FromBytes;
 pub struct ChangePubKeyOp {
     pub tx: ChangePubKey,
     pub account_id: AccountId,
+    /// `tx.signer_set.threshold` if this op installs a threshold-multisig
+    /// signer set, or `0` for an ordinary single-key `ChangePubKey`. Folded
+    /// into the pubdata so the commit circuit can enforce it without
+    /// re-deriving it from `tx.signer_set`.
+    pub threshold: u8,
 }
 
 impl ChangePubKeyOp {
-    pub const CHUNKS: usize = 6;
+    pub const CHUNKS: usize = 7;
     pub const OP_CODE: u8 = 0x07;
 
     pub fn get_public_data(&self) -> Vec<u8> {
@@ -30,6 +35,7 @@ impl ChangePubKeyOp {
         data.extend_from_slice(&self.tx.nonce.to_be_bytes());
         data.extend_from_slice(&self.tx.fee_token.to_be_bytes());
         data.extend_from_slice(&pack_fee_amount(&self.tx.fee));
+        data.push(self.threshold);
         data.resize(Self::CHUNKS * CHUNK_BYTES, 0x00);
         data
     }
@@ -54,7 +60,9 @@ impl ChangePubKeyOp {
         let nonce_offset = account_offset + ADDRESS_WIDTH / 8;
         let fee_token_offset = nonce_offset + NONCE_BIT_WIDTH / 8;
         let fee_offset = fee_token_offset + TOKEN_BIT_WIDTH / 8;
-        let end = fee_offset + (FEE_EXPONENT_BIT_WIDTH + FEE_MANTISSA_BIT_WIDTH) / 8;
+        let fee_end = fee_offset + (FEE_EXPONENT_BIT_WIDTH + FEE_MANTISSA_BIT_WIDTH) / 8;
+        let threshold_offset = fee_end;
+        let end = threshold_offset + 1;
 
         ensure!(
             bytes.len() >= end,
@@ -69,8 +77,9 @@ impl ChangePubKeyOp {
             .ok_or_else(|| format_err!("Change pubkey offchain, fail to get nonce"))?;
         let fee_token = u16::from_bytes(&bytes[fee_token_offset..fee_offset])
             .ok_or_else(|| format_err!("Change pubkey offchain, fail to get fee token ID"))?;
-        let fee = unpack_fee_amount(&bytes[fee_offset..end])
+        let fee = unpack_fee_amount(&bytes[fee_offset..fee_end])
             .ok_or_else(|| format_err!("Change pubkey offchain, fail to get fee"))?;
+        let threshold = bytes[threshold_offset];
 
         Ok(ChangePubKeyOp {
             tx: ChangePubKey::new(
@@ -85,6 +94,7 @@ impl ChangePubKeyOp {
                 None,
             ),
             account_id: AccountId(account_id),
+            threshold,
         })
     }
 
@@ -3,7 +3,7 @@ use crate::Close;
 use crate::{AccountId, Address, Nonce};
 use anyhow::{ensure, format_err};
 use serde::{Deserialize, Serialize};
-use zkdpos_crypto::params::{ACCOUNT_ID_BIT_WIDTH, CHUNK_BYTES};
+use zkdpos_crypto::params::{ACCOUNT_ID_BIT_WIDTH, ATP_ADDRESS_BIT_WIDTH, CHUNK_BYTES};
 use zkdpos_crypto::primitives::FromBytes;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,13 +13,14 @@ pub struct CloseOp {
 }
 
 impl CloseOp {
-    pub const CHUNKS: usize = 1;
+    pub const CHUNKS: usize = 2;
     pub const OP_CODE: u8 = 0x04;
 
     pub(crate) fn get_public_data(&self) -> Vec<u8> {
         let mut data = Vec::new();
         data.push(Self::OP_CODE); // opcode
         data.extend_from_slice(&self.account_id.to_be_bytes());
+        data.extend_from_slice(self.tx.account.as_bytes());
         data.resize(Self::CHUNKS * CHUNK_BYTES, 0x00);
         data
     }
@@ -31,11 +32,14 @@ impl CloseOp {
         );
 
         let account_id_offset = 1;
+        let account_address_offset = account_id_offset + ACCOUNT_ID_BIT_WIDTH / 8;
         let account_id = u32::from_bytes(
             &bytes[account_id_offset..account_id_offset + ACCOUNT_ID_BIT_WIDTH / 8],
         )
         .ok_or_else(|| format_err!("Cant get from account id from close pubdata"))?;
-        let account_address = Address::zero(); // From pubdata it is unknown
+        let account_address = Address::from_slice(
+            &bytes[account_address_offset..account_address_offset + ATP_ADDRESS_BIT_WIDTH / 8],
+        );
         let nonce = 0; // From pubdata it is unknown
         let signature = TxSignature::default(); // From pubdata it is unknown
         let time_range = Default::default();
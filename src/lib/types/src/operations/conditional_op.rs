@@ -0,0 +1,99 @@
+use crate::priority_ops::ConditionalPredicate;
+use crate::{AccountId, Address, Conditional, TokenId};
+use anyhow::{ensure, format_err};
+use serde::{Deserialize, Serialize};
+use zkdpos_crypto::params::{
+    ACCOUNT_ID_BIT_WIDTH, AMOUNT_EXPONENT_BIT_WIDTH, AMOUNT_MANTISSA_BIT_WIDTH, CHUNK_BYTES,
+    FEE_EXPONENT_BIT_WIDTH, FEE_MANTISSA_BIT_WIDTH, FR_ADDRESS_LEN, TOKEN_BIT_WIDTH,
+};
+use zkdpos_crypto::primitives::FromBytes;
+
+use crate::helpers::{pack_fee_amount, pack_token_amount, unpack_fee_amount, unpack_token_amount};
+
+/// Commits the escrow-lock phase of a `Conditional` priority operation: `amount + fee`
+/// has already been moved out of `from` into the `pending` escrow sub-account by
+/// `apply_conditional_op`; settlement (crediting `to` or refunding `from`) happens
+/// later via a `ConditionalSettle` transaction. For details, see the documentation
+/// of [`ZkDposOp`](./operations/enum.ZkDposOp.html).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConditionalOp {
+    pub priority_op: Conditional,
+    pub from: AccountId,
+    pub to: AccountId,
+    pub pending: AccountId,
+}
+
+impl ConditionalOp {
+    pub const CHUNKS: usize = 6;
+    pub const OP_CODE: u8 = 0x10;
+
+    pub(crate) fn get_public_data(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.push(Self::OP_CODE); // opcode
+        data.extend_from_slice(&self.from.to_be_bytes());
+        data.extend_from_slice(&self.to.to_be_bytes());
+        data.extend_from_slice(&self.pending.to_be_bytes());
+        data.extend_from_slice(&self.priority_op.token.to_be_bytes());
+        data.extend_from_slice(&pack_token_amount(&self.priority_op.amount));
+        data.extend_from_slice(&pack_fee_amount(&self.priority_op.fee));
+        data.extend_from_slice(&self.priority_op.predicate.to_be_bytes());
+        data.resize(Self::CHUNKS * CHUNK_BYTES, 0x00);
+        data
+    }
+
+    pub fn from_public_data(bytes: &[u8]) -> Result<Self, anyhow::Error> {
+        ensure!(
+            bytes.len() == Self::CHUNKS * CHUNK_BYTES,
+            "Wrong bytes length for conditional pubdata"
+        );
+
+        let from_offset = 1;
+        let to_offset = from_offset + ACCOUNT_ID_BIT_WIDTH / 8;
+        let pending_offset = to_offset + ACCOUNT_ID_BIT_WIDTH / 8;
+        let token_offset = pending_offset + ACCOUNT_ID_BIT_WIDTH / 8;
+        let amount_offset = token_offset + TOKEN_BIT_WIDTH / 8;
+        let fee_offset =
+            amount_offset + (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8;
+        let predicate_kind_offset =
+            fee_offset + (FEE_EXPONENT_BIT_WIDTH + FEE_MANTISSA_BIT_WIDTH) / 8;
+        let predicate_payload_offset = predicate_kind_offset + 1;
+
+        let from_id = u32::from_bytes(&bytes[from_offset..to_offset])
+            .ok_or_else(|| format_err!("Cant get from account id from conditional pubdata"))?;
+        let to_id = u32::from_bytes(&bytes[to_offset..pending_offset])
+            .ok_or_else(|| format_err!("Cant get to account id from conditional pubdata"))?;
+        let pending_id = u32::from_bytes(&bytes[pending_offset..token_offset])
+            .ok_or_else(|| format_err!("Cant get pending account id from conditional pubdata"))?;
+        let token = u16::from_bytes(&bytes[token_offset..amount_offset])
+            .ok_or_else(|| format_err!("Cant get token id from conditional pubdata"))?;
+        let amount = unpack_token_amount(&bytes[amount_offset..fee_offset])
+            .ok_or_else(|| format_err!("Cant get amount from conditional pubdata"))?;
+        let fee = unpack_fee_amount(&bytes[fee_offset..predicate_kind_offset])
+            .ok_or_else(|| format_err!("Cant get fee from conditional pubdata"))?;
+        let predicate = ConditionalPredicate::from_bytes(
+            bytes[predicate_kind_offset],
+            &bytes[predicate_payload_offset..predicate_payload_offset + FR_ADDRESS_LEN],
+        )?;
+
+        let from_address = Address::zero(); // From pubdata it is unknown
+        let to_address = Address::zero(); // From pubdata it is unknown
+
+        Ok(Self {
+            priority_op: Conditional {
+                from: from_address,
+                to: to_address,
+                token: TokenId(token),
+                amount,
+                fee,
+                predicate,
+            },
+            from: AccountId(from_id),
+            to: AccountId(to_id),
+            pending: AccountId(pending_id),
+        })
+    }
+
+    pub fn get_updated_account_ids(&self) -> Vec<AccountId> {
+        vec![self.from, self.to, self.pending]
+    }
+}
@@ -0,0 +1,110 @@
+use crate::{
+    helpers::{pack_fee_amount, pack_token_amount, unpack_fee_amount, unpack_token_amount},
+    priority_ops::ConditionalPredicate,
+    ConditionalSettle,
+};
+use crate::{AccountId, Address, Nonce, TokenId};
+use anyhow::{ensure, format_err};
+use serde::{Deserialize, Serialize};
+use zkdpos_crypto::params::{
+    ACCOUNT_ID_BIT_WIDTH, AMOUNT_EXPONENT_BIT_WIDTH, AMOUNT_MANTISSA_BIT_WIDTH, CHUNK_BYTES,
+    FEE_EXPONENT_BIT_WIDTH, FEE_MANTISSA_BIT_WIDTH, TOKEN_BIT_WIDTH,
+};
+use zkdpos_crypto::primitives::FromBytes;
+
+/// Conditional settle operation. For details, see the documentation of
+/// [`ZkDposOp`](./operations/enum.ZkDposOp.html).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConditionalSettleOp {
+    pub tx: ConditionalSettle,
+    pub pending: AccountId,
+    /// The account credited by this op: `tx.to`'s account id if `released`,
+    /// `tx.from`'s account id otherwise (see the `ConditionalSettle` handler).
+    pub receiver: AccountId,
+    /// `true` if the escrow released to `receiver` because `tx.predicate` was
+    /// satisfied, `false` if it refunded because `tx.deadline_block` passed
+    /// with the predicate unmet.
+    pub released: bool,
+}
+
+impl ConditionalSettleOp {
+    pub const CHUNKS: usize = 6;
+    pub const OP_CODE: u8 = 0x11;
+
+    pub(crate) fn get_public_data(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.push(Self::OP_CODE); // opcode
+        data.extend_from_slice(&self.pending.to_be_bytes());
+        data.extend_from_slice(&self.receiver.to_be_bytes());
+        data.extend_from_slice(&self.tx.token.to_be_bytes());
+        data.extend_from_slice(&pack_token_amount(&self.tx.amount));
+        data.extend_from_slice(&pack_fee_amount(&self.tx.fee));
+        data.push(self.released as u8);
+        data.resize(Self::CHUNKS * CHUNK_BYTES, 0x00);
+        data
+    }
+
+    pub fn from_public_data(bytes: &[u8]) -> Result<Self, anyhow::Error> {
+        ensure!(
+            bytes.len() == Self::CHUNKS * CHUNK_BYTES,
+            "Wrong bytes length for conditional settle pubdata"
+        );
+
+        let pending_offset = 1;
+        let receiver_offset = pending_offset + ACCOUNT_ID_BIT_WIDTH / 8;
+        let token_offset = receiver_offset + ACCOUNT_ID_BIT_WIDTH / 8;
+        let amount_offset = token_offset + TOKEN_BIT_WIDTH / 8;
+        let fee_offset =
+            amount_offset + (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8;
+        let released_offset = fee_offset + (FEE_EXPONENT_BIT_WIDTH + FEE_MANTISSA_BIT_WIDTH) / 8;
+
+        let pending_id =
+            u32::from_bytes(&bytes[pending_offset..receiver_offset])
+                .ok_or_else(|| format_err!("Cant get pending account id from conditional settle pubdata"))?;
+        let receiver_id = u32::from_bytes(&bytes[receiver_offset..token_offset])
+            .ok_or_else(|| format_err!("Cant get receiver account id from conditional settle pubdata"))?;
+        let token = u16::from_bytes(&bytes[token_offset..amount_offset])
+            .ok_or_else(|| format_err!("Cant get token id from conditional settle pubdata"))?;
+        let amount = unpack_token_amount(&bytes[amount_offset..fee_offset])
+            .ok_or_else(|| format_err!("Cant get amount from conditional settle pubdata"))?;
+        let fee = unpack_fee_amount(&bytes[fee_offset..released_offset])
+            .ok_or_else(|| format_err!("Cant get fee from conditional settle pubdata"))?;
+        let released = bytes[released_offset] != 0;
+
+        // The predicate and the escrow's original `to`/`from` addresses
+        // aren't committed to this fixed-offset pubdata, only the resolved
+        // `receiver` and the `released` outcome are; same convention as
+        // `EscrowOp` and `ConditionalTransferOp` filling in "unknown from
+        // pubdata" fields with placeholders.
+        let to_address = Address::zero(); // From pubdata it is unknown
+        let from_address = Address::zero(); // From pubdata it is unknown
+        let predicate = ConditionalPredicate::After(0); // From pubdata it is unknown
+        let nonce = 0; // It is unknown from pubdata
+        let time_range = Default::default();
+
+        Ok(Self {
+            tx: ConditionalSettle::new(
+                AccountId(0), // The submitter isn't committed to settle pubdata
+                AccountId(pending_id),
+                to_address,
+                from_address,
+                TokenId(token),
+                amount,
+                fee,
+                predicate,
+                0, // It is unknown from pubdata
+                None,
+                Nonce(nonce),
+                time_range,
+                None,
+            ),
+            pending: AccountId(pending_id),
+            receiver: AccountId(receiver_id),
+            released,
+        })
+    }
+
+    pub fn get_updated_account_ids(&self) -> Vec<AccountId> {
+        vec![self.pending, self.receiver]
+    }
+}
@@ -0,0 +1,123 @@
+use crate::{
+    helpers::{pack_token_amount, unpack_token_amount},
+    ConditionalTransfer,
+};
+use crate::tx::PredicateNode;
+use crate::{AccountId, Address, Nonce, PubKeyHash, TokenId};
+use anyhow::{ensure, format_err};
+use num::BigUint;
+use serde::{Deserialize, Serialize};
+use zkdpos_crypto::params::{
+    ACCOUNT_ID_BIT_WIDTH, AMOUNT_EXPONENT_BIT_WIDTH, AMOUNT_MANTISSA_BIT_WIDTH, CHUNK_BYTES,
+    NEW_PUBKEY_HASH_WIDTH, TOKEN_BIT_WIDTH,
+};
+use zkdpos_crypto::primitives::FromBytes;
+
+/// Conditional transfer operation. For details, see the documentation of
+/// [`ZkDposOp`](./operations/enum.ZkDposOp.html).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConditionalTransferOp {
+    pub tx: ConditionalTransfer,
+    pub from: AccountId,
+    pub to: AccountId,
+    /// Bitmap over `tx.predicate.leaves()` as of the block this op is included
+    /// in; see `PredicateNode::satisfied_leaf_bitmap`.
+    pub satisfied_leaf_bitmap: u64,
+    /// Whether `satisfied_leaf_bitmap` made `tx.predicate` evaluate to `true`
+    /// and the escrowed funds were released to `to` by this op, as opposed to
+    /// remaining locked for a later resubmission.
+    pub released: bool,
+}
+
+impl ConditionalTransferOp {
+    pub const CHUNKS: usize = 6;
+    pub const OP_CODE: u8 = 0x0e;
+
+    pub(crate) fn get_public_data(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.push(Self::OP_CODE); // opcode
+        data.extend_from_slice(&self.from.to_be_bytes());
+        data.extend_from_slice(&self.to.to_be_bytes());
+        data.extend_from_slice(&self.tx.token.to_be_bytes());
+        data.extend_from_slice(&pack_token_amount(&self.tx.amount));
+        data.extend_from_slice(&self.tx.predicate.commitment().data);
+        data.extend_from_slice(&self.satisfied_leaf_bitmap.to_be_bytes());
+        data.push(self.released as u8);
+        data.resize(Self::CHUNKS * CHUNK_BYTES, 0x00);
+        data
+    }
+
+    pub fn from_public_data(bytes: &[u8]) -> Result<Self, anyhow::Error> {
+        ensure!(
+            bytes.len() == Self::CHUNKS * CHUNK_BYTES,
+            "Wrong bytes length for conditional transfer pubdata"
+        );
+
+        let from_offset = 1;
+        let to_offset = from_offset + ACCOUNT_ID_BIT_WIDTH / 8;
+        let token_offset = to_offset + ACCOUNT_ID_BIT_WIDTH / 8;
+        let amount_offset = token_offset + TOKEN_BIT_WIDTH / 8;
+        let commitment_offset =
+            amount_offset + (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8;
+        let bitmap_offset = commitment_offset + NEW_PUBKEY_HASH_WIDTH / 8;
+        let released_offset = bitmap_offset + 8;
+        let end = released_offset + 1;
+
+        let from_id = u32::from_bytes(&bytes[from_offset..from_offset + ACCOUNT_ID_BIT_WIDTH / 8])
+            .ok_or_else(|| format_err!("Cant get from account id from conditional transfer pubdata"))?;
+        let to_id = u32::from_bytes(&bytes[to_offset..to_offset + ACCOUNT_ID_BIT_WIDTH / 8])
+            .ok_or_else(|| format_err!("Cant get to account id from conditional transfer pubdata"))?;
+        let token = u16::from_bytes(&bytes[token_offset..token_offset + TOKEN_BIT_WIDTH / 8])
+            .ok_or_else(|| format_err!("Cant get token id from conditional transfer pubdata"))?;
+        let amount = unpack_token_amount(
+            &bytes[amount_offset
+                ..amount_offset + (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8],
+        )
+        .ok_or_else(|| format_err!("Cant get amount from conditional transfer pubdata"))?;
+        let commitment = PubKeyHash::from_bytes(&bytes[commitment_offset..bitmap_offset])?;
+        let satisfied_leaf_bitmap = u64::from_be_bytes(
+            bytes[bitmap_offset..released_offset]
+                .try_into()
+                .map_err(|_| format_err!("Cant get satisfied leaf bitmap from conditional transfer pubdata"))?,
+        );
+        let released = bytes[released_offset] != 0;
+        ensure!(bytes.len() >= end, "Conditional transfer pubdata too short");
+
+        // The predicate tree itself isn't committed to this fixed-offset
+        // pubdata, only its commitment (see `ConditionalTransferOp::get_public_data`),
+        // same as `EscrowOp` only commits `SpendingCondition::condition_type`.
+        // Restoring from L1 pubdata alone can't recover the tree, so it's
+        // filled in with a placeholder that reproduces the same commitment.
+        let predicate = PredicateNode::Approver(commitment);
+        let from_address = Address::zero(); // From pubdata it is unknown
+        let to_address = Address::zero(); // From pubdata it is unknown
+        let fee = BigUint::from(0u8); // Fee isn't committed to conditional transfer pubdata
+        let nonce = 0; // It is unknown from pubdata
+        let time_range = Default::default();
+
+        Ok(Self {
+            tx: ConditionalTransfer::new(
+                AccountId(from_id),
+                from_address,
+                to_address,
+                TokenId(token),
+                amount,
+                fee,
+                predicate,
+                Vec::new(),
+                Nonce(nonce),
+                None, // Durable-nonce binding (if any) isn't committed to pubdata
+                time_range,
+                None,
+            ),
+            from: AccountId(from_id),
+            to: AccountId(to_id),
+            satisfied_leaf_bitmap,
+            released,
+        })
+    }
+
+    pub fn get_updated_account_ids(&self) -> Vec<AccountId> {
+        vec![self.from, self.to]
+    }
+}
@@ -0,0 +1,122 @@
+use crate::{
+    helpers::{pack_token_amount, unpack_token_amount},
+    EscrowTransfer,
+};
+use crate::tx::SpendingCondition;
+use crate::{AccountId, Address, Nonce, TokenId};
+use anyhow::{ensure, format_err};
+use num::BigUint;
+use serde::{Deserialize, Serialize};
+use zkdpos_crypto::params::{
+    ACCOUNT_ID_BIT_WIDTH, AMOUNT_EXPONENT_BIT_WIDTH, AMOUNT_MANTISSA_BIT_WIDTH, ATP_ADDRESS_BIT_WIDTH,
+    CHUNK_BYTES, TOKEN_BIT_WIDTH,
+};
+use zkdpos_crypto::primitives::FromBytes;
+
+/// Escrow operation. For details, see the documentation of [`ZkDposOp`](./operations/enum.ZkDposOp.html).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EscrowOp {
+    pub tx: EscrowTransfer,
+    pub from: AccountId,
+    pub beneficiary: AccountId,
+    pub pending: AccountId,
+}
+
+impl EscrowOp {
+    pub const CHUNKS: usize = 5;
+    pub const OP_CODE: u8 = 0x0d;
+
+    pub(crate) fn get_public_data(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.push(Self::OP_CODE); // opcode
+        data.extend_from_slice(&self.from.to_be_bytes());
+        data.extend_from_slice(&self.beneficiary.to_be_bytes());
+        data.extend_from_slice(&self.pending.to_be_bytes());
+        data.extend_from_slice(&self.tx.token.to_be_bytes());
+        data.extend_from_slice(&pack_token_amount(&self.tx.amount));
+        data.push(self.tx.condition.condition_type());
+        data.extend_from_slice(self.tx.refund_to.as_bytes());
+        data.resize(Self::CHUNKS * CHUNK_BYTES, 0x00);
+        data
+    }
+
+    pub fn from_public_data(bytes: &[u8]) -> Result<Self, anyhow::Error> {
+        ensure!(
+            bytes.len() == Self::CHUNKS * CHUNK_BYTES,
+            "Wrong bytes length for escrow pubdata"
+        );
+
+        let from_offset = 1;
+        let beneficiary_offset = from_offset + ACCOUNT_ID_BIT_WIDTH / 8;
+        let pending_offset = beneficiary_offset + ACCOUNT_ID_BIT_WIDTH / 8;
+        let token_offset = pending_offset + ACCOUNT_ID_BIT_WIDTH / 8;
+        let amount_offset = token_offset + TOKEN_BIT_WIDTH / 8;
+        let condition_type_offset =
+            amount_offset + (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8;
+        let refund_to_offset = condition_type_offset + 1;
+
+        let from_id = u32::from_bytes(&bytes[from_offset..from_offset + ACCOUNT_ID_BIT_WIDTH / 8])
+            .ok_or_else(|| format_err!("Cant get from account id from escrow pubdata"))?;
+        let beneficiary_id = u32::from_bytes(
+            &bytes[beneficiary_offset..beneficiary_offset + ACCOUNT_ID_BIT_WIDTH / 8],
+        )
+        .ok_or_else(|| format_err!("Cant get beneficiary account id from escrow pubdata"))?;
+        let pending_id =
+            u32::from_bytes(&bytes[pending_offset..pending_offset + ACCOUNT_ID_BIT_WIDTH / 8])
+                .ok_or_else(|| format_err!("Cant get pending account id from escrow pubdata"))?;
+        let token = u16::from_bytes(&bytes[token_offset..token_offset + TOKEN_BIT_WIDTH / 8])
+            .ok_or_else(|| format_err!("Cant get token id from escrow pubdata"))?;
+        let amount = unpack_token_amount(
+            &bytes[amount_offset
+                ..amount_offset + (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8],
+        )
+        .ok_or_else(|| format_err!("Cant get amount from escrow pubdata"))?;
+        let refund_to = Address::from_slice(
+            &bytes[refund_to_offset..refund_to_offset + ATP_ADDRESS_BIT_WIDTH / 8],
+        );
+
+        // The time range / witness address a `Time`, `Witness`, `And` or `Or`
+        // condition carries aren't committed to this fixed-offset pubdata (see
+        // `EscrowTransfer::get_bytes`, which is where they're actually bound to
+        // the signature); only the condition's shape is. Restoring from L1
+        // pubdata alone can recognize *which* predicate applies but not its
+        // parameters, so they're filled in with defaults here, same as the
+        // other "unknown from pubdata" fields below.
+        let condition = match bytes[condition_type_offset] {
+            0 => SpendingCondition::Time(Default::default()),
+            1 => SpendingCondition::Witness(Address::zero()),
+            2 => SpendingCondition::And(Default::default(), Address::zero()),
+            3 => SpendingCondition::Or(Default::default(), Address::zero()),
+            other => return Err(format_err!("Unknown escrow condition type: {}", other)),
+        };
+
+        let from_address = Address::zero(); // From pubdata it is unknown
+        let beneficiary_address = Address::zero(); // From pubdata it is unknown
+        let fee = BigUint::from(0u8); // Fee isn't committed to escrow pubdata
+        let nonce = 0; // It is unknown from pubdata
+        let time_range = Default::default();
+
+        Ok(Self {
+            tx: EscrowTransfer::new(
+                AccountId(from_id),
+                from_address,
+                beneficiary_address,
+                refund_to,
+                TokenId(token),
+                amount,
+                fee,
+                condition,
+                Nonce(nonce),
+                time_range,
+                None,
+            ),
+            from: AccountId(from_id),
+            beneficiary: AccountId(beneficiary_id),
+            pending: AccountId(pending_id),
+        })
+    }
+
+    pub fn get_updated_account_ids(&self) -> Vec<AccountId> {
+        vec![self.from, self.beneficiary, self.pending]
+    }
+}
@@ -17,10 +17,18 @@ pub struct ExchangeOp {
     pub tx: Exchange,
     pub from: AccountId,
     pub to: AccountId,
+    /// The price attested by `tx.price_attestation`'s oracle at the time this op was
+    /// created, or `0` if `tx.price_attestation` is `None`. Folded into the pubdata so
+    /// the commit circuit can enforce the price bound without re-deriving it from the
+    /// (unverified, off-circuit) attestation.
+    pub attested_price: u64,
+    /// `true` if `tx.price_attestation` was present and verified `Ok` against the
+    /// operator's `PriceOracleConfig` when this op was created.
+    pub price_valid: bool,
 }
 
 impl ExchangeOp {
-    pub const CHUNKS: usize = 2;
+    pub const CHUNKS: usize = 4;
     pub const OP_CODE: u8 = 0x05;
 
     pub(crate) fn get_public_data(&self) -> Vec<u8> {
@@ -33,6 +41,8 @@ impl ExchangeOp {
         data.extend_from_slice(&pack_token_amount(&self.tx.amount_b));
         data.extend_from_slice(&pack_fee_amount(&self.tx.price));
         data.extend_from_slice(&pack_fee_amount(&self.tx.fee));
+        data.extend_from_slice(&self.attested_price.to_be_bytes());
+        data.push(self.price_valid as u8);
         data.resize(Self::CHUNKS * CHUNK_BYTES, 0x00);
         data
     }
@@ -49,6 +59,9 @@ impl ExchangeOp {
         let amount_offset = to_offset + ACCOUNT_ID_BIT_WIDTH / 8;
         let fee_offset =
             amount_offset + (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8;
+        let attested_price_offset =
+            fee_offset + (FEE_EXPONENT_BIT_WIDTH + FEE_MANTISSA_BIT_WIDTH) / 8;
+        let price_valid_offset = attested_price_offset + 8;
 
         let from_address = Address::zero(); // From pubdata its unknown
         let token_a = u16::from_bytes(&bytes[token_id_offset..token_id_offset + TOKEN_BIT_WIDTH / 8])
@@ -72,6 +85,9 @@ impl ExchangeOp {
             &bytes[fee_offset..fee_offset + (FEE_EXPONENT_BIT_WIDTH + FEE_MANTISSA_BIT_WIDTH) / 8],
         )
         .ok_or_else(|| format_err!("Cant get fee from exchange pubdata"))?;
+        let attested_price = u64::from_bytes(&bytes[attested_price_offset..attested_price_offset + 8])
+            .ok_or_else(|| format_err!("Cant get attested price from exchange pubdata"))?;
+        let price_valid = bytes[price_valid_offset] != 0;
         let nonce = 0; // It is unknown from pubdata
         let from_id = u32::from_bytes(&bytes[from_offset..from_offset + ACCOUNT_ID_BIT_WIDTH / 8])
             .ok_or_else(|| format_err!("Cant get from account id from exchange pubdata"))?;
@@ -89,12 +105,17 @@ impl ExchangeOp {
                 amount_b,
                 price,
                 fee,
+                None,
+                None,
                 Nonce(nonce),
                 time_range,
                 None,
+                0, // It is unknown from pubdata
             ),
             from: AccountId(from_id),
             to: AccountId(to_id),
+            attested_price,
+            price_valid,
         })
     }
 
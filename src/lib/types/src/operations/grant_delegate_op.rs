@@ -0,0 +1,76 @@
+use crate::{
+    helpers::{pack_fee_amount, unpack_fee_amount},
+    AccountId, GrantDelegate, Nonce, PubKeyHash, TokenId,
+};
+use anyhow::{ensure, format_err};
+use serde::{Deserialize, Serialize};
+use zkdpos_crypto::params::{
+    ACCOUNT_ID_BIT_WIDTH, CHUNK_BYTES, FEE_EXPONENT_BIT_WIDTH, FEE_MANTISSA_BIT_WIDTH,
+    NEW_PUBKEY_HASH_WIDTH, TOKEN_BIT_WIDTH,
+};
+use zkdpos_crypto::primitives::FromBytes;
+
+/// Grant-delegate operation: installs (or, when `delegate` is the default
+/// `PubKeyHash`, revokes) `account_id`'s delegated signer. See
+/// [`GrantDelegate`] for the delegation mechanism itself, and
+/// [`ZkDposOp`](./operations/enum.ZkDposOp.html) for its place among other
+/// operations.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrantDelegateOp {
+    pub tx: GrantDelegate,
+    pub account_id: AccountId,
+}
+
+impl GrantDelegateOp {
+    pub const CHUNKS: usize = 3;
+    pub const OP_CODE: u8 = 0x15;
+
+    pub(crate) fn get_public_data(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.push(Self::OP_CODE); // opcode
+        data.extend_from_slice(&self.account_id.to_be_bytes());
+        data.extend_from_slice(&self.tx.delegate.data);
+        data.extend_from_slice(&self.tx.fee_token.to_be_bytes());
+        data.extend_from_slice(&pack_fee_amount(&self.tx.fee));
+        data.resize(Self::CHUNKS * CHUNK_BYTES, 0x00);
+        data
+    }
+
+    pub fn from_public_data(bytes: &[u8]) -> Result<Self, anyhow::Error> {
+        ensure!(
+            bytes.len() == Self::CHUNKS * CHUNK_BYTES,
+            "Wrong bytes length for grant delegate pubdata"
+        );
+
+        let account_id_offset = 1;
+        let delegate_offset = account_id_offset + ACCOUNT_ID_BIT_WIDTH / 8;
+        let fee_token_offset = delegate_offset + NEW_PUBKEY_HASH_WIDTH / 8;
+        let fee_offset = fee_token_offset + TOKEN_BIT_WIDTH / 8;
+        let end_offset = fee_offset + (FEE_EXPONENT_BIT_WIDTH + FEE_MANTISSA_BIT_WIDTH) / 8;
+
+        let account_id = u32::from_bytes(&bytes[account_id_offset..delegate_offset])
+            .ok_or_else(|| format_err!("Cant get account id from grant delegate pubdata"))?;
+        let delegate = PubKeyHash::from_bytes(&bytes[delegate_offset..fee_token_offset])?;
+        let fee_token = u16::from_bytes(&bytes[fee_token_offset..fee_offset])
+            .ok_or_else(|| format_err!("Cant get fee token from grant delegate pubdata"))?;
+        let fee = unpack_fee_amount(&bytes[fee_offset..end_offset])
+            .ok_or_else(|| format_err!("Cant get fee from grant delegate pubdata"))?;
+
+        Ok(Self {
+            tx: GrantDelegate::new(
+                AccountId(account_id),
+                delegate,
+                TokenId(fee_token),
+                fee,
+                Nonce(0), // It is unknown from pubdata
+                Default::default(),
+                None,
+            ),
+            account_id: AccountId(account_id),
+        })
+    }
+
+    pub fn get_updated_account_ids(&self) -> Vec<AccountId> {
+        vec![self.account_id]
+    }
+}
@@ -6,26 +6,45 @@ use anyhow::format_err;
 use serde::{Deserialize, Serialize};
 use zkdpos_crypto::params::CHUNK_BYTES;
 
+mod advance_nonce_op;
+mod grant_delegate_op;
 mod change_pubkey_op;
 mod close_op;
+mod conditional_op;
+mod conditional_settle_op;
+mod conditional_transfer_op;
 mod deposit_op;
+mod escrow_op;
 mod forced_exit;
 mod full_exit_op;
 mod noop_op;
+mod order_match_op;
+mod range_settle_op;
+mod range_settle_complete_op;
 mod transfer_op;
 mod transfer_to_new_op;
 mod withdraw_op;
 mod exchange_op;
 mod add_liquidity_op;
 mod remove_liquidity_op;
+mod swap_op;
 
 
 #[doc(hidden)]
 pub use self::close_op::CloseOp;
 pub use self::{
-    change_pubkey_op::ChangePubKeyOp, deposit_op::DepositOp, forced_exit::ForcedExitOp,
-    full_exit_op::FullExitOp, noop_op::NoopOp, transfer_op::TransferOp, exchange_op::ExchangeOp,
-    transfer_to_new_op::TransferToNewOp, withdraw_op::WithdrawOp, add_liquidity_op::AddLiquidityOp, remove_liquidity_op::RemoveLiquidityOp
+    advance_nonce_op::AdvanceNonceOp,
+    grant_delegate_op::GrantDelegateOp,
+    change_pubkey_op::ChangePubKeyOp, conditional_op::ConditionalOp,
+    conditional_settle_op::ConditionalSettleOp,
+    conditional_transfer_op::ConditionalTransferOp,
+    deposit_op::DepositOp, escrow_op::EscrowOp,
+    forced_exit::ForcedExitOp,
+    full_exit_op::FullExitOp, noop_op::NoopOp, order_match_op::OrderMatchOp,
+    range_settle_op::RangeSettleOp, range_settle_complete_op::RangeSettleCompleteOp,
+    transfer_op::TransferOp, exchange_op::ExchangeOp,
+    transfer_to_new_op::TransferToNewOp, withdraw_op::WithdrawOp, add_liquidity_op::AddLiquidityOp,
+    remove_liquidity_op::RemoveLiquidityOp, swap_op::SwapOp,
 };
 use zkdpos_basic_types::AccountId;
 
@@ -50,6 +69,22 @@ pub enum ZkDposOp {
     Exchange(Box<ExchangeOp>),
     AddLiquidity(Box<AddLiquidityOp>),
     RemoveLiquidity(Box<RemoveLiquidityOp>),
+    Swap(Box<SwapOp>),
+    Escrow(Box<EscrowOp>),
+    ConditionalTransfer(Box<ConditionalTransferOp>),
+    OrderMatch(Box<OrderMatchOp>),
+    /// Escrow-lock phase of a `Conditional` priority operation. See [`ConditionalOp`].
+    Conditional(Box<ConditionalOp>),
+    /// Settlement phase of a `Conditional` priority operation. See [`ConditionalSettleOp`].
+    ConditionalSettle(Box<ConditionalSettleOp>),
+    /// Escrow-lock phase of a `RangeSettle` priority operation. See [`RangeSettleOp`].
+    RangeSettle(Box<RangeSettleOp>),
+    /// Settlement phase of a `RangeSettle` priority operation. See [`RangeSettleCompleteOp`].
+    RangeSettleComplete(Box<RangeSettleCompleteOp>),
+    /// Rotates an account's durable nonce. See [`AdvanceNonceOp`].
+    AdvanceNonce(Box<AdvanceNonceOp>),
+    /// Installs or revokes an account's delegated signer. See [`GrantDelegateOp`].
+    GrantDelegate(Box<GrantDelegateOp>),
 }
 
 impl ZkDposOp {
@@ -65,9 +100,19 @@ impl ZkDposOp {
             ZkDposOp::Exchange(_) => ExchangeOp::CHUNKS,
             ZkDposOp::AddLiquidity(_) => AddLiquidityOp::CHUNKS,
             ZkDposOp::RemoveLiquidity(_) => RemoveLiquidityOp::CHUNKS,
+            ZkDposOp::Swap(_) => SwapOp::CHUNKS,
+            ZkDposOp::Escrow(_) => EscrowOp::CHUNKS,
+            ZkDposOp::ConditionalTransfer(_) => ConditionalTransferOp::CHUNKS,
+            ZkDposOp::OrderMatch(_) => OrderMatchOp::CHUNKS,
             ZkDposOp::FullExit(_) => FullExitOp::CHUNKS,
             ZkDposOp::ChangePubKeyOffchain(_) => ChangePubKeyOp::CHUNKS,
             ZkDposOp::ForcedExit(_) => ForcedExitOp::CHUNKS,
+            ZkDposOp::Conditional(_) => ConditionalOp::CHUNKS,
+            ZkDposOp::ConditionalSettle(_) => ConditionalSettleOp::CHUNKS,
+            ZkDposOp::RangeSettle(_) => RangeSettleOp::CHUNKS,
+            ZkDposOp::RangeSettleComplete(_) => RangeSettleCompleteOp::CHUNKS,
+            ZkDposOp::AdvanceNonce(_) => AdvanceNonceOp::CHUNKS,
+            ZkDposOp::GrantDelegate(_) => GrantDelegateOp::CHUNKS,
         }
     }
 
@@ -83,9 +128,19 @@ impl ZkDposOp {
             ZkDposOp::Exchange(op) => op.get_public_data(),
             ZkDposOp::AddLiquidity(op) => op.get_public_data(),
             ZkDposOp::RemoveLiquidity(op) => op.get_public_data(),
+            ZkDposOp::Swap(op) => op.get_public_data(),
+            ZkDposOp::Escrow(op) => op.get_public_data(),
+            ZkDposOp::ConditionalTransfer(op) => op.get_public_data(),
+            ZkDposOp::OrderMatch(op) => op.get_public_data(),
             ZkDposOp::FullExit(op) => op.get_public_data(),
             ZkDposOp::ChangePubKeyOffchain(op) => op.get_public_data(),
             ZkDposOp::ForcedExit(op) => op.get_public_data(),
+            ZkDposOp::Conditional(op) => op.get_public_data(),
+            ZkDposOp::ConditionalSettle(op) => op.get_public_data(),
+            ZkDposOp::RangeSettle(op) => op.get_public_data(),
+            ZkDposOp::RangeSettleComplete(op) => op.get_public_data(),
+            ZkDposOp::AdvanceNonce(op) => op.get_public_data(),
+            ZkDposOp::GrantDelegate(op) => op.get_public_data(),
         }
     }
 
@@ -147,6 +202,43 @@ impl ZkDposOp {
             ForcedExitOp::OP_CODE => Ok(ZkDposOp::ForcedExit(Box::new(
                 ForcedExitOp::from_public_data(&bytes)?,
             ))),
+            ExchangeOp::OP_CODE => Ok(ZkDposOp::Exchange(Box::new(
+                ExchangeOp::from_public_data(&bytes)?,
+            ))),
+            AddLiquidityOp::OP_CODE => Ok(ZkDposOp::AddLiquidity(Box::new(
+                AddLiquidityOp::from_public_data(&bytes)?,
+            ))),
+            RemoveLiquidityOp::OP_CODE => Ok(ZkDposOp::RemoveLiquidity(Box::new(
+                RemoveLiquidityOp::from_public_data(&bytes)?,
+            ))),
+            SwapOp::OP_CODE => Ok(ZkDposOp::Swap(Box::new(SwapOp::from_public_data(&bytes)?))),
+            EscrowOp::OP_CODE => Ok(ZkDposOp::Escrow(Box::new(EscrowOp::from_public_data(
+                &bytes,
+            )?))),
+            ConditionalTransferOp::OP_CODE => Ok(ZkDposOp::ConditionalTransfer(Box::new(
+                ConditionalTransferOp::from_public_data(&bytes)?,
+            ))),
+            OrderMatchOp::OP_CODE => Ok(ZkDposOp::OrderMatch(Box::new(
+                OrderMatchOp::from_public_data(&bytes)?,
+            ))),
+            ConditionalOp::OP_CODE => Ok(ZkDposOp::Conditional(Box::new(
+                ConditionalOp::from_public_data(&bytes)?,
+            ))),
+            ConditionalSettleOp::OP_CODE => Ok(ZkDposOp::ConditionalSettle(Box::new(
+                ConditionalSettleOp::from_public_data(&bytes)?,
+            ))),
+            RangeSettleOp::OP_CODE => Ok(ZkDposOp::RangeSettle(Box::new(
+                RangeSettleOp::from_public_data(&bytes)?,
+            ))),
+            RangeSettleCompleteOp::OP_CODE => Ok(ZkDposOp::RangeSettleComplete(Box::new(
+                RangeSettleCompleteOp::from_public_data(&bytes)?,
+            ))),
+            AdvanceNonceOp::OP_CODE => Ok(ZkDposOp::AdvanceNonce(Box::new(
+                AdvanceNonceOp::from_public_data(&bytes)?,
+            ))),
+            GrantDelegateOp::OP_CODE => Ok(ZkDposOp::GrantDelegate(Box::new(
+                GrantDelegateOp::from_public_data(&bytes)?,
+            ))),
             _ => Err(format_err!("Wrong operation type: {}", &op_type)),
         }
     }
@@ -163,6 +255,19 @@ impl ZkDposOp {
             FullExitOp::OP_CODE => Ok(FullExitOp::CHUNKS),
             ChangePubKeyOp::OP_CODE => Ok(ChangePubKeyOp::CHUNKS),
             ForcedExitOp::OP_CODE => Ok(ForcedExitOp::CHUNKS),
+            ExchangeOp::OP_CODE => Ok(ExchangeOp::CHUNKS),
+            AddLiquidityOp::OP_CODE => Ok(AddLiquidityOp::CHUNKS),
+            RemoveLiquidityOp::OP_CODE => Ok(RemoveLiquidityOp::CHUNKS),
+            SwapOp::OP_CODE => Ok(SwapOp::CHUNKS),
+            EscrowOp::OP_CODE => Ok(EscrowOp::CHUNKS),
+            ConditionalTransferOp::OP_CODE => Ok(ConditionalTransferOp::CHUNKS),
+            OrderMatchOp::OP_CODE => Ok(OrderMatchOp::CHUNKS),
+            ConditionalOp::OP_CODE => Ok(ConditionalOp::CHUNKS),
+            ConditionalSettleOp::OP_CODE => Ok(ConditionalSettleOp::CHUNKS),
+            RangeSettleOp::OP_CODE => Ok(RangeSettleOp::CHUNKS),
+            RangeSettleCompleteOp::OP_CODE => Ok(RangeSettleCompleteOp::CHUNKS),
+            AdvanceNonceOp::OP_CODE => Ok(AdvanceNonceOp::CHUNKS),
+            GrantDelegateOp::OP_CODE => Ok(GrantDelegateOp::CHUNKS),
             _ => Err(format_err!("Wrong operation type: {}", &op_type)),
         }
         .map(|chunks| chunks * CHUNK_BYTES)
@@ -188,6 +293,12 @@ impl ZkDposOp {
         match self {
             ZkDposOp::Deposit(op) => Ok(ZkDposPriorityOp::Deposit(op.priority_op.clone())),
             ZkDposOp::FullExit(op) => Ok(ZkDposPriorityOp::FullExit(op.priority_op.clone())),
+            ZkDposOp::Conditional(op) => {
+                Ok(ZkDposPriorityOp::Conditional(op.priority_op.clone()))
+            }
+            ZkDposOp::RangeSettle(op) => {
+                Ok(ZkDposPriorityOp::RangeSettle(op.priority_op.clone()))
+            }
             _ => Err(format_err!("Wrong operation type")),
         }
     }
@@ -204,9 +315,19 @@ impl ZkDposOp {
             ZkDposOp::Exchange(op) => op.get_updated_account_ids(),
             ZkDposOp::AddLiquidity(op) => op.get_updated_account_ids(),
             ZkDposOp::RemoveLiquidity(op) => op.get_updated_account_ids(),
+            ZkDposOp::Swap(op) => op.get_updated_account_ids(),
+            ZkDposOp::Escrow(op) => op.get_updated_account_ids(),
+            ZkDposOp::ConditionalTransfer(op) => op.get_updated_account_ids(),
+            ZkDposOp::OrderMatch(op) => op.get_updated_account_ids(),
             ZkDposOp::FullExit(op) => op.get_updated_account_ids(),
             ZkDposOp::ChangePubKeyOffchain(op) => op.get_updated_account_ids(),
             ZkDposOp::ForcedExit(op) => op.get_updated_account_ids(),
+            ZkDposOp::Conditional(op) => op.get_updated_account_ids(),
+            ZkDposOp::ConditionalSettle(op) => op.get_updated_account_ids(),
+            ZkDposOp::RangeSettle(op) => op.get_updated_account_ids(),
+            ZkDposOp::RangeSettleComplete(op) => op.get_updated_account_ids(),
+            ZkDposOp::AdvanceNonce(op) => op.get_updated_account_ids(),
+            ZkDposOp::GrantDelegate(op) => op.get_updated_account_ids(),
         }
     }
 
@@ -218,6 +339,8 @@ impl ZkDposOp {
                 | &ZkDposOp::FullExit(_)
                 | &ZkDposOp::ChangePubKeyOffchain(_)
                 | &ZkDposOp::ForcedExit(_)
+                | &ZkDposOp::Conditional(_)
+                | &ZkDposOp::RangeSettle(_)
         )
     }
 
@@ -229,7 +352,13 @@ impl ZkDposOp {
     }
 
     pub fn is_priority_op(&self) -> bool {
-        matches!(self, &ZkDposOp::Deposit(_) | &ZkDposOp::FullExit(_))
+        matches!(
+            self,
+            &ZkDposOp::Deposit(_)
+                | &ZkDposOp::FullExit(_)
+                | &ZkDposOp::Conditional(_)
+                | &ZkDposOp::RangeSettle(_)
+        )
     }
 }
 
@@ -286,3 +415,81 @@ impl From<ForcedExitOp> for ZkDposOp {
         Self::ForcedExit(Box::new(op))
     }
 }
+
+impl From<ExchangeOp> for ZkDposOp {
+    fn from(op: ExchangeOp) -> Self {
+        Self::Exchange(Box::new(op))
+    }
+}
+
+impl From<AddLiquidityOp> for ZkDposOp {
+    fn from(op: AddLiquidityOp) -> Self {
+        Self::AddLiquidity(Box::new(op))
+    }
+}
+
+impl From<RemoveLiquidityOp> for ZkDposOp {
+    fn from(op: RemoveLiquidityOp) -> Self {
+        Self::RemoveLiquidity(Box::new(op))
+    }
+}
+
+impl From<SwapOp> for ZkDposOp {
+    fn from(op: SwapOp) -> Self {
+        Self::Swap(Box::new(op))
+    }
+}
+
+impl From<EscrowOp> for ZkDposOp {
+    fn from(op: EscrowOp) -> Self {
+        Self::Escrow(Box::new(op))
+    }
+}
+
+impl From<ConditionalTransferOp> for ZkDposOp {
+    fn from(op: ConditionalTransferOp) -> Self {
+        Self::ConditionalTransfer(Box::new(op))
+    }
+}
+
+impl From<OrderMatchOp> for ZkDposOp {
+    fn from(op: OrderMatchOp) -> Self {
+        Self::OrderMatch(Box::new(op))
+    }
+}
+
+impl From<ConditionalOp> for ZkDposOp {
+    fn from(op: ConditionalOp) -> Self {
+        Self::Conditional(Box::new(op))
+    }
+}
+
+impl From<ConditionalSettleOp> for ZkDposOp {
+    fn from(op: ConditionalSettleOp) -> Self {
+        Self::ConditionalSettle(Box::new(op))
+    }
+}
+
+impl From<RangeSettleOp> for ZkDposOp {
+    fn from(op: RangeSettleOp) -> Self {
+        Self::RangeSettle(Box::new(op))
+    }
+}
+
+impl From<RangeSettleCompleteOp> for ZkDposOp {
+    fn from(op: RangeSettleCompleteOp) -> Self {
+        Self::RangeSettleComplete(Box::new(op))
+    }
+}
+
+impl From<AdvanceNonceOp> for ZkDposOp {
+    fn from(op: AdvanceNonceOp) -> Self {
+        Self::AdvanceNonce(Box::new(op))
+    }
+}
+
+impl From<GrantDelegateOp> for ZkDposOp {
+    fn from(op: GrantDelegateOp) -> Self {
+        Self::GrantDelegate(Box::new(op))
+    }
+}
@@ -0,0 +1,147 @@
+use crate::helpers::{pack_fee_amount, unpack_fee_amount};
+use crate::tx::{Order, OrderMatch, TimeRange};
+use crate::{AccountId, Address, Nonce, TokenId};
+use anyhow::{ensure, format_err};
+use num::{BigUint, FromPrimitive, ToPrimitive};
+use serde::{Deserialize, Serialize};
+use zkdpos_crypto::params::{
+    ACCOUNT_ID_BIT_WIDTH, BALANCE_BIT_WIDTH, CHUNK_BYTES, FEE_EXPONENT_BIT_WIDTH,
+    FEE_MANTISSA_BIT_WIDTH, TOKEN_BIT_WIDTH,
+};
+use zkdpos_crypto::primitives::FromBytes;
+
+/// Order match operation. For details, see the documentation of [`ZkDposOp`](./operations/enum.ZkDposOp.html).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderMatchOp {
+    pub tx: OrderMatch,
+    /// `tx.order_a.account_id`, debited `amount_a` of `token_sell_a` (plus the submitter's fee).
+    pub account_a: AccountId,
+    /// `tx.order_b.account_id`, debited `amount_b` of `token_sell_b`.
+    pub account_b: AccountId,
+    /// Account resolved from `tx.order_a.recipient`, credited `amount_b` of `token_sell_b`.
+    pub recipient_a: AccountId,
+    /// Account resolved from `tx.order_b.recipient`, credited `amount_a` of `token_sell_a`.
+    pub recipient_b: AccountId,
+    /// Quantity of `order_b.token_sell` this match fills, derived from `tx.amount`
+    /// (the quantity of `order_a.token_sell` filled) via `order_a`'s own price.
+    pub amount_b: BigUint,
+}
+
+impl OrderMatchOp {
+    pub const CHUNKS: usize = 6;
+    pub const OP_CODE: u8 = 0x0f;
+
+    pub(crate) fn get_public_data(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.push(Self::OP_CODE); // opcode
+        data.extend_from_slice(&self.account_a.to_be_bytes());
+        data.extend_from_slice(&self.account_b.to_be_bytes());
+        data.extend_from_slice(&self.recipient_a.to_be_bytes());
+        data.extend_from_slice(&self.recipient_b.to_be_bytes());
+        data.extend_from_slice(&self.tx.order_a.token_sell.to_be_bytes());
+        data.extend_from_slice(&self.tx.order_b.token_sell.to_be_bytes());
+        data.extend_from_slice(&self.tx.amount.to_u128().unwrap().to_be_bytes());
+        data.extend_from_slice(&self.amount_b.to_u128().unwrap().to_be_bytes());
+        data.extend_from_slice(&pack_fee_amount(&self.tx.fee));
+        data.resize(Self::CHUNKS * CHUNK_BYTES, 0x00);
+        data
+    }
+
+    pub fn from_public_data(bytes: &[u8]) -> Result<Self, anyhow::Error> {
+        ensure!(
+            bytes.len() == Self::CHUNKS * CHUNK_BYTES,
+            "Wrong bytes length for order match pubdata"
+        );
+
+        let account_a_offset = 1;
+        let account_b_offset = account_a_offset + ACCOUNT_ID_BIT_WIDTH / 8;
+        let recipient_a_offset = account_b_offset + ACCOUNT_ID_BIT_WIDTH / 8;
+        let recipient_b_offset = recipient_a_offset + ACCOUNT_ID_BIT_WIDTH / 8;
+        let token_sell_a_offset = recipient_b_offset + ACCOUNT_ID_BIT_WIDTH / 8;
+        let token_sell_b_offset = token_sell_a_offset + TOKEN_BIT_WIDTH / 8;
+        let amount_a_offset = token_sell_b_offset + TOKEN_BIT_WIDTH / 8;
+        let amount_b_offset = amount_a_offset + BALANCE_BIT_WIDTH / 8;
+        let fee_offset = amount_b_offset + BALANCE_BIT_WIDTH / 8;
+        let fee_end = fee_offset + (FEE_EXPONENT_BIT_WIDTH + FEE_MANTISSA_BIT_WIDTH) / 8;
+
+        ensure!(bytes.len() >= fee_end, "Order match pubdata too short");
+
+        let account_a = u32::from_bytes(&bytes[account_a_offset..account_b_offset])
+            .ok_or_else(|| format_err!("Cant get account a id from order match pubdata"))?;
+        let account_b = u32::from_bytes(&bytes[account_b_offset..recipient_a_offset])
+            .ok_or_else(|| format_err!("Cant get account b id from order match pubdata"))?;
+        let recipient_a = u32::from_bytes(&bytes[recipient_a_offset..recipient_b_offset])
+            .ok_or_else(|| format_err!("Cant get recipient a id from order match pubdata"))?;
+        let recipient_b = u32::from_bytes(&bytes[recipient_b_offset..token_sell_a_offset])
+            .ok_or_else(|| format_err!("Cant get recipient b id from order match pubdata"))?;
+        let token_sell_a =
+            u16::from_bytes(&bytes[token_sell_a_offset..token_sell_b_offset])
+                .ok_or_else(|| format_err!("Cant get token sell a from order match pubdata"))?;
+        let token_sell_b = u16::from_bytes(&bytes[token_sell_b_offset..amount_a_offset])
+            .ok_or_else(|| format_err!("Cant get token sell b from order match pubdata"))?;
+        let amount_a = BigUint::from_u128(
+            u128::from_bytes(&bytes[amount_a_offset..amount_b_offset])
+                .ok_or_else(|| format_err!("Cant get amount a from order match pubdata"))?,
+        )
+        .unwrap();
+        let amount_b = BigUint::from_u128(
+            u128::from_bytes(&bytes[amount_b_offset..fee_offset])
+                .ok_or_else(|| format_err!("Cant get amount b from order match pubdata"))?,
+        )
+        .unwrap();
+        let fee = unpack_fee_amount(&bytes[fee_offset..fee_end])
+            .ok_or_else(|| format_err!("Cant get fee from order match pubdata"))?;
+
+        // Each order's own nonce/recipient address/price/signature isn't
+        // committed to this fixed-offset pubdata, only the ids and tokens
+        // needed to replay the balance updates - same as `EscrowOp` only
+        // commits account ids and `WithdrawOp` only commits the L1 address,
+        // not the full signed transaction.
+        let order_a = Order {
+            account_id: AccountId(account_a),
+            recipient: Address::zero(),
+            nonce: Nonce(0),
+            token_sell: TokenId(token_sell_a),
+            token_buy: TokenId(token_sell_b),
+            price_sell: BigUint::from(1u8),
+            price_buy: BigUint::from(1u8),
+            amount: amount_a.clone(),
+            time_range: TimeRange::default(),
+            signature: Default::default(),
+        };
+        let order_b = Order {
+            account_id: AccountId(account_b),
+            recipient: Address::zero(),
+            nonce: Nonce(0),
+            token_sell: TokenId(token_sell_b),
+            token_buy: TokenId(token_sell_a),
+            price_sell: BigUint::from(1u8),
+            price_buy: BigUint::from(1u8),
+            amount: amount_b.clone(),
+            time_range: TimeRange::default(),
+            signature: Default::default(),
+        };
+
+        Ok(Self {
+            tx: OrderMatch::new(
+                AccountId(account_a),
+                order_a,
+                order_b,
+                amount_a,
+                fee,
+                Nonce(0),
+                TimeRange::default(),
+                None,
+            ),
+            account_a: AccountId(account_a),
+            account_b: AccountId(account_b),
+            recipient_a: AccountId(recipient_a),
+            recipient_b: AccountId(recipient_b),
+            amount_b,
+        })
+    }
+
+    pub fn get_updated_account_ids(&self) -> Vec<AccountId> {
+        vec![self.account_a, self.account_b, self.recipient_a, self.recipient_b]
+    }
+}
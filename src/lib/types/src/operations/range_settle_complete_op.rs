@@ -0,0 +1,139 @@
+use crate::{
+    account::PubKeyHash, dlc::RangeSettleCurve, AccountId, Address, Nonce, RangeSettleComplete,
+    TokenId,
+};
+use anyhow::{ensure, format_err};
+use num::BigUint;
+use serde::{Deserialize, Serialize};
+use zkdpos_crypto::params::{
+    ACCOUNT_ID_BIT_WIDTH, AMOUNT_EXPONENT_BIT_WIDTH, AMOUNT_MANTISSA_BIT_WIDTH, CHUNK_BYTES,
+    FEE_EXPONENT_BIT_WIDTH, FEE_MANTISSA_BIT_WIDTH, TOKEN_BIT_WIDTH,
+};
+use zkdpos_crypto::primitives::FromBytes;
+
+use crate::helpers::{pack_fee_amount, pack_token_amount, unpack_fee_amount, unpack_token_amount};
+
+/// Range settle operation: resolves a `RangeSettle` escrow's payout split
+/// between `to_a` and `to_b`. For details, see the documentation of
+/// [`ZkDposOp`](./operations/enum.ZkDposOp.html).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RangeSettleCompleteOp {
+    pub tx: RangeSettleComplete,
+    pub pending: AccountId,
+    pub to_a: AccountId,
+    pub to_b: AccountId,
+    /// Amount credited to `to_a`, resolved from `tx.curve`/`tx.oracle_attestation`
+    /// at `create_op` time (see `RangeSettleComplete::resolved_payout_a_bp`).
+    pub payout_a: BigUint,
+    /// Amount credited to `to_b`: the remainder of the escrowed pot.
+    pub payout_b: BigUint,
+    /// `true` if this settlement fell back to `tx.curve.refund_payout_a_bp`
+    /// because no valid attestation matched any condition by `tx.deadline_block`.
+    pub refunded: bool,
+}
+
+impl RangeSettleCompleteOp {
+    pub const CHUNKS: usize = 8;
+    pub const OP_CODE: u8 = 0x13;
+
+    pub(crate) fn get_public_data(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.push(Self::OP_CODE); // opcode
+        data.extend_from_slice(&self.pending.to_be_bytes());
+        data.extend_from_slice(&self.to_a.to_be_bytes());
+        data.extend_from_slice(&self.to_b.to_be_bytes());
+        data.extend_from_slice(&self.tx.token.to_be_bytes());
+        data.extend_from_slice(&pack_token_amount(&self.payout_a));
+        data.extend_from_slice(&pack_token_amount(&self.payout_b));
+        data.extend_from_slice(&pack_fee_amount(&self.tx.fee));
+        data.push(self.refunded as u8);
+        data.resize(Self::CHUNKS * CHUNK_BYTES, 0x00);
+        data
+    }
+
+    pub fn from_public_data(bytes: &[u8]) -> Result<Self, anyhow::Error> {
+        ensure!(
+            bytes.len() == Self::CHUNKS * CHUNK_BYTES,
+            "Wrong bytes length for range settle complete pubdata"
+        );
+
+        let pending_offset = 1;
+        let to_a_offset = pending_offset + ACCOUNT_ID_BIT_WIDTH / 8;
+        let to_b_offset = to_a_offset + ACCOUNT_ID_BIT_WIDTH / 8;
+        let token_offset = to_b_offset + ACCOUNT_ID_BIT_WIDTH / 8;
+        let payout_a_offset = token_offset + TOKEN_BIT_WIDTH / 8;
+        let payout_b_offset =
+            payout_a_offset + (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8;
+        let fee_offset =
+            payout_b_offset + (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8;
+        let refunded_offset = fee_offset + (FEE_EXPONENT_BIT_WIDTH + FEE_MANTISSA_BIT_WIDTH) / 8;
+
+        let pending_id = u32::from_bytes(&bytes[pending_offset..to_a_offset]).ok_or_else(|| {
+            format_err!("Cant get pending account id from range settle complete pubdata")
+        })?;
+        let to_a_id = u32::from_bytes(&bytes[to_a_offset..to_b_offset]).ok_or_else(|| {
+            format_err!("Cant get to_a account id from range settle complete pubdata")
+        })?;
+        let to_b_id = u32::from_bytes(&bytes[to_b_offset..token_offset]).ok_or_else(|| {
+            format_err!("Cant get to_b account id from range settle complete pubdata")
+        })?;
+        let token = u16::from_bytes(&bytes[token_offset..payout_a_offset])
+            .ok_or_else(|| format_err!("Cant get token id from range settle complete pubdata"))?;
+        let payout_a = unpack_token_amount(&bytes[payout_a_offset..payout_b_offset])
+            .ok_or_else(|| format_err!("Cant get payout_a from range settle complete pubdata"))?;
+        let payout_b = unpack_token_amount(&bytes[payout_b_offset..fee_offset])
+            .ok_or_else(|| format_err!("Cant get payout_b from range settle complete pubdata"))?;
+        let fee = unpack_fee_amount(&bytes[fee_offset..refunded_offset])
+            .ok_or_else(|| format_err!("Cant get fee from range settle complete pubdata"))?;
+        let refunded = bytes[refunded_offset] != 0;
+        ensure!(
+            bytes.len() >= refunded_offset + 1,
+            "Range settle complete pubdata too short"
+        );
+
+        // Neither the curve nor the oracle attestation that resolved this
+        // settlement are committed to this fixed-offset pubdata, only the
+        // resolved `payout_a`/`payout_b`/`refunded` outcome - same convention
+        // as `ConditionalSettleOp` only committing `receiver`/`released`.
+        let to_a_address = Address::zero(); // From pubdata it is unknown
+        let to_b_address = Address::zero(); // From pubdata it is unknown
+        let amount = &payout_a + &payout_b;
+        let curve = RangeSettleCurve {
+            base: 0,
+            num_digits: 0,
+            conditions: Vec::new(),
+            refund_payout_a_bp: 0,
+        };
+        let nonce = 0; // It is unknown from pubdata
+        let time_range = Default::default();
+
+        Ok(Self {
+            tx: RangeSettleComplete::new(
+                AccountId(0), // The submitter isn't committed to settle pubdata
+                AccountId(pending_id),
+                to_a_address,
+                to_b_address,
+                TokenId(token),
+                amount,
+                fee,
+                PubKeyHash::default(), // From pubdata it is unknown
+                curve,
+                None, // The attestation isn't committed to settle pubdata
+                0,    // It is unknown from pubdata
+                Nonce(nonce),
+                time_range,
+                None,
+            ),
+            pending: AccountId(pending_id),
+            to_a: AccountId(to_a_id),
+            to_b: AccountId(to_b_id),
+            payout_a,
+            payout_b,
+            refunded,
+        })
+    }
+
+    pub fn get_updated_account_ids(&self) -> Vec<AccountId> {
+        vec![self.pending, self.to_a, self.to_b]
+    }
+}
@@ -0,0 +1,129 @@
+use crate::priority_ops::RangeSettle;
+use crate::{AccountId, Address, TokenId};
+use crate::account::PubKeyHash;
+use crate::dlc::RangeSettleCurve;
+use anyhow::{ensure, format_err};
+use serde::{Deserialize, Serialize};
+use zkdpos_crypto::params::{
+    ACCOUNT_ID_BIT_WIDTH, AMOUNT_EXPONENT_BIT_WIDTH, AMOUNT_MANTISSA_BIT_WIDTH, CHUNK_BYTES,
+    FEE_EXPONENT_BIT_WIDTH, FEE_MANTISSA_BIT_WIDTH, FR_ADDRESS_LEN, NEW_PUBKEY_HASH_WIDTH,
+    TOKEN_BIT_WIDTH,
+};
+use zkdpos_crypto::primitives::FromBytes;
+
+use crate::helpers::{pack_fee_amount, pack_token_amount, unpack_fee_amount, unpack_token_amount};
+
+/// Commits the escrow-lock phase of a `RangeSettle` priority operation:
+/// `amount + fee` has already been moved out of `from` into the `pending`
+/// escrow sub-account by `apply_range_settle_op`; settlement (splitting the
+/// pot between `to_a`/`to_b`) happens later via a `RangeSettleComplete`
+/// transaction. `curve` is variable-length and never committed here in full -
+/// only its `commitment()` is, the same way `ConditionalTransferOp` only
+/// commits `PredicateNode::commitment()` rather than the predicate tree. The
+/// full curve is instead kept alive by installing this same commitment (see
+/// `apply_range_settle_op`) into the `pending` account's own `pub_key_hash`,
+/// so a `RangeSettleComplete` can be checked against it even though this op's
+/// own pubdata has long scrolled off into history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RangeSettleOp {
+    pub priority_op: RangeSettle,
+    pub from: AccountId,
+    pub to_a: AccountId,
+    pub to_b: AccountId,
+    pub pending: AccountId,
+}
+
+impl RangeSettleOp {
+    pub const CHUNKS: usize = 10;
+    pub const OP_CODE: u8 = 0x12;
+
+    pub(crate) fn get_public_data(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.push(Self::OP_CODE); // opcode
+        data.extend_from_slice(&self.from.to_be_bytes());
+        data.extend_from_slice(&self.to_a.to_be_bytes());
+        data.extend_from_slice(&self.to_b.to_be_bytes());
+        data.extend_from_slice(&self.pending.to_be_bytes());
+        data.extend_from_slice(&self.priority_op.token.to_be_bytes());
+        data.extend_from_slice(&pack_token_amount(&self.priority_op.amount));
+        data.extend_from_slice(&pack_fee_amount(&self.priority_op.fee));
+        data.extend_from_slice(&self.priority_op.oracle_pubkey_hash.data);
+        data.extend_from_slice(&self.priority_op.curve.commitment().data);
+        data.resize(Self::CHUNKS * CHUNK_BYTES, 0x00);
+        data
+    }
+
+    pub fn from_public_data(bytes: &[u8]) -> Result<Self, anyhow::Error> {
+        ensure!(
+            bytes.len() == Self::CHUNKS * CHUNK_BYTES,
+            "Wrong bytes length for range settle pubdata"
+        );
+
+        let from_offset = 1;
+        let to_a_offset = from_offset + ACCOUNT_ID_BIT_WIDTH / 8;
+        let to_b_offset = to_a_offset + ACCOUNT_ID_BIT_WIDTH / 8;
+        let pending_offset = to_b_offset + ACCOUNT_ID_BIT_WIDTH / 8;
+        let token_offset = pending_offset + ACCOUNT_ID_BIT_WIDTH / 8;
+        let amount_offset = token_offset + TOKEN_BIT_WIDTH / 8;
+        let fee_offset =
+            amount_offset + (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8;
+        let oracle_offset = fee_offset + (FEE_EXPONENT_BIT_WIDTH + FEE_MANTISSA_BIT_WIDTH) / 8;
+        let commitment_offset = oracle_offset + FR_ADDRESS_LEN;
+        let end = commitment_offset + NEW_PUBKEY_HASH_WIDTH / 8;
+
+        let from_id = u32::from_bytes(&bytes[from_offset..to_a_offset])
+            .ok_or_else(|| format_err!("Cant get from account id from range settle pubdata"))?;
+        let to_a_id = u32::from_bytes(&bytes[to_a_offset..to_b_offset])
+            .ok_or_else(|| format_err!("Cant get to_a account id from range settle pubdata"))?;
+        let to_b_id = u32::from_bytes(&bytes[to_b_offset..pending_offset])
+            .ok_or_else(|| format_err!("Cant get to_b account id from range settle pubdata"))?;
+        let pending_id = u32::from_bytes(&bytes[pending_offset..token_offset])
+            .ok_or_else(|| format_err!("Cant get pending account id from range settle pubdata"))?;
+        let token = u16::from_bytes(&bytes[token_offset..amount_offset])
+            .ok_or_else(|| format_err!("Cant get token id from range settle pubdata"))?;
+        let amount = unpack_token_amount(&bytes[amount_offset..fee_offset])
+            .ok_or_else(|| format_err!("Cant get amount from range settle pubdata"))?;
+        let fee = unpack_fee_amount(&bytes[fee_offset..oracle_offset])
+            .ok_or_else(|| format_err!("Cant get fee from range settle pubdata"))?;
+        let oracle_pubkey_hash =
+            PubKeyHash::from_bytes(&bytes[oracle_offset..commitment_offset])?;
+        ensure!(bytes.len() >= end, "Range settle pubdata too short");
+
+        // The curve itself isn't committed to this fixed-offset pubdata, only
+        // its commitment is (see `get_public_data` above); restoring from L1
+        // pubdata alone can't recover the step function, so it's filled in
+        // with a placeholder empty curve that falls back to a 0bp refund -
+        // same idea as `ConditionalTransferOp::from_public_data` reconstructing
+        // a bare `PredicateNode::Approver` from a commitment.
+        let curve = RangeSettleCurve {
+            base: 0,
+            num_digits: 0,
+            conditions: Vec::new(),
+            refund_payout_a_bp: 0,
+        };
+        let from_address = Address::zero(); // From pubdata it is unknown
+        let to_a_address = Address::zero(); // From pubdata it is unknown
+        let to_b_address = Address::zero(); // From pubdata it is unknown
+
+        Ok(Self {
+            priority_op: RangeSettle {
+                from: from_address,
+                to_a: to_a_address,
+                to_b: to_b_address,
+                token: TokenId(token),
+                amount,
+                fee,
+                oracle_pubkey_hash,
+                curve,
+            },
+            from: AccountId(from_id),
+            to_a: AccountId(to_a_id),
+            to_b: AccountId(to_b_id),
+            pending: AccountId(pending_id),
+        })
+    }
+
+    pub fn get_updated_account_ids(&self) -> Vec<AccountId> {
+        vec![self.from, self.to_a, self.to_b, self.pending]
+    }
+}
@@ -0,0 +1,120 @@
+use crate::{
+    helpers::{pack_fee_amount, pack_token_amount, unpack_fee_amount, unpack_token_amount},
+    RemoveLiquidity,
+};
+use crate::{AccountId, Address, LiquidityId, Nonce, TokenId};
+use anyhow::{ensure, format_err};
+use serde::{Deserialize, Serialize};
+use zkdpos_crypto::params::{
+    ACCOUNT_ID_BIT_WIDTH, AMOUNT_EXPONENT_BIT_WIDTH, AMOUNT_MANTISSA_BIT_WIDTH, CHUNK_BYTES,
+    FEE_EXPONENT_BIT_WIDTH, FEE_MANTISSA_BIT_WIDTH, TOKEN_BIT_WIDTH,
+};
+use zkdpos_crypto::primitives::FromBytes;
+
+/// RemoveLiquidity operation. For details, see the documentation of [`ZkDposOp`](./operations/enum.ZkDposOp.html).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoveLiquidityOp {
+    pub tx: RemoveLiquidity,
+    pub from: AccountId,
+    pub to: AccountId,
+}
+
+impl RemoveLiquidityOp {
+    pub const CHUNKS: usize = 4;
+    pub const OP_CODE: u8 = 0x0b;
+
+    pub(crate) fn get_public_data(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.push(Self::OP_CODE); // opcode
+        data.extend_from_slice(&self.from.to_be_bytes());
+        data.extend_from_slice(&self.to.to_be_bytes());
+        data.extend_from_slice(&self.tx.token_a.to_be_bytes());
+        data.extend_from_slice(&self.tx.token_b.to_be_bytes());
+        data.extend_from_slice(&pack_token_amount(&self.tx.shares));
+        data.extend_from_slice(&pack_token_amount(&self.tx.amount_a_min));
+        data.extend_from_slice(&pack_token_amount(&self.tx.amount_b_min));
+        data.extend_from_slice(&pack_fee_amount(&self.tx.fee_a));
+        data.extend_from_slice(&pack_fee_amount(&self.tx.fee_b));
+        data.resize(Self::CHUNKS * CHUNK_BYTES, 0x00);
+        data
+    }
+
+    pub fn from_public_data(bytes: &[u8]) -> Result<Self, anyhow::Error> {
+        ensure!(
+            bytes.len() == Self::CHUNKS * CHUNK_BYTES,
+            "Wrong bytes length for remove liquidity pubdata"
+        );
+
+        let from_offset = 1;
+        let to_offset = from_offset + ACCOUNT_ID_BIT_WIDTH / 8;
+        let token_a_offset = to_offset + ACCOUNT_ID_BIT_WIDTH / 8;
+        let token_b_offset = token_a_offset + TOKEN_BIT_WIDTH / 8;
+        let shares_offset = token_b_offset + TOKEN_BIT_WIDTH / 8;
+        let amount_a_min_offset =
+            shares_offset + (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8;
+        let amount_b_min_offset =
+            amount_a_min_offset + (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8;
+        let fee_a_offset =
+            amount_b_min_offset + (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8;
+        let fee_b_offset = fee_a_offset + (FEE_EXPONENT_BIT_WIDTH + FEE_MANTISSA_BIT_WIDTH) / 8;
+
+        let to_address = Address::zero(); // From pubdata it is unknown
+        let token_a = u16::from_bytes(&bytes[token_a_offset..token_a_offset + TOKEN_BIT_WIDTH / 8])
+            .ok_or_else(|| format_err!("Cant get token a from remove liquidity pubdata"))?;
+        let token_b = u16::from_bytes(&bytes[token_b_offset..token_b_offset + TOKEN_BIT_WIDTH / 8])
+            .ok_or_else(|| format_err!("Cant get token b from remove liquidity pubdata"))?;
+        let shares = unpack_token_amount(
+            &bytes[shares_offset
+                ..shares_offset + (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8],
+        )
+        .ok_or_else(|| format_err!("Cant get shares from remove liquidity pubdata"))?;
+        let amount_a_min = unpack_token_amount(
+            &bytes[amount_a_min_offset
+                ..amount_a_min_offset + (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8],
+        )
+        .ok_or_else(|| format_err!("Cant get amount_a_min from remove liquidity pubdata"))?;
+        let amount_b_min = unpack_token_amount(
+            &bytes[amount_b_min_offset
+                ..amount_b_min_offset + (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8],
+        )
+        .ok_or_else(|| format_err!("Cant get amount_b_min from remove liquidity pubdata"))?;
+        let fee_a = unpack_fee_amount(
+            &bytes[fee_a_offset..fee_a_offset + (FEE_EXPONENT_BIT_WIDTH + FEE_MANTISSA_BIT_WIDTH) / 8],
+        )
+        .ok_or_else(|| format_err!("Cant get fee a from remove liquidity pubdata"))?;
+        let fee_b = unpack_fee_amount(
+            &bytes[fee_b_offset..fee_b_offset + (FEE_EXPONENT_BIT_WIDTH + FEE_MANTISSA_BIT_WIDTH) / 8],
+        )
+        .ok_or_else(|| format_err!("Cant get fee b from remove liquidity pubdata"))?;
+        let nonce = 0; // It is unknown from pubdata
+        let from_id = u32::from_bytes(&bytes[from_offset..from_offset + ACCOUNT_ID_BIT_WIDTH / 8])
+            .ok_or_else(|| format_err!("Cant get from account id from remove liquidity pubdata"))?;
+        let to_id = u32::from_bytes(&bytes[to_offset..to_offset + ACCOUNT_ID_BIT_WIDTH / 8])
+            .ok_or_else(|| format_err!("Cant get to account id from remove liquidity pubdata"))?;
+        let time_range = Default::default();
+
+        Ok(Self {
+            tx: RemoveLiquidity::new(
+                AccountId(from_id),
+                LiquidityId(token_a),
+                to_address,
+                shares,
+                amount_a_min,
+                amount_b_min,
+                TokenId(token_a),
+                TokenId(token_b),
+                fee_a,
+                fee_b,
+                Nonce(nonce),
+                time_range,
+                None,
+            ),
+            from: AccountId(from_id),
+            to: AccountId(to_id),
+        })
+    }
+
+    pub fn get_updated_account_ids(&self) -> Vec<AccountId> {
+        vec![self.from, self.to]
+    }
+}
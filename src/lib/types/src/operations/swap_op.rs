@@ -0,0 +1,102 @@
+use crate::{
+    helpers::{pack_fee_amount, pack_token_amount, unpack_fee_amount, unpack_token_amount},
+    Swap,
+};
+use crate::{AccountId, LiquidityId, Nonce, TokenId};
+use anyhow::{ensure, format_err};
+use serde::{Deserialize, Serialize};
+use zkdpos_crypto::params::{
+    ACCOUNT_ID_BIT_WIDTH, AMOUNT_EXPONENT_BIT_WIDTH, AMOUNT_MANTISSA_BIT_WIDTH, CHUNK_BYTES,
+    FEE_EXPONENT_BIT_WIDTH, FEE_MANTISSA_BIT_WIDTH, TOKEN_BIT_WIDTH,
+};
+use zkdpos_crypto::primitives::FromBytes;
+
+/// Swap operation. For details, see the documentation of [`ZkDposOp`](./operations/enum.ZkDposOp.html).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SwapOp {
+    pub tx: Swap,
+    pub account_id: AccountId,
+}
+
+impl SwapOp {
+    pub const CHUNKS: usize = 3;
+    pub const OP_CODE: u8 = 0x0c;
+
+    pub(crate) fn get_public_data(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.push(Self::OP_CODE); // opcode
+        data.extend_from_slice(&self.account_id.to_be_bytes());
+        data.extend_from_slice(&self.tx.token_in.to_be_bytes());
+        data.extend_from_slice(&self.tx.token_out.to_be_bytes());
+        data.extend_from_slice(&pack_token_amount(&self.tx.amount_in));
+        data.extend_from_slice(&pack_token_amount(&self.tx.amount_out_min));
+        data.extend_from_slice(&pack_fee_amount(&self.tx.fee));
+        data.resize(Self::CHUNKS * CHUNK_BYTES, 0x00);
+        data
+    }
+
+    pub fn from_public_data(bytes: &[u8]) -> Result<Self, anyhow::Error> {
+        ensure!(
+            bytes.len() == Self::CHUNKS * CHUNK_BYTES,
+            "Wrong bytes length for swap pubdata"
+        );
+
+        let account_offset = 1;
+        let token_in_offset = account_offset + ACCOUNT_ID_BIT_WIDTH / 8;
+        let token_out_offset = token_in_offset + TOKEN_BIT_WIDTH / 8;
+        let amount_in_offset = token_out_offset + TOKEN_BIT_WIDTH / 8;
+        let amount_out_min_offset =
+            amount_in_offset + (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8;
+        let fee_offset =
+            amount_out_min_offset + (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8;
+
+        let account_id =
+            u32::from_bytes(&bytes[account_offset..account_offset + ACCOUNT_ID_BIT_WIDTH / 8])
+                .ok_or_else(|| format_err!("Cant get account id from swap pubdata"))?;
+        let token_in =
+            u16::from_bytes(&bytes[token_in_offset..token_in_offset + TOKEN_BIT_WIDTH / 8])
+                .ok_or_else(|| format_err!("Cant get token in from swap pubdata"))?;
+        let token_out =
+            u16::from_bytes(&bytes[token_out_offset..token_out_offset + TOKEN_BIT_WIDTH / 8])
+                .ok_or_else(|| format_err!("Cant get token out from swap pubdata"))?;
+        let amount_in = unpack_token_amount(
+            &bytes[amount_in_offset
+                ..amount_in_offset + (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8],
+        )
+        .ok_or_else(|| format_err!("Cant get amount in from swap pubdata"))?;
+        let amount_out_min = unpack_token_amount(
+            &bytes[amount_out_min_offset
+                ..amount_out_min_offset + (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8],
+        )
+        .ok_or_else(|| format_err!("Cant get amount out min from swap pubdata"))?;
+        let fee = unpack_fee_amount(
+            &bytes[fee_offset..fee_offset + (FEE_EXPONENT_BIT_WIDTH + FEE_MANTISSA_BIT_WIDTH) / 8],
+        )
+        .ok_or_else(|| format_err!("Cant get fee from swap pubdata"))?;
+        let nonce = 0; // It is unknown from pubdata
+        // The pool identifier itself isn't committed to pubdata; it is derived
+        // from the token pair by the caller restoring state from L1 logs.
+        let liquidity_id = token_in;
+        let time_range = Default::default();
+
+        Ok(Self {
+            tx: Swap::new(
+                AccountId(account_id),
+                LiquidityId(liquidity_id),
+                TokenId(token_in),
+                TokenId(token_out),
+                amount_in,
+                amount_out_min,
+                fee,
+                Nonce(nonce),
+                time_range,
+                None,
+            ),
+            account_id: AccountId(account_id),
+        })
+    }
+
+    pub fn get_updated_account_ids(&self) -> Vec<AccountId> {
+        vec![self.account_id]
+    }
+}
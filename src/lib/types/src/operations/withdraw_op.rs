@@ -45,6 +45,12 @@ impl WithdrawOp {
         data
     }
 
+    /// Decodes a committed `WithdrawOp` chunk.
+    ///
+    /// Unlike `ZkDposPriorityOp::parse_from_priority_queue_logs`, this layout
+    /// carries no version byte: the fields are read at fixed offsets, and the
+    /// zero padding up to `CHUNKS * CHUNK_BYTES` is not inspected. Only the
+    /// version-0 layout below is supported.
     pub fn from_public_data(bytes: &[u8]) -> Result<Self, anyhow::Error> {
         ensure!(
             bytes.len() == Self::CHUNKS * CHUNK_BYTES,
@@ -89,6 +95,7 @@ impl WithdrawOp {
                 Nonce(nonce),
                 time_range,
                 None,
+                0, // From pubdata it is unknown
             ),
             account_id: AccountId(account_id),
         })
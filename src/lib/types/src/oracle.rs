@@ -0,0 +1,257 @@
+//! Price-oracle attestations bounding the rate an `Exchange` is allowed to
+//! match at.
+//!
+//! A configured oracle key Schnorr-signs `(token_a, token_b, price,
+//! timestamp)` tuples the same way [`crate::tx::OracleAttestation`] signs a
+//! single DLC outcome, over `TxSignature`'s zkDpos musig scheme. An `Exchange`
+//! may carry a reference to one such attestation instead of baking the
+//! oracle's full public key into every transaction, and [`PriceOracleConfig`]
+//! - the operator's configured list of trusted oracle keys plus deviation and
+//! staleness tolerances - checks it the same way `token_limits` checks an
+//! amount against a configured cap.
+
+use std::fmt;
+
+use num::BigUint;
+use serde::{Deserialize, Serialize};
+
+use crate::account::PubKeyHash;
+use crate::tx::TxSignature;
+use crate::TokenId;
+
+/// An oracle's attestation that `(token_a, token_b)` traded at `price` as of
+/// `timestamp`, referencing the attesting oracle by its index into the
+/// verifier's configured [`PriceOracleConfig::oracles`] rather than carrying
+/// the oracle's public key inline.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PriceAttestation {
+    pub oracle_id: u32,
+    pub price: u64,
+    pub timestamp: u64,
+    pub signature: TxSignature,
+}
+
+impl PriceAttestation {
+    /// The message an oracle signs: `token_a`, `token_b`, `price` and
+    /// `timestamp`, each big-endian, concatenated in that order.
+    pub fn message(token_a: TokenId, token_b: TokenId, price: u64, timestamp: u64) -> Vec<u8> {
+        let mut out = Vec::with_capacity(2 + 2 + 8 + 8);
+        out.extend_from_slice(&token_a.to_be_bytes());
+        out.extend_from_slice(&token_b.to_be_bytes());
+        out.extend_from_slice(&price.to_be_bytes());
+        out.extend_from_slice(&timestamp.to_be_bytes());
+        out
+    }
+
+    /// Verifies that `signature` is a valid signature by `oracle_pubkey_hash`
+    /// over this attestation's `(token_a, token_b, price, timestamp)`.
+    pub fn verify(&self, oracle_pubkey_hash: PubKeyHash, token_a: TokenId, token_b: TokenId) -> bool {
+        let message = Self::message(token_a, token_b, self.price, self.timestamp);
+        match self.signature.verify_musig(&message) {
+            Some(pub_key) => PubKeyHash::from_pubkey(&pub_key) == oracle_pubkey_hash,
+            None => false,
+        }
+    }
+
+    /// The fields folded into `Exchange::get_bytes`, binding the signed
+    /// transaction to this specific attestation. `signature` is excluded, the
+    /// same way `ExchangeCondition::to_be_bytes` excludes the oracle
+    /// signature it refers to - it's verified on its own terms against
+    /// `Self::message`, not re-signed as part of the exchange.
+    pub fn to_be_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(4 + 8 + 8);
+        out.extend_from_slice(&self.oracle_id.to_be_bytes());
+        out.extend_from_slice(&self.price.to_be_bytes());
+        out.extend_from_slice(&self.timestamp.to_be_bytes());
+        out
+    }
+}
+
+/// Why an `Exchange`'s [`PriceAttestation`] failed [`PriceOracleConfig::check`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OracleError {
+    /// `oracle_id` does not index a configured oracle.
+    UnknownOracle(u32),
+    /// The attestation's signature does not verify against the configured
+    /// oracle's key.
+    BadSignature,
+    /// The attestation is older than `PriceOracleConfig::max_staleness_secs`
+    /// relative to the transaction's `time_range.valid_from`.
+    Stale { attested_at: u64, valid_from: u64 },
+    /// `tx.price` deviates from the attested price by more than
+    /// `PriceOracleConfig::tolerance_bps`.
+    Deviation { tx_price: BigUint, attested_price: u64 },
+}
+
+impl fmt::Display for OracleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownOracle(oracle_id) => write!(f, "no oracle configured at index {}", oracle_id),
+            Self::BadSignature => write!(f, "price attestation signature does not verify"),
+            Self::Stale { attested_at, valid_from } => write!(
+                f,
+                "price attestation at {} is stale relative to the transaction's valid_from {}",
+                attested_at, valid_from
+            ),
+            Self::Deviation { tx_price, attested_price } => write!(
+                f,
+                "exchange price {} deviates from the attested price {} by more than the allowed tolerance",
+                tx_price, attested_price
+            ),
+        }
+    }
+}
+
+impl std::error::Error for OracleError {}
+
+/// The operator's configured set of trusted price oracles and the tolerances
+/// an `Exchange`'s [`PriceAttestation`] is checked against, mirroring how
+/// `TokenLimits` holds the configured per-token deposit caps.
+#[derive(Debug, Clone)]
+pub struct PriceOracleConfig {
+    /// Registered oracle public key hashes, indexed by `PriceAttestation::oracle_id`.
+    pub oracles: Vec<PubKeyHash>,
+    /// Maximum allowed deviation of `tx.price` from the attested price, in
+    /// basis points of the attested price.
+    pub tolerance_bps: u64,
+    /// Maximum age, in seconds, an attestation may be relative to the
+    /// transaction's `time_range.valid_from` before it's considered stale.
+    pub max_staleness_secs: u64,
+}
+
+impl PriceOracleConfig {
+    pub fn oracle_pubkey_hash(&self, oracle_id: u32) -> Option<PubKeyHash> {
+        self.oracles.get(oracle_id as usize).copied()
+    }
+
+    /// Checks `attestation` against this configuration: the oracle index must
+    /// resolve, the signature must verify, the attestation must not be stale
+    /// relative to `valid_from`, and `tx_price` must be within
+    /// `tolerance_bps` of the attested price.
+    ///
+    /// The deviation check cross-multiplies rather than dividing either side
+    /// out, the same rounding-free comparison style [`crate::pool::Fraction`]
+    /// uses.
+    pub fn check(
+        &self,
+        attestation: &PriceAttestation,
+        token_a: TokenId,
+        token_b: TokenId,
+        tx_price: &BigUint,
+        valid_from: u64,
+    ) -> Result<(), OracleError> {
+        let oracle_pubkey_hash = self
+            .oracle_pubkey_hash(attestation.oracle_id)
+            .ok_or(OracleError::UnknownOracle(attestation.oracle_id))?;
+        if !attestation.verify(oracle_pubkey_hash, token_a, token_b) {
+            return Err(OracleError::BadSignature);
+        }
+        if attestation.timestamp + self.max_staleness_secs < valid_from {
+            return Err(OracleError::Stale {
+                attested_at: attestation.timestamp,
+                valid_from,
+            });
+        }
+
+        let attested_price = BigUint::from(attestation.price);
+        let diff = if *tx_price >= attested_price {
+            tx_price - &attested_price
+        } else {
+            &attested_price - tx_price
+        };
+        if diff * BigUint::from(10_000u64) > attested_price * BigUint::from(self.tolerance_bps) {
+            return Err(OracleError::Deviation {
+                tx_price: tx_price.clone(),
+                attested_price: attestation.price,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Engine;
+    use zkdpos_crypto::priv_key_from_fs;
+    use zkdpos_crypto::rand::{thread_rng, Rng};
+
+    fn random_oracle_key() -> zkdpos_crypto::PrivateKey<Engine> {
+        let mut rng = thread_rng();
+        priv_key_from_fs(rng.gen())
+    }
+
+    fn attest(
+        oracle_key: &zkdpos_crypto::PrivateKey<Engine>,
+        oracle_id: u32,
+        token_a: TokenId,
+        token_b: TokenId,
+        price: u64,
+        timestamp: u64,
+    ) -> PriceAttestation {
+        let message = PriceAttestation::message(token_a, token_b, price, timestamp);
+        PriceAttestation {
+            oracle_id,
+            price,
+            timestamp,
+            signature: TxSignature::sign_musig(oracle_key, &message),
+        }
+    }
+
+    #[test]
+    fn accepts_an_attestation_within_tolerance_and_staleness() {
+        let oracle_key = random_oracle_key();
+        let oracle_pubkey_hash = PubKeyHash::from_privkey(&oracle_key);
+        let config = PriceOracleConfig {
+            oracles: vec![oracle_pubkey_hash],
+            tolerance_bps: 100,
+            max_staleness_secs: 60,
+        };
+        let attestation = attest(&oracle_key, 0, TokenId(0), TokenId(1), 1_000, 1_000);
+        config
+            .check(&attestation, TokenId(0), TokenId(1), &BigUint::from(1_005u64), 1_030)
+            .unwrap();
+    }
+
+    #[test]
+    fn rejects_a_price_outside_tolerance() {
+        let oracle_key = random_oracle_key();
+        let oracle_pubkey_hash = PubKeyHash::from_privkey(&oracle_key);
+        let config = PriceOracleConfig {
+            oracles: vec![oracle_pubkey_hash],
+            tolerance_bps: 100,
+            max_staleness_secs: 60,
+        };
+        let attestation = attest(&oracle_key, 0, TokenId(0), TokenId(1), 1_000, 1_000);
+        let result = config.check(&attestation, TokenId(0), TokenId(1), &BigUint::from(1_100u64), 1_000);
+        assert!(matches!(result, Err(OracleError::Deviation { .. })));
+    }
+
+    #[test]
+    fn rejects_a_stale_attestation() {
+        let oracle_key = random_oracle_key();
+        let oracle_pubkey_hash = PubKeyHash::from_privkey(&oracle_key);
+        let config = PriceOracleConfig {
+            oracles: vec![oracle_pubkey_hash],
+            tolerance_bps: 100,
+            max_staleness_secs: 60,
+        };
+        let attestation = attest(&oracle_key, 0, TokenId(0), TokenId(1), 1_000, 1_000);
+        let result = config.check(&attestation, TokenId(0), TokenId(1), &BigUint::from(1_000u64), 2_000);
+        assert!(matches!(result, Err(OracleError::Stale { .. })));
+    }
+
+    #[test]
+    fn rejects_an_unknown_oracle_id() {
+        let oracle_key = random_oracle_key();
+        let oracle_pubkey_hash = PubKeyHash::from_privkey(&oracle_key);
+        let config = PriceOracleConfig {
+            oracles: vec![oracle_pubkey_hash],
+            tolerance_bps: 100,
+            max_staleness_secs: 60,
+        };
+        let attestation = attest(&oracle_key, 7, TokenId(0), TokenId(1), 1_000, 1_000);
+        let result = config.check(&attestation, TokenId(0), TokenId(1), &BigUint::from(1_000u64), 1_000);
+        assert!(matches!(result, Err(OracleError::UnknownOracle(7))));
+    }
+}
@@ -0,0 +1,230 @@
+//! Constant-product AMM pool bookkeeping shared by the `AddLiquidity`, `RemoveLiquidity`
+//! and `Swap` state handlers.
+//!
+//! A [`Pool`] mirrors the reserves and total minted LP shares of a Uniswap-V2-style
+//! pair. The math here is pure (no state-tree access) so it can be unit tested and
+//! reused identically by the server and the circuit witness.
+
+use crate::{LiquidityId, TokenId};
+use num::{BigUint, ToPrimitive, Zero};
+use std::cmp::Ordering;
+use zkdpos_crypto::params::max_token_id;
+
+/// Minimum liquidity permanently locked on the first `AddLiquidity` call, so that
+/// `total_shares` can never be driven back down to zero by a later `RemoveLiquidity`.
+pub const MINIMUM_LIQUIDITY: u64 = 1_000;
+
+/// Trading fee taken out of `amount_in` on every `Swap`, expressed in basis points
+/// out of `FEE_BPS_BASE`.
+pub const SWAP_FEE_BPS: u64 = 30;
+pub const FEE_BPS_BASE: u64 = 10_000;
+
+/// Scale a pool's exact `reserve_b / reserve_a` [`Fraction`] is discretized
+/// by (see [`Fraction::to_scaled_price`]) before it's compared against an
+/// `AddLiquidity` deposit's `p_low`/`p_high` concentrated-liquidity band,
+/// which is expressed in this same fixed-point scale.
+pub const PRICE_SCALE: u64 = 1_000_000_000;
+
+/// An exact rational value `numerator / denominator`, kept unreduced over two
+/// `BigUint`s. Pool ratio math (desired/min ratios, share pricing, swap output)
+/// is built out of these instead of chaining several lossy integer divisions, so
+/// that the invariant and slippage checks only round once, at the very end, when
+/// a result has to be committed as an integer on-chain amount.
+///
+/// The rounding direction is an explicit invariant throughout this module:
+/// every amount owed *to* a user rounds down, and every amount owed *by* a user
+/// rounds up, so that in aggregate the pool can never be drained by rounding
+/// drift. [`Fraction::round_down`] and [`Fraction::round_up`] make the direction
+/// explicit at each call site rather than relying on `BigUint`'s default (floor)
+/// division semantics.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fraction {
+    pub numerator: BigUint,
+    pub denominator: BigUint,
+}
+
+impl Fraction {
+    pub fn new(numerator: BigUint, denominator: BigUint) -> Self {
+        assert!(!denominator.is_zero(), "Fraction denominator must not be zero");
+        Self {
+            numerator,
+            denominator,
+        }
+    }
+
+    /// Compares two fractions by cross-multiplication, so no division (and thus no
+    /// precision loss) is ever performed to order them.
+    pub fn cmp_exact(&self, other: &Fraction) -> Ordering {
+        (&self.numerator * &other.denominator).cmp(&(&other.numerator * &self.denominator))
+    }
+
+    pub fn ge(&self, other: &Fraction) -> bool {
+        !matches!(self.cmp_exact(other), Ordering::Less)
+    }
+
+    pub fn eq_exact(&self, other: &Fraction) -> bool {
+        matches!(self.cmp_exact(other), Ordering::Equal)
+    }
+
+    /// Rounds down to the nearest integer: the direction used for amounts a user
+    /// is about to receive (depositor shares, withdrawn reserves, swap output).
+    pub fn round_down(&self) -> BigUint {
+        &self.numerator / &self.denominator
+    }
+
+    /// Rounds up to the nearest integer: the direction used for amounts a user
+    /// is about to pay or that are debited against their balance.
+    pub fn round_up(&self) -> BigUint {
+        let (quotient, remainder) = (
+            &self.numerator / &self.denominator,
+            &self.numerator % &self.denominator,
+        );
+        if remainder.is_zero() {
+            quotient
+        } else {
+            quotient + BigUint::from(1u8)
+        }
+    }
+
+    /// Discretizes this fraction as `round_down(self * PRICE_SCALE)`, clamped
+    /// to `u64::MAX`, for comparison against an `AddLiquidity` price band's
+    /// `p_low`/`p_high` bounds.
+    pub fn to_scaled_price(&self) -> u64 {
+        let scaled = Fraction::new(&self.numerator * PRICE_SCALE, self.denominator.clone());
+        scaled.round_down().to_u64().unwrap_or(u64::MAX)
+    }
+}
+
+/// A single token-pair liquidity pool, keyed by its `LiquidityId` in `ZkDposState`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Pool {
+    pub liquidity_id: LiquidityId,
+    pub token_a: TokenId,
+    pub token_b: TokenId,
+    pub reserve_a: BigUint,
+    pub reserve_b: BigUint,
+    pub total_shares: BigUint,
+}
+
+impl Pool {
+    /// LP shares for a given pool are tracked as an ordinary account balance, in the
+    /// reserved `TokenId` range just above the real, tradable tokens. This lets share
+    /// minting/burning ride the existing `AccountUpdate::UpdateBalance` machinery
+    /// instead of inventing a new kind of state update.
+    pub fn lp_token_id(liquidity_id: LiquidityId) -> TokenId {
+        TokenId(*max_token_id() + 1 + *liquidity_id)
+    }
+
+    pub fn empty(liquidity_id: LiquidityId, token_a: TokenId, token_b: TokenId) -> Self {
+        Self {
+            liquidity_id,
+            token_a,
+            token_b,
+            reserve_a: BigUint::zero(),
+            reserve_b: BigUint::zero(),
+            total_shares: BigUint::zero(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total_shares.is_zero()
+    }
+
+    /// Shares minted for the very first deposit into the pool: `sqrt(amount_a * amount_b)`
+    /// minus the permanently locked `MINIMUM_LIQUIDITY`.
+    pub fn initial_shares(amount_a: &BigUint, amount_b: &BigUint) -> Option<BigUint> {
+        let product = amount_a * amount_b;
+        let shares = product.sqrt();
+        shares.checked_sub(&BigUint::from(MINIMUM_LIQUIDITY))
+    }
+
+    /// The pool's current price, as an exact `reserve_b / reserve_a` fraction. A
+    /// deposit's [`Fraction`] from [`AddLiquidity::effective_ratio`] is compared
+    /// against this with [`Fraction::cmp_exact`] rather than the cross-multiplied
+    /// equality this method used to compute inline, so the same exact-rational
+    /// comparison is shared by every caller instead of being re-derived per call site.
+    pub fn effective_ratio(&self) -> Fraction {
+        Fraction::new(self.reserve_b.clone(), self.reserve_a.clone())
+    }
+
+    /// Shares minted for a deposit into a non-empty pool, matching the pool's current
+    /// ratio. Rounded down: shares are what the depositor receives.
+    pub fn proportional_shares(&self, amount_a: &BigUint, amount_b: &BigUint) -> BigUint {
+        let from_a = Fraction::new(amount_a * &self.total_shares, self.reserve_a.clone());
+        let from_b = Fraction::new(amount_b * &self.total_shares, self.reserve_b.clone());
+        from_a.round_down().min(from_b.round_down())
+    }
+
+    /// `true` if `amount_b` is within the pool's current ratio of `amount_a`, i.e.
+    /// `amount_b / amount_a == reserve_b / reserve_a`, compared exactly via
+    /// cross-multiplication rather than by dividing either ratio out.
+    pub fn matches_ratio(&self, amount_a: &BigUint, amount_b: &BigUint) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+        Fraction::new(amount_b.clone(), amount_a.clone()).eq_exact(&self.effective_ratio())
+    }
+
+    /// Amount of each side returned for burning `shares` LP units. Rounded down:
+    /// these are what the withdrawing account receives.
+    pub fn shares_to_amounts(&self, shares: &BigUint) -> (BigUint, BigUint) {
+        let amount_a = Fraction::new(shares * &self.reserve_a, self.total_shares.clone());
+        let amount_b = Fraction::new(shares * &self.reserve_b, self.total_shares.clone());
+        (amount_a.round_down(), amount_b.round_down())
+    }
+
+    /// Constant-product swap output, net of the `SWAP_FEE_BPS` trading fee:
+    /// `amount_out = (amount_in * (10000 - fee_bps) * reserve_out) /
+    ///               (reserve_in * 10000 + amount_in * (10000 - fee_bps))`.
+    /// Rounded down: the output is what the trader receives.
+    pub fn swap_output(reserve_in: &BigUint, reserve_out: &BigUint, amount_in: &BigUint) -> BigUint {
+        let amount_in_with_fee = amount_in * (FEE_BPS_BASE - SWAP_FEE_BPS);
+        let numerator = &amount_in_with_fee * reserve_out;
+        let denominator = reserve_in * FEE_BPS_BASE + &amount_in_with_fee;
+        Fraction::new(numerator, denominator).round_down()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn initial_shares_locks_minimum_liquidity() {
+        let shares = Pool::initial_shares(&BigUint::from(10_000u64), &BigUint::from(10_000u64))
+            .expect("shares should be minted");
+        assert_eq!(shares, BigUint::from(10_000u64 - MINIMUM_LIQUIDITY));
+    }
+
+    #[test]
+    fn swap_output_respects_constant_product_with_fee() {
+        let reserve_in = BigUint::from(1_000_000u64);
+        let reserve_out = BigUint::from(1_000_000u64);
+        let amount_in = BigUint::from(1_000u64);
+        let amount_out = Pool::swap_output(&reserve_in, &reserve_out, &amount_in);
+        // The post-trade product must not decrease.
+        assert!((&reserve_in + &amount_in) * (&reserve_out - &amount_out) >= &reserve_in * &reserve_out);
+        assert!(amount_out < amount_in);
+    }
+
+    #[test]
+    fn fraction_rounds_down_and_up_around_a_non_exact_division() {
+        let fraction = Fraction::new(BigUint::from(7u64), BigUint::from(2u64));
+        assert_eq!(fraction.round_down(), BigUint::from(3u64));
+        assert_eq!(fraction.round_up(), BigUint::from(4u64));
+    }
+
+    #[test]
+    fn fraction_round_down_and_up_agree_on_an_exact_division() {
+        let fraction = Fraction::new(BigUint::from(8u64), BigUint::from(2u64));
+        assert_eq!(fraction.round_down(), fraction.round_up());
+    }
+
+    #[test]
+    fn fraction_cmp_exact_does_not_depend_on_reduced_form() {
+        let a = Fraction::new(BigUint::from(1u64), BigUint::from(3u64));
+        let b = Fraction::new(BigUint::from(2u64), BigUint::from(6u64));
+        assert!(a.eq_exact(&b));
+        assert!(a.ge(&b));
+    }
+}
@@ -7,13 +7,16 @@ use serde::{Deserialize, Serialize};
 use std::convert::{TryFrom, TryInto};
 use zkdpos_basic_types::{Address, Log, H256, U256};
 use zkdpos_crypto::params::{
-    ACCOUNT_ID_BIT_WIDTH, BALANCE_BIT_WIDTH, ATP_ADDRESS_BIT_WIDTH, FR_ADDRESS_LEN,
-    TOKEN_BIT_WIDTH, TX_TYPE_BIT_WIDTH,
+    ACCOUNT_ID_BIT_WIDTH, BALANCE_BIT_WIDTH, ATP_ADDRESS_BIT_WIDTH, FEE_EXPONENT_BIT_WIDTH,
+    FEE_MANTISSA_BIT_WIDTH, FR_ADDRESS_LEN, TOKEN_BIT_WIDTH, TX_TYPE_BIT_WIDTH,
 };
 use zkdpos_utils::BigUintSerdeAsRadix10Str;
 
+use crate::account::PubKeyHash;
+use crate::dlc::RangeSettleCurve;
+use crate::helpers::unpack_fee_amount;
 use super::{
-    operations::{DepositOp, FullExitOp},
+    operations::{ConditionalOp, DepositOp, FullExitOp, RangeSettleOp},
     utils::h256_as_vec,
     AccountId, SerialId, TokenId,
 };
@@ -47,12 +50,122 @@ pub struct FullExit {
     pub token: TokenId,
 }
 
+/// Gates settlement of a `Conditional` priority operation, HTLC-style.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ConditionalPredicate {
+    /// Settles to `to` once the Alaya block height has reached this value;
+    /// before that, and once `PriorityOp::deadline_block` has passed with no
+    /// settlement, the escrow refunds to `from` instead.
+    After(u64),
+    /// Settles to `to` once a witness produces a `TxSignature` over the
+    /// op's `serial_id` that recovers to this `PubKeyHash` - the "hash-lock"
+    /// analogue, gated by a signature rather than a hash preimage.
+    Signed(PubKeyHash),
+}
+
+impl ConditionalPredicate {
+    pub fn kind(&self) -> u8 {
+        match self {
+            Self::After(_) => 0,
+            Self::Signed(_) => 1,
+        }
+    }
+
+    /// Encodes as a fixed-width `kind || payload` pair, the payload
+    /// right-aligned/zero-padded to `FR_ADDRESS_LEN` so both variants commit
+    /// to the same number of bytes regardless of which one is in play.
+    pub fn to_be_bytes(&self) -> Vec<u8> {
+        let mut out = vec![self.kind()];
+        let mut payload = vec![0u8; FR_ADDRESS_LEN];
+        match self {
+            Self::After(block) => {
+                payload[FR_ADDRESS_LEN - 8..].copy_from_slice(&block.to_be_bytes());
+            }
+            Self::Signed(witness_pubkey) => {
+                payload.copy_from_slice(&witness_pubkey.data);
+            }
+        }
+        out.extend_from_slice(&payload);
+        out
+    }
+
+    /// Inverse of [`Self::to_be_bytes`]: `payload` must be exactly `FR_ADDRESS_LEN` bytes.
+    pub(crate) fn from_bytes(kind: u8, payload: &[u8]) -> Result<Self, anyhow::Error> {
+        ensure!(
+            payload.len() == FR_ADDRESS_LEN,
+            "Conditional predicate payload has wrong length"
+        );
+        match kind {
+            0 => {
+                let block = u64::from_be_bytes(payload[FR_ADDRESS_LEN - 8..].try_into().unwrap());
+                Ok(Self::After(block))
+            }
+            1 => Ok(Self::Signed(PubKeyHash::from_bytes(payload)?)),
+            other => Err(format_err!("Unknown conditional predicate kind: {}", other)),
+        }
+    }
+}
+
+/// Locks `amount + fee` of `token` out of `from`'s L1 balance into an L2
+/// escrow sub-balance (see `ConditionalOp`), settling to `to` once
+/// `predicate` is satisfied, or refunding `from` once the priority queue
+/// entry's `deadline_block` passes with the predicate still unmet. Gives
+/// users timelocked payments and simple HTLC-style flows without leaving L2.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Conditional {
+    pub from: Address,
+    pub to: Address,
+    pub token: TokenId,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub amount: BigUint,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub fee: BigUint,
+    pub predicate: ConditionalPredicate,
+}
+
+/// Locks `amount + fee` of `token` out of `from`'s L1 balance into an L2
+/// escrow sub-balance (see `RangeSettleOp`), to be paid out between `to_a`
+/// and `to_b` once an oracle attests to an outcome - the contract-for-
+/// difference settlement pattern. `curve` is the step function mapping an
+/// outcome to the split between the two parties, encoded compactly via
+/// [`crate::dlc::decompose_curve`]; `oracle_pubkey_hash` identifies the
+/// oracle whose attestation resolves it. If the priority queue entry's
+/// `deadline_block` passes with no attestation matching any of `curve`'s
+/// conditions, the escrow falls back to `curve.refund_payout_a_bp`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RangeSettle {
+    pub from: Address,
+    pub to_a: Address,
+    pub to_b: Address,
+    pub token: TokenId,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub amount: BigUint,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub fee: BigUint,
+    pub oracle_pubkey_hash: PubKeyHash,
+    pub curve: RangeSettleCurve,
+}
+
+/// Length in bytes of the version-0 `Deposit` pubdata: `tx_type || account_id || token || amount || address`.
+/// A log of any other length is assumed to carry an explicit leading version byte.
+const DEPOSIT_PUBDATA_V0_LEN: usize =
+    TX_TYPE_BIT_WIDTH / 8 + ACCOUNT_ID_BIT_WIDTH / 8 + TOKEN_BIT_WIDTH / 8 + BALANCE_BIT_WIDTH / 8 + FR_ADDRESS_LEN;
+
+/// Length in bytes of the version-0 `FullExit` pubdata: `tx_type || account_id || atp_address || token || amount`.
+const FULL_EXIT_PUBDATA_V0_LEN: usize = TX_TYPE_BIT_WIDTH / 8
+    + ACCOUNT_ID_BIT_WIDTH / 8
+    + ATP_ADDRESS_BIT_WIDTH / 8
+    + TOKEN_BIT_WIDTH / 8
+    + BALANCE_BIT_WIDTH / 8;
+
 /// A set of L1 priority operations supported by the zkDpos network.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum ZkDposPriorityOp {
     Deposit(Deposit),
     FullExit(FullExit),
+    Conditional(Conditional),
+    RangeSettle(RangeSettle),
 }
 
 impl ZkDposPriorityOp {
@@ -66,121 +179,28 @@ impl ZkDposPriorityOp {
     }
 
     /// Parses priority operation from the Alaya logs.
+    ///
+    /// Pubdata emitted by the legacy (un-versioned) contract has no version
+    /// byte at all, so the exact length of the op's version-0 layout is used
+    /// to tell it apart from pubdata carrying an explicit leading version
+    /// byte. This mirrors how `AtpBatchSignatures` keeps deserializing both
+    /// its old and new shapes instead of forking the type.
     pub fn parse_from_priority_queue_logs(
         pub_data: &[u8],
         op_type_id: u8,
         sender: Address,
     ) -> Result<Self, anyhow::Error> {
-        // see contracts/contracts/Operations.sol
-        match op_type_id {
-            DepositOp::OP_CODE => {
-                let pub_data_left = pub_data;
-
-                ensure!(
-                    pub_data_left.len() >= TX_TYPE_BIT_WIDTH / 8,
-                    "PubData length mismatch"
-                );
-                let (_, pub_data_left) = pub_data_left.split_at(TX_TYPE_BIT_WIDTH / 8);
-
-                // account_id
-                ensure!(
-                    pub_data_left.len() >= ACCOUNT_ID_BIT_WIDTH / 8,
-                    "PubData length mismatch"
-                );
-                let (_, pub_data_left) = pub_data_left.split_at(ACCOUNT_ID_BIT_WIDTH / 8);
-
-                // token
-                let (token, pub_data_left) = {
-                    ensure!(
-                        pub_data_left.len() >= TOKEN_BIT_WIDTH / 8,
-                        "PubData length mismatch"
-                    );
-                    let (token, left) = pub_data_left.split_at(TOKEN_BIT_WIDTH / 8);
-                    (u16::from_be_bytes(token.try_into().unwrap()), left)
-                };
-
-                // amount
-                let (amount, pub_data_left) = {
-                    ensure!(
-                        pub_data_left.len() >= BALANCE_BIT_WIDTH / 8,
-                        "PubData length mismatch"
-                    );
-                    let (amount, left) = pub_data_left.split_at(BALANCE_BIT_WIDTH / 8);
-                    let amount = u128::from_be_bytes(amount.try_into().unwrap());
-                    (BigUint::from(amount), left)
-                };
-
-                // account
-                let (account, pub_data_left) = {
-                    ensure!(
-                        pub_data_left.len() >= FR_ADDRESS_LEN,
-                        "PubData length mismatch"
-                    );
-                    let (account, left) = pub_data_left.split_at(FR_ADDRESS_LEN);
-                    (Address::from_slice(account), left)
-                };
-
-                ensure!(
-                    pub_data_left.is_empty(),
-                    "DepositOp parse failed: input too big"
-                );
-
-                Ok(Self::Deposit(Deposit {
-                    from: sender,
-                    token: TokenId(token),
-                    amount,
-                    to: account,
-                }))
-            }
-            FullExitOp::OP_CODE => {
-                ensure!(
-                    pub_data.len() >= TX_TYPE_BIT_WIDTH / 8,
-                    "PubData length mismatch"
-                );
-                let (_, pub_data_left) = pub_data.split_at(TX_TYPE_BIT_WIDTH / 8);
-
-                // account_id
-                let (account_id, pub_data_left) = {
-                    ensure!(
-                        pub_data_left.len() >= ACCOUNT_ID_BIT_WIDTH / 8,
-                        "PubData length mismatch"
-                    );
-                    let (account_id, left) = pub_data_left.split_at(ACCOUNT_ID_BIT_WIDTH / 8);
-                    (u32::from_bytes(account_id).unwrap(), left)
-                };
-
-                // owner
-                let (atp_address, pub_data_left) = {
-                    ensure!(
-                        pub_data_left.len() >= ATP_ADDRESS_BIT_WIDTH / 8,
-                        "PubData length mismatch"
-                    );
-                    let (atp_address, left) = pub_data_left.split_at(ATP_ADDRESS_BIT_WIDTH / 8);
-                    (Address::from_slice(atp_address), left)
-                };
-
-                // token
-                let (token, pub_data_left) = {
-                    ensure!(
-                        pub_data_left.len() >= TOKEN_BIT_WIDTH / 8,
-                        "PubData length mismatch"
-                    );
-                    let (token, left) = pub_data_left.split_at(TOKEN_BIT_WIDTH / 8);
-                    (u16::from_be_bytes(token.try_into().unwrap()), left)
-                };
-
-                // amount
-                ensure!(
-                    pub_data_left.len() == BALANCE_BIT_WIDTH / 8,
-                    "FullExitOp parse failed: input too big: {:?}",
-                    pub_data_left
-                );
-
-                Ok(Self::FullExit(FullExit {
-                    account_id: AccountId(account_id),
-                    atp_address,
-                    token: TokenId(token),
-                }))
+        let (version, body) = Self::split_off_version(pub_data, op_type_id);
+        match (version, op_type_id) {
+            (0, DepositOp::OP_CODE) => Self::parse_deposit_v0(body, sender),
+            (0, FullExitOp::OP_CODE) => Self::parse_full_exit_v0(body),
+            (0, ConditionalOp::OP_CODE) => Self::parse_conditional_v0(body, sender),
+            (0, RangeSettleOp::OP_CODE) => Self::parse_range_settle_v0(body, sender),
+            (_, DepositOp::OP_CODE)
+            | (_, FullExitOp::OP_CODE)
+            | (_, ConditionalOp::OP_CODE)
+            | (_, RangeSettleOp::OP_CODE) => {
+                bail!("Unsupported pubdata version {} for op {}", version, op_type_id);
             }
             _ => {
                 bail!("Unsupported priority op type");
@@ -188,11 +208,326 @@ impl ZkDposPriorityOp {
         }
     }
 
+    /// Splits a leading version byte off `pub_data`, if present.
+    ///
+    /// A legacy log (emitted before versioning existed) is exactly as long as
+    /// the version-0 layout and carries no version byte, so it is reported as
+    /// `(0, pub_data)` unchanged. Anything else is assumed to have been
+    /// emitted by a version-aware contract and starts with an explicit
+    /// version byte.
+    fn split_off_version(pub_data: &[u8], op_type_id: u8) -> (u8, &[u8]) {
+        let legacy_len = match op_type_id {
+            DepositOp::OP_CODE => Some(DEPOSIT_PUBDATA_V0_LEN),
+            FullExitOp::OP_CODE => Some(FULL_EXIT_PUBDATA_V0_LEN),
+            _ => None,
+        };
+        if legacy_len == Some(pub_data.len()) {
+            return (0, pub_data);
+        }
+        match pub_data.split_first() {
+            Some((version, body)) => (*version, body),
+            None => (0, pub_data),
+        }
+    }
+
+    /// Version-0 `Deposit` layout: `tx_type || account_id || token || amount || address`.
+    fn parse_deposit_v0(pub_data: &[u8], sender: Address) -> Result<Self, anyhow::Error> {
+        let pub_data_left = pub_data;
+
+        ensure!(
+            pub_data_left.len() >= TX_TYPE_BIT_WIDTH / 8,
+            "PubData length mismatch"
+        );
+        let (_, pub_data_left) = pub_data_left.split_at(TX_TYPE_BIT_WIDTH / 8);
+
+        // account_id
+        ensure!(
+            pub_data_left.len() >= ACCOUNT_ID_BIT_WIDTH / 8,
+            "PubData length mismatch"
+        );
+        let (_, pub_data_left) = pub_data_left.split_at(ACCOUNT_ID_BIT_WIDTH / 8);
+
+        // token
+        let (token, pub_data_left) = {
+            ensure!(
+                pub_data_left.len() >= TOKEN_BIT_WIDTH / 8,
+                "PubData length mismatch"
+            );
+            let (token, left) = pub_data_left.split_at(TOKEN_BIT_WIDTH / 8);
+            (u16::from_be_bytes(token.try_into().unwrap()), left)
+        };
+
+        // amount
+        let (amount, pub_data_left) = {
+            ensure!(
+                pub_data_left.len() >= BALANCE_BIT_WIDTH / 8,
+                "PubData length mismatch"
+            );
+            let (amount, left) = pub_data_left.split_at(BALANCE_BIT_WIDTH / 8);
+            let amount = u128::from_be_bytes(amount.try_into().unwrap());
+            (BigUint::from(amount), left)
+        };
+
+        // account
+        let (account, pub_data_left) = {
+            ensure!(
+                pub_data_left.len() >= FR_ADDRESS_LEN,
+                "PubData length mismatch"
+            );
+            let (account, left) = pub_data_left.split_at(FR_ADDRESS_LEN);
+            (Address::from_slice(account), left)
+        };
+
+        ensure!(
+            pub_data_left.is_empty(),
+            "DepositOp parse failed: input too big"
+        );
+
+        Ok(Self::Deposit(Deposit {
+            from: sender,
+            token: TokenId(token),
+            amount,
+            to: account,
+        }))
+    }
+
+    /// Version-0 `FullExit` layout: `tx_type || account_id || atp_address || token || amount`.
+    fn parse_full_exit_v0(pub_data: &[u8]) -> Result<Self, anyhow::Error> {
+        ensure!(
+            pub_data.len() >= TX_TYPE_BIT_WIDTH / 8,
+            "PubData length mismatch"
+        );
+        let (_, pub_data_left) = pub_data.split_at(TX_TYPE_BIT_WIDTH / 8);
+
+        // account_id
+        let (account_id, pub_data_left) = {
+            ensure!(
+                pub_data_left.len() >= ACCOUNT_ID_BIT_WIDTH / 8,
+                "PubData length mismatch"
+            );
+            let (account_id, left) = pub_data_left.split_at(ACCOUNT_ID_BIT_WIDTH / 8);
+            (u32::from_bytes(account_id).unwrap(), left)
+        };
+
+        // owner
+        let (atp_address, pub_data_left) = {
+            ensure!(
+                pub_data_left.len() >= ATP_ADDRESS_BIT_WIDTH / 8,
+                "PubData length mismatch"
+            );
+            let (atp_address, left) = pub_data_left.split_at(ATP_ADDRESS_BIT_WIDTH / 8);
+            (Address::from_slice(atp_address), left)
+        };
+
+        // token
+        let (token, pub_data_left) = {
+            ensure!(
+                pub_data_left.len() >= TOKEN_BIT_WIDTH / 8,
+                "PubData length mismatch"
+            );
+            let (token, left) = pub_data_left.split_at(TOKEN_BIT_WIDTH / 8);
+            (u16::from_be_bytes(token.try_into().unwrap()), left)
+        };
+
+        // amount
+        ensure!(
+            pub_data_left.len() == BALANCE_BIT_WIDTH / 8,
+            "FullExitOp parse failed: input too big: {:?}",
+            pub_data_left
+        );
+
+        Ok(Self::FullExit(FullExit {
+            account_id: AccountId(account_id),
+            atp_address,
+            token: TokenId(token),
+        }))
+    }
+
+    /// Version-0 `Conditional` layout: `tx_type || to || token || amount || fee || predicate_kind || predicate_payload`.
+    /// `from` isn't part of the pubdata; like `Deposit`, it comes from the L1 event's sender.
+    fn parse_conditional_v0(pub_data: &[u8], sender: Address) -> Result<Self, anyhow::Error> {
+        ensure!(
+            pub_data.len() >= TX_TYPE_BIT_WIDTH / 8,
+            "PubData length mismatch"
+        );
+        let (_, pub_data_left) = pub_data.split_at(TX_TYPE_BIT_WIDTH / 8);
+
+        // to
+        let (to, pub_data_left) = {
+            ensure!(
+                pub_data_left.len() >= FR_ADDRESS_LEN,
+                "PubData length mismatch"
+            );
+            let (to, left) = pub_data_left.split_at(FR_ADDRESS_LEN);
+            (Address::from_slice(to), left)
+        };
+
+        // token
+        let (token, pub_data_left) = {
+            ensure!(
+                pub_data_left.len() >= TOKEN_BIT_WIDTH / 8,
+                "PubData length mismatch"
+            );
+            let (token, left) = pub_data_left.split_at(TOKEN_BIT_WIDTH / 8);
+            (u16::from_be_bytes(token.try_into().unwrap()), left)
+        };
+
+        // amount
+        let (amount, pub_data_left) = {
+            ensure!(
+                pub_data_left.len() >= BALANCE_BIT_WIDTH / 8,
+                "PubData length mismatch"
+            );
+            let (amount, left) = pub_data_left.split_at(BALANCE_BIT_WIDTH / 8);
+            let amount = u128::from_be_bytes(amount.try_into().unwrap());
+            (BigUint::from(amount), left)
+        };
+
+        // fee
+        let (fee, pub_data_left) = {
+            let fee_len = (FEE_EXPONENT_BIT_WIDTH + FEE_MANTISSA_BIT_WIDTH) / 8;
+            ensure!(pub_data_left.len() >= fee_len, "PubData length mismatch");
+            let (fee, left) = pub_data_left.split_at(fee_len);
+            let fee = unpack_fee_amount(fee)
+                .ok_or_else(|| format_err!("Cant unpack conditional fee from pubdata"))?;
+            (fee, left)
+        };
+
+        // predicate
+        let predicate = {
+            ensure!(
+                pub_data_left.len() == 1 + FR_ADDRESS_LEN,
+                "Conditional parse failed: input too big"
+            );
+            let (kind, payload) = pub_data_left.split_at(1);
+            ConditionalPredicate::from_bytes(kind[0], payload)?
+        };
+
+        Ok(Self::Conditional(Conditional {
+            from: sender,
+            to,
+            token: TokenId(token),
+            amount,
+            fee,
+            predicate,
+        }))
+    }
+
+    /// Version-0 `RangeSettle` layout: `tx_type || to_a || to_b || token ||
+    /// amount || fee || oracle_pubkey_hash || base || num_digits ||
+    /// refund_payout_a_bp || condition_count || conditions`, where each
+    /// condition is `prefix_len || prefix digits (u64 each) || payout_a_bp`.
+    /// Unlike the fixed-`CHUNKS` L2 commit pubdata (`RangeSettleOp`, which
+    /// only ever commits the curve's `commitment()`), the L1 priority-queue
+    /// log itself carries the curve in full, the same way `from` isn't part
+    /// of `Conditional`'s pubdata but comes from the L1 event's sender.
+    fn parse_range_settle_v0(pub_data: &[u8], sender: Address) -> Result<Self, anyhow::Error> {
+        ensure!(
+            pub_data.len() >= TX_TYPE_BIT_WIDTH / 8,
+            "PubData length mismatch"
+        );
+        let (_, pub_data_left) = pub_data.split_at(TX_TYPE_BIT_WIDTH / 8);
+
+        let (to_a, pub_data_left) = {
+            ensure!(pub_data_left.len() >= FR_ADDRESS_LEN, "PubData length mismatch");
+            let (to_a, left) = pub_data_left.split_at(FR_ADDRESS_LEN);
+            (Address::from_slice(to_a), left)
+        };
+        let (to_b, pub_data_left) = {
+            ensure!(pub_data_left.len() >= FR_ADDRESS_LEN, "PubData length mismatch");
+            let (to_b, left) = pub_data_left.split_at(FR_ADDRESS_LEN);
+            (Address::from_slice(to_b), left)
+        };
+        let (token, pub_data_left) = {
+            ensure!(pub_data_left.len() >= TOKEN_BIT_WIDTH / 8, "PubData length mismatch");
+            let (token, left) = pub_data_left.split_at(TOKEN_BIT_WIDTH / 8);
+            (u16::from_be_bytes(token.try_into().unwrap()), left)
+        };
+        let (amount, pub_data_left) = {
+            ensure!(pub_data_left.len() >= BALANCE_BIT_WIDTH / 8, "PubData length mismatch");
+            let (amount, left) = pub_data_left.split_at(BALANCE_BIT_WIDTH / 8);
+            let amount = u128::from_be_bytes(amount.try_into().unwrap());
+            (BigUint::from(amount), left)
+        };
+        let (fee, pub_data_left) = {
+            let fee_len = (FEE_EXPONENT_BIT_WIDTH + FEE_MANTISSA_BIT_WIDTH) / 8;
+            ensure!(pub_data_left.len() >= fee_len, "PubData length mismatch");
+            let (fee, left) = pub_data_left.split_at(fee_len);
+            let fee = unpack_fee_amount(fee)
+                .ok_or_else(|| format_err!("Cant unpack range settle fee from pubdata"))?;
+            (fee, left)
+        };
+        let (oracle_pubkey_hash, pub_data_left) = {
+            ensure!(pub_data_left.len() >= FR_ADDRESS_LEN, "PubData length mismatch");
+            let (payload, left) = pub_data_left.split_at(FR_ADDRESS_LEN);
+            (PubKeyHash::from_bytes(payload)?, left)
+        };
+        let (base, pub_data_left) = {
+            ensure!(pub_data_left.len() >= 8, "PubData length mismatch");
+            let (base, left) = pub_data_left.split_at(8);
+            (u64::from_be_bytes(base.try_into().unwrap()), left)
+        };
+        let (num_digits, pub_data_left) = {
+            ensure!(pub_data_left.len() >= 8, "PubData length mismatch");
+            let (num_digits, left) = pub_data_left.split_at(8);
+            (u64::from_be_bytes(num_digits.try_into().unwrap()) as usize, left)
+        };
+        let (refund_payout_a_bp, pub_data_left) = {
+            ensure!(pub_data_left.len() >= 2, "PubData length mismatch");
+            let (bp, left) = pub_data_left.split_at(2);
+            (u16::from_be_bytes(bp.try_into().unwrap()), left)
+        };
+        ensure!(!pub_data_left.is_empty(), "PubData length mismatch");
+        let (condition_count, mut pub_data_left) = {
+            let (count, left) = pub_data_left.split_at(1);
+            (count[0] as usize, left)
+        };
+
+        let mut conditions = Vec::with_capacity(condition_count);
+        for _ in 0..condition_count {
+            ensure!(!pub_data_left.is_empty(), "PubData length mismatch");
+            let (prefix_len, left) = pub_data_left.split_at(1);
+            let prefix_len = prefix_len[0] as usize;
+            ensure!(left.len() >= prefix_len * 8 + 2, "PubData length mismatch");
+            let (prefix_bytes, left) = left.split_at(prefix_len * 8);
+            let prefix = prefix_bytes
+                .chunks_exact(8)
+                .map(|chunk| u64::from_be_bytes(chunk.try_into().unwrap()))
+                .collect();
+            let (payout_bytes, left) = left.split_at(2);
+            let payout_a_bp = u16::from_be_bytes(payout_bytes.try_into().unwrap());
+            conditions.push(crate::dlc::RangeSettleCondition { prefix, payout_a_bp });
+            pub_data_left = left;
+        }
+        ensure!(
+            pub_data_left.is_empty(),
+            "RangeSettle parse failed: input too big"
+        );
+
+        Ok(Self::RangeSettle(RangeSettle {
+            from: sender,
+            to_a,
+            to_b,
+            token: TokenId(token),
+            amount,
+            fee,
+            oracle_pubkey_hash,
+            curve: RangeSettleCurve {
+                base,
+                num_digits,
+                conditions,
+                refund_payout_a_bp,
+            },
+        }))
+    }
+
     /// Returns the amount of chunks required to include the priority operation into the block.
     pub fn chunks(&self) -> usize {
         match self {
             Self::Deposit(_) => DepositOp::CHUNKS,
             Self::FullExit(_) => FullExitOp::CHUNKS,
+            Self::Conditional(_) => ConditionalOp::CHUNKS,
+            Self::RangeSettle(_) => RangeSettleOp::CHUNKS,
         }
     }
 
@@ -0,0 +1,87 @@
+//! Per-token maximum transaction amounts, enforced by the state handlers in
+//! addition to the usual balance/nonce/signature checks.
+
+use crate::TokenId;
+use anyhow::ensure;
+use num::BigUint;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A single token's configured limit, expressed in the token's smallest unit.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TokenLimit {
+    pub id: TokenId,
+    pub decimals: u8,
+    pub max_tx_amount: BigUint,
+}
+
+impl TokenLimit {
+    /// Builds a limit from a whole-token amount, scaling it up by the token's
+    /// `decimals` so callers can configure limits in human units (e.g. `10_000` ATP)
+    /// instead of pre-computed smallest-unit integers.
+    pub fn from_whole_tokens(id: TokenId, decimals: u8, whole_tokens: u64) -> Self {
+        Self {
+            id,
+            decimals,
+            max_tx_amount: BigUint::from(whole_tokens) * BigUint::from(10u64).pow(decimals as u32),
+        }
+    }
+}
+
+/// Lookup table of [`TokenLimit`]s, keyed by token. Tokens with no configured
+/// entry are treated as unlimited, so rolling this feature out for a subset of
+/// tokens doesn't require listing every token in the network.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TokenLimits {
+    limits: HashMap<TokenId, TokenLimit>,
+}
+
+impl TokenLimits {
+    pub fn new(limits: Vec<TokenLimit>) -> Self {
+        Self {
+            limits: limits.into_iter().map(|limit| (limit.id, limit)).collect(),
+        }
+    }
+
+    pub fn max_tx_amount(&self, token: TokenId) -> Option<&BigUint> {
+        self.limits.get(&token).map(|limit| &limit.max_tx_amount)
+    }
+
+    /// Checks `amount` against the configured limit for `token`, if any.
+    pub fn check(&self, token: TokenId, amount: &BigUint) -> Result<(), anyhow::Error> {
+        if let Some(limit) = self.max_tx_amount(token) {
+            ensure!(
+                amount <= limit,
+                "Amount {} for token {} exceeds the configured limit of {}",
+                amount,
+                *token,
+                limit
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_whole_tokens_scales_by_decimals() {
+        let limit = TokenLimit::from_whole_tokens(TokenId(1), 6, 10_000);
+        assert_eq!(limit.max_tx_amount, BigUint::from(10_000_000_000u64));
+    }
+
+    #[test]
+    fn unconfigured_token_is_unlimited() {
+        let limits = TokenLimits::new(vec![TokenLimit::from_whole_tokens(TokenId(1), 18, 1)]);
+        assert!(limits.check(TokenId(2), &BigUint::from(u128::max_value())).is_ok());
+    }
+
+    #[test]
+    fn amount_over_limit_is_rejected() {
+        let limits = TokenLimits::new(vec![TokenLimit::from_whole_tokens(TokenId(1), 0, 100)]);
+        assert!(limits.check(TokenId(1), &BigUint::from(100u64)).is_ok());
+        assert!(limits.check(TokenId(1), &BigUint::from(101u64)).is_err());
+    }
+}
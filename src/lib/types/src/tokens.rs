@@ -1,8 +1,10 @@
 use crate::{Address, TokenId};
+use anyhow::ensure;
 use chrono::{DateTime, Utc};
 use num::{rational::Ratio, BigUint};
 use serde::{Deserialize, Serialize};
 use std::{fmt, fs::read_to_string, path::PathBuf, str::FromStr};
+use zkdpos_utils::format_units;
 use zkdpos_utils::parse_env;
 use zkdpos_utils::UnsignedRatioSerializeAsDecimal;
 
@@ -53,11 +55,30 @@ impl fmt::Display for TokenLike {
     }
 }
 
+/// Disambiguation hint for [`TokenLike::parse_with_hint`], letting the caller
+/// override the default "a bare number is a token ID" interpretation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenLikeHint {
+    /// Interpret a bare number as a token ID (the default, see [`TokenLike::parse`]).
+    Id,
+    /// Interpret a bare number as a symbol, never as a token ID.
+    NotId,
+}
+
 impl TokenLike {
     pub fn parse(value: &str) -> Self {
+        Self::parse_with_hint(value, TokenLikeHint::Id)
+    }
+
+    /// Same as [`Self::parse`], but lets the caller indicate that a bare number
+    /// should not be interpreted as a token ID, e.g. because it is known to be
+    /// a symbol such as "8" for some memecoin ticker.
+    pub fn parse_with_hint(value: &str, hint: TokenLikeHint) -> Self {
         // Try to interpret an address as the token ID.
-        if let Ok(id) = u16::from_str(value) {
-            return Self::Id(TokenId(id));
+        if hint == TokenLikeHint::Id {
+            if let Ok(id) = u16::from_str(value) {
+                return Self::Id(TokenId(id));
+            }
         }
         // Try to interpret a token as the token address with or without a prefix.
         let maybe_address = if let Some(value) = value.strip_prefix("0x") {
@@ -117,6 +138,39 @@ impl Token {
     }
 }
 
+/// Converts a human-readable decimal amount (e.g. `"1.25"`) into base units, using
+/// `token.decimals` to determine the scaling factor. Rejects amounts with more
+/// fractional digits than the token's denomination supports, since those digits
+/// can't be represented without losing precision.
+pub fn parse_amount(token: &Token, input: &str) -> Result<BigUint, anyhow::Error> {
+    let (whole, fractional) = match input.split_once('.') {
+        Some((whole, fractional)) => (whole, fractional),
+        None => (input, ""),
+    };
+    ensure!(
+        fractional.len() <= token.decimals as usize,
+        "Amount '{}' has more fractional digits than '{}' supports ({} decimals)",
+        input,
+        token.symbol,
+        token.decimals
+    );
+
+    let mut digits = String::with_capacity(whole.len() + token.decimals as usize);
+    digits.push_str(if whole.is_empty() { "0" } else { whole });
+    digits.push_str(fractional);
+    digits.push_str(&"0".repeat(token.decimals as usize - fractional.len()));
+
+    digits
+        .parse::<BigUint>()
+        .map_err(|_| anyhow::anyhow!("'{}' is not a valid decimal amount", input))
+}
+
+/// Formats a base-units amount as a human-readable decimal string, the inverse of
+/// [`parse_amount`].
+pub fn format_amount(token: &Token, amount: &BigUint) -> String {
+    format_units(amount, token.decimals)
+}
+
 // Hidden as it relies on the filesystem structure, which can be different for reverse dependencies.
 #[doc(hidden)]
 pub fn get_genesis_token_list(network: &str) -> Result<Vec<TokenGenesisListItem>, anyhow::Error> {
@@ -149,6 +203,7 @@ pub enum ChangePubKeyFeeType {
     Onchain,
     ECDSA,
     CREATE2,
+    EIP712,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Hash, Eq)]
@@ -231,5 +286,34 @@ mod tests {
                 ChangePubKeyFeeType::CREATE2
             ))
         );
+
+        let deserialized: TxFeeTypes =
+            serde_json::from_str(r#"{ "ChangePubKey": "EIP712" }"#).unwrap();
+
+        assert_eq!(
+            deserialized,
+            TxFeeTypes::ChangePubKey(ChangePubKeyFeeTypeArg::ContractsV4Version(
+                ChangePubKeyFeeType::EIP712
+            ))
+        );
+    }
+
+    #[test]
+    fn amount_parse_and_format_round_trip() {
+        let token = Token::new(TokenId(0), Address::zero(), "ATP", 18);
+
+        let amount = parse_amount(&token, "1.25").unwrap();
+        assert_eq!(amount, BigUint::from(1_250_000_000_000_000_000u128));
+        assert_eq!(
+            parse_amount(&token, &format_amount(&token, &amount)).unwrap(),
+            amount
+        );
+
+        assert_eq!(
+            parse_amount(&token, "5").unwrap(),
+            BigUint::from(5u128) * BigUint::from(10u128).pow(18)
+        );
+
+        assert!(parse_amount(&token, "1.0000000000000000001").is_err());
     }
 }
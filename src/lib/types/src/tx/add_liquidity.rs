@@ -1,21 +1,26 @@
 use crate::{
+    dlc::{self, DigitPrefix, PRICE_RANGE_BASE, PRICE_RANGE_DIGITS},
     helpers::{
         is_fee_amount_packable, is_token_amount_packable, pack_fee_amount, pack_token_amount,
     },
     tx::TimeRange,
-    AccountId, LiquidityId, Nonce, TokenId,
+    AccountId, Fraction, LiquidityId, Nonce, TokenId,
 };
 use num::BigUint;
 
 use crate::account::PubKeyHash;
 use crate::Engine;
+use parity_crypto::Keccak256;
 use serde::{Deserialize, Serialize};
-use zkdpos_basic_types::Address;
+use zkdpos_basic_types::{Address, H256};
 use zkdpos_crypto::franklin_crypto::eddsa::PrivateKey;
 use zkdpos_crypto::params::{max_account_id};
 use zkdpos_utils::{format_units, BigUintSerdeAsRadix10Str};
 
-use super::{TxSignature, VerifiedSignatureCache};
+use super::{
+    eip712_digest, eip712_domain_separator, encode_word, AccountSignerSet, ThresholdMusigSignature,
+    TxSignature, VerifiableSignature, VerifiedSignatureCache,
+};
 
 /// `AddLiquidity` transaction performs a move of funds from one zkDpos account to another.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,14 +44,22 @@ pub struct AddLiquidity {
     /// amountB Min of funds to add liquidity.
     #[serde(with = "BigUintSerdeAsRadix10Str")]
     pub amount_b_min: BigUint,
-    /// Type of token for transfer. Also represents the token in which fee will be paid.
-    pub token: TokenId,
+    /// First token of the pool, and the token `fee_a` is paid in.
+    pub token_a: TokenId,
+    /// Second token of the pool, and the token `fee_b` is paid in.
+    pub token_b: TokenId,
     /// Fee A for the transaction.
     #[serde(with = "BigUintSerdeAsRadix10Str")]
     pub fee_a: BigUint,
     /// Fee B for the transaction.
     #[serde(with = "BigUintSerdeAsRadix10Str")]
     pub fee_b: BigUint,
+    /// Lower bound (inclusive) of the price band, in `amount_b_desired /
+    /// amount_a_desired` terms scaled by [`crate::pool::PRICE_SCALE`], this
+    /// deposit concentrates its liquidity in.
+    pub p_low: u64,
+    /// Upper bound (exclusive) of the price band.
+    pub p_high: u64,
     /// Current account nonce.
     pub nonce: Nonce,
     /// Time range when the transaction is valid
@@ -55,6 +68,10 @@ pub struct AddLiquidity {
     pub time_range: Option<TimeRange>,
     /// Transaction zkDpos signature.
     pub signature: TxSignature,
+    /// If set, authorizes this `AddLiquidity` via the account's registered
+    /// threshold-multisig signer set instead of `signature`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub threshold_auth: Option<ThresholdMusigSignature>,
     #[serde(skip)]
     cached_signer: VerifiedSignatureCache,
 }
@@ -76,9 +93,12 @@ impl AddLiquidity {
         amount_b_desired: BigUint,
         amount_a_min: BigUint,
         amount_b_min: BigUint,
-        token: TokenId,
+        token_a: TokenId,
+        token_b: TokenId,
         fee_a: BigUint,
         fee_b: BigUint,
+        p_low: u64,
+        p_high: u64,
         nonce: Nonce,
         time_range: TimeRange,
         signature: Option<TxSignature>,
@@ -91,12 +111,16 @@ impl AddLiquidity {
             amount_b_desired,
             amount_a_min,
             amount_b_min,
-            token,
+            token_a,
+            token_b,
             fee_a,
             fee_b,
+            p_low,
+            p_high,
             nonce,
             time_range: Some(time_range),
             signature: signature.clone().unwrap_or_default(),
+            threshold_auth: None,
             cached_signer: VerifiedSignatureCache::NotCached,
         };
         if signature.is_some() {
@@ -116,15 +140,18 @@ impl AddLiquidity {
         amount_b_desired: BigUint,
         amount_a_min: BigUint,
         amount_b_min: BigUint,
-        token: TokenId,
+        token_a: TokenId,
+        token_b: TokenId,
         fee_a: BigUint,
         fee_b: BigUint,
+        p_low: u64,
+        p_high: u64,
         nonce: Nonce,
         time_range: TimeRange,
         private_key: &PrivateKey<Engine>,
     ) -> Result<Self, anyhow::Error> {
         let mut tx = Self::new(
-            account_id, liquidity_id, to, amount_a_desired, amount_b_desired, amount_a_min,  amount_b_min, token, fee_a, fee_b, nonce, time_range, None,
+            account_id, liquidity_id, to, amount_a_desired, amount_b_desired, amount_a_min, amount_b_min, token_a, token_b, fee_a, fee_b, p_low, p_high, nonce, time_range, None,
         );
         tx.signature = TxSignature::sign_musig(private_key, &tx.get_bytes());
         if !tx.check_correctness() {
@@ -139,12 +166,16 @@ impl AddLiquidity {
         out.extend_from_slice(&[Self::TX_TYPE]);
         out.extend_from_slice(&self.account_id.to_be_bytes());
         out.extend_from_slice(&self.to.as_bytes());
+        out.extend_from_slice(&self.token_a.to_be_bytes());
+        out.extend_from_slice(&self.token_b.to_be_bytes());
         out.extend_from_slice(&pack_token_amount(&self.amount_a_desired));
         out.extend_from_slice(&pack_token_amount(&self.amount_b_desired));
         out.extend_from_slice(&pack_token_amount(&self.amount_a_min));
         out.extend_from_slice(&pack_token_amount(&self.amount_b_min));
         out.extend_from_slice(&pack_fee_amount(&self.fee_a));
         out.extend_from_slice(&pack_fee_amount(&self.fee_b));
+        out.extend_from_slice(&self.p_low.to_be_bytes());
+        out.extend_from_slice(&self.p_high.to_be_bytes());
         out.extend_from_slice(&self.nonce.to_be_bytes());
         if let Some(time_range) = &self.time_range {
             out.extend_from_slice(&time_range.to_be_bytes());
@@ -159,6 +190,7 @@ impl AddLiquidity {
     /// - `amount` field must represent a packable value.
     /// - `fee` field must represent a packable value.
     /// - add liquidity recipient must not be `Adddress::zero()`.
+    /// - `p_low`/`p_high` must describe a well-formed, representable price band.
     /// - zkDpos signature must correspond to the PubKeyHash of the account.
     pub fn check_correctness(&mut self) -> bool {
         let mut valid = self.amount_a_desired <= BigUint::from(u128::max_value())
@@ -167,18 +199,98 @@ impl AddLiquidity {
             && is_fee_amount_packable(&self.fee_b)
             && self.account_id <= max_account_id()
             && self.to != Address::zero()
+            && self.p_low <= self.p_high
+            && PRICE_RANGE_BASE
+                .checked_pow(PRICE_RANGE_DIGITS as u32)
+                .map_or(true, |max| self.p_high <= max)
             && self
                 .time_range
                 .map(|r| r.check_correctness())
+                .unwrap_or(true)
+            && self
+                .threshold_auth
+                .as_ref()
+                .map(|auth| {
+                    !auth.signatures.is_empty()
+                        && auth.participant_bitmap.count_ones() as usize == auth.signatures.len()
+                })
                 .unwrap_or(true);
         if valid {
             let signer = self.verify_signature();
-            valid = valid && signer.is_some();
+            valid = valid && (signer.is_some() || self.threshold_auth.is_some());
             self.cached_signer = VerifiedSignatureCache::Cached(signer);
         };
         valid
     }
 
+    /// The desired deposit ratio `amount_b_desired / amount_a_desired`, as an exact
+    /// [`Fraction`] over the full-precision amounts the caller supplied. The state
+    /// applier compares this against [`crate::Pool::effective_ratio`] with
+    /// [`Fraction::eq_exact`] instead of either side ever being divided out, so the
+    /// ratio check can't be fooled by rounding drift from a lossy intermediate value.
+    pub fn effective_ratio(&self) -> Fraction {
+        Fraction::new(self.amount_b_desired.clone(), self.amount_a_desired.clone())
+    }
+
+    /// Computes the `hashStruct` of this transaction's EIP-712 typed struct, the
+    /// part of the digest specific to the transaction rather than the signing
+    /// domain.
+    pub fn eip712_struct_hash(&self) -> H256 {
+        let mut preimage = Vec::new();
+        preimage.extend_from_slice(
+            &b"AddLiquidity(address to,uint16 tokenA,uint16 tokenB,uint256 amountADesired,\
+               uint256 amountBDesired,uint256 amountAMin,uint256 amountBMin,uint256 feeA,\
+               uint256 feeB,uint64 pLow,uint64 pHigh,uint32 nonce)"
+                .to_vec()
+                .keccak256(),
+        );
+        preimage.extend_from_slice(&encode_word(self.to.as_bytes()));
+        preimage.extend_from_slice(&encode_word(&self.token_a.to_be_bytes()));
+        preimage.extend_from_slice(&encode_word(&self.token_b.to_be_bytes()));
+        preimage.extend_from_slice(&encode_word(&self.amount_a_desired.to_bytes_be()));
+        preimage.extend_from_slice(&encode_word(&self.amount_b_desired.to_bytes_be()));
+        preimage.extend_from_slice(&encode_word(&self.amount_a_min.to_bytes_be()));
+        preimage.extend_from_slice(&encode_word(&self.amount_b_min.to_bytes_be()));
+        preimage.extend_from_slice(&encode_word(&self.fee_a.to_bytes_be()));
+        preimage.extend_from_slice(&encode_word(&self.fee_b.to_bytes_be()));
+        preimage.extend_from_slice(&encode_word(&self.p_low.to_be_bytes()));
+        preimage.extend_from_slice(&encode_word(&self.p_high.to_be_bytes()));
+        preimage.extend_from_slice(&encode_word(&self.nonce.to_be_bytes()));
+        H256::from_slice(&preimage.keccak256())
+    }
+
+    /// Decomposes this deposit's `[p_low, p_high)` price band into the
+    /// minimal set of digit prefixes a trade price can be checked against
+    /// with a single prefix-match (see [`dlc::decompose_half_open_range`]).
+    /// Derived on demand from the committed `p_low`/`p_high` bounds rather
+    /// than stored alongside them, so the pubdata this transaction commits to
+    /// stays the fixed width every other operation's pubdata uses instead of
+    /// growing with the band's prefix count.
+    pub fn price_band_prefixes(&self) -> anyhow::Result<Vec<DigitPrefix>> {
+        dlc::decompose_half_open_range(self.p_low, self.p_high, PRICE_RANGE_BASE, PRICE_RANGE_DIGITS)
+    }
+
+    /// `true` if `price` (scaled the same way as `p_low`/`p_high`) falls
+    /// inside this deposit's band, checked via a digit-prefix match against
+    /// [`Self::price_band_prefixes`] rather than a direct `p_low <= price <
+    /// p_high` comparison, since the prefix form is what the commitment
+    /// circuit can verify cheaply.
+    pub fn covers_price(&self, price: u64) -> anyhow::Result<bool> {
+        let prefixes = self.price_band_prefixes()?;
+        Ok(prefixes
+            .iter()
+            .any(|prefix| dlc::outcome_matches_prefix(price, PRICE_RANGE_BASE, PRICE_RANGE_DIGITS, prefix)))
+    }
+
+    /// Computes the EIP-712 typed-data digest that must be signed with the Alaya
+    /// private key in order to authorize this `AddLiquidity` transaction.
+    /// `chain_id` and `verifying_contract` pin the signature to a specific
+    /// deployment of the zkDpos contract, so it can't be replayed across networks.
+    pub fn get_eip712_signed_data(&self, chain_id: u32, verifying_contract: Address) -> H256 {
+        let domain_separator = eip712_domain_separator(chain_id, verifying_contract);
+        eip712_digest(&domain_separator, &self.eip712_struct_hash())
+    }
+
     /// Restores the `PubKeyHash` from the transaction signature.
     pub fn verify_signature(&self) -> Option<PubKeyHash> {
         if let VerifiedSignatureCache::Cached(cached_signer) = &self.cached_signer {
@@ -190,6 +302,16 @@ impl AddLiquidity {
         }
     }
 
+    /// Verifies that this `AddLiquidity`'s `threshold_auth` (if present) meets
+    /// `key_set`'s quorum over `get_bytes()`, recognizing the account's
+    /// registered threshold-multisig signers as an alternative to the single
+    /// `signature` field. Returns the signer set's key commitment on success.
+    pub fn verify_threshold_auth(&self, key_set: &AccountSignerSet) -> Option<PubKeyHash> {
+        self.threshold_auth
+            .as_ref()
+            .and_then(|auth| auth.verify(&self.get_bytes(), key_set))
+    }
+
     /// Get the first part of the message we expect to be signed by Alaya account key.
     /// The only difference is the missing `nonce` since it's added at the end of the transactions
     /// batch message.
@@ -241,3 +363,9 @@ impl AddLiquidity {
         )
     }
 }
+
+impl VerifiableSignature for AddLiquidity {
+    fn verify_signature(&self) -> Option<PubKeyHash> {
+        Self::verify_signature(self)
+    }
+}
@@ -0,0 +1,187 @@
+use crate::{
+    helpers::{is_fee_amount_packable, pack_fee_amount},
+    AccountId, Nonce, TokenId,
+};
+
+use crate::account::PubKeyHash;
+use crate::Engine;
+use num::BigUint;
+use parity_crypto::Keccak256;
+use serde::{Deserialize, Serialize};
+use zkdpos_basic_types::{Address, H256};
+use zkdpos_crypto::franklin_crypto::eddsa::PrivateKey;
+use zkdpos_crypto::params::{max_account_id, max_token_id};
+use zkdpos_utils::BigUintSerdeAsRadix10Str;
+
+use super::{TimeRange, TxSignature, VerifiableSignature, VerifiedSignatureCache};
+
+/// Rotates an account's durable nonce - a secondary, non-sequential nonce
+/// that other transactions can bind to instead of the strictly incrementing
+/// `Nonce`, modeled on Solana's durable transaction nonces.
+///
+/// A transaction signed against a durable nonce stays valid for as long as
+/// the referenced value remains current, rather than expiring the moment any
+/// other transaction from the account lands; submitting `AdvanceNonce` rolls
+/// the stored value forward and invalidates every transaction that was
+/// signed against the old one. This lets callers pre-sign transactions that
+/// never expire and can be broadcast out of order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdvanceNonce {
+    /// zkDpos network account ID of the transaction initiator.
+    pub account_id: AccountId,
+    /// Address of the account whose durable nonce is being advanced.
+    pub account: Address,
+    /// The durable nonce value the caller expects to still be current;
+    /// advancing fails if the account's stored value has since moved on.
+    pub expected_durable_nonce: H256,
+    /// Token the fee is paid in.
+    pub fee_token: TokenId,
+    /// Fee for the transaction.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub fee: BigUint,
+    /// Current (sequential) account nonce, authorizing this transaction itself.
+    pub nonce: Nonce,
+    /// Time range when the transaction is valid.
+    #[serde(flatten)]
+    pub time_range: Option<TimeRange>,
+    /// Transaction zkDpos signature.
+    pub signature: TxSignature,
+    #[serde(skip)]
+    cached_signer: VerifiedSignatureCache,
+}
+
+impl AdvanceNonce {
+    /// Unique identifier of the transaction type in zkDpos network.
+    pub const TX_TYPE: u8 = 16;
+
+    /// Creates transaction from all the required fields.
+    ///
+    /// While `signature` field is mandatory for new transactions, it may be `None`
+    /// in some cases (e.g. when restoring the network state from the L1 contract data).
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        account_id: AccountId,
+        account: Address,
+        expected_durable_nonce: H256,
+        fee_token: TokenId,
+        fee: BigUint,
+        nonce: Nonce,
+        time_range: TimeRange,
+        signature: Option<TxSignature>,
+    ) -> Self {
+        let mut tx = Self {
+            account_id,
+            account,
+            expected_durable_nonce,
+            fee_token,
+            fee,
+            nonce,
+            time_range: Some(time_range),
+            signature: signature.clone().unwrap_or_default(),
+            cached_signer: VerifiedSignatureCache::NotCached,
+        };
+        if signature.is_some() {
+            tx.cached_signer = VerifiedSignatureCache::Cached(tx.verify_signature());
+        }
+        tx
+    }
+
+    /// Creates a signed transaction using private key and
+    /// checks for the transaction correcteness.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_signed(
+        account_id: AccountId,
+        account: Address,
+        expected_durable_nonce: H256,
+        fee_token: TokenId,
+        fee: BigUint,
+        nonce: Nonce,
+        time_range: TimeRange,
+        private_key: &PrivateKey<Engine>,
+    ) -> Result<Self, anyhow::Error> {
+        let mut tx = Self::new(
+            account_id,
+            account,
+            expected_durable_nonce,
+            fee_token,
+            fee,
+            nonce,
+            time_range,
+            None,
+        );
+        tx.signature = TxSignature::sign_musig(private_key, &tx.get_bytes());
+        if !tx.check_correctness() {
+            anyhow::bail!(crate::tx::TRANSACTION_SIGNATURE_ERROR);
+        }
+        Ok(tx)
+    }
+
+    /// Encodes the transaction data as the byte sequence according to the zkDpos protocol.
+    pub fn get_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&[Self::TX_TYPE]);
+        out.extend_from_slice(&self.account_id.to_be_bytes());
+        out.extend_from_slice(self.account.as_bytes());
+        out.extend_from_slice(self.expected_durable_nonce.as_bytes());
+        out.extend_from_slice(&self.fee_token.to_be_bytes());
+        out.extend_from_slice(&pack_fee_amount(&self.fee));
+        out.extend_from_slice(&self.nonce.to_be_bytes());
+        if let Some(time_range) = &self.time_range {
+            out.extend_from_slice(&time_range.to_be_bytes());
+        }
+        out
+    }
+
+    /// Verifies the transaction correctness:
+    ///
+    /// - `account_id` field must be within supported range.
+    /// - `fee_token` field must be within supported range.
+    /// - `fee` field must represent a packable value.
+    /// - zkDpos signature must correspond to the PubKeyHash of the account.
+    pub fn check_correctness(&mut self) -> bool {
+        let mut valid = is_fee_amount_packable(&self.fee)
+            && self.account_id <= max_account_id()
+            && self.fee_token <= max_token_id()
+            && self
+                .time_range
+                .map(|r| r.check_correctness())
+                .unwrap_or(true);
+
+        if valid {
+            let signer = self.verify_signature();
+            valid = valid && signer.is_some();
+            self.cached_signer = VerifiedSignatureCache::Cached(signer);
+        }
+        valid
+    }
+
+    /// Restores the `PubKeyHash` from the transaction signature.
+    pub fn verify_signature(&self) -> Option<PubKeyHash> {
+        if let VerifiedSignatureCache::Cached(cached_signer) = &self.cached_signer {
+            *cached_signer
+        } else {
+            self.signature
+                .verify_musig(&self.get_bytes())
+                .map(|pub_key| PubKeyHash::from_pubkey(&pub_key))
+        }
+    }
+}
+
+impl VerifiableSignature for AdvanceNonce {
+    fn verify_signature(&self) -> Option<PubKeyHash> {
+        Self::verify_signature(self)
+    }
+}
+
+/// Deterministically rotates a durable nonce forward:
+/// `keccak256(prev || block_number)`. Keying the rotation off the committed
+/// block number (rather than fresh randomness) keeps it fully reproducible
+/// from chain state, which is what lets `create_op` and block verification
+/// agree on `new_durable_nonce` without any extra witness data.
+pub fn next_durable_nonce(prev: H256, block_number: u64) -> H256 {
+    let mut bytes = Vec::with_capacity(40);
+    bytes.extend_from_slice(prev.as_bytes());
+    bytes.extend_from_slice(&block_number.to_be_bytes());
+    H256::from_slice(&bytes.keccak256())
+}
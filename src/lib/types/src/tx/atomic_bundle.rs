@@ -0,0 +1,132 @@
+//! Atomic multi-operation L2 transactions.
+//!
+//! Modeled on Solana's multi-instruction transactions: an [`AtomicBundle`]
+//! groups several inner [`ZkDposTx`] operations behind one nonce slot and one
+//! signature, so a wallet can express "do all of these, or none of these" as
+//! a single signing request instead of submitting each op separately (and
+//! risking that only some of them land).
+//!
+//! Note: this only covers the protocol-level type -- encoding, signing, and
+//! well-formedness checks. Applying a bundle with true all-or-nothing
+//! semantics requires the state keeper to execute every inner op against a
+//! scratch copy of the state and only commit it if every op succeeds; that
+//! wiring lives in `ZkDposState`/`ZkDposOp`, which this tree doesn't carry
+//! (most op handlers and `state.rs` itself aren't present here), so it isn't
+//! implemented in this commit.
+
+use crate::{AccountId, Nonce};
+
+use crate::account::PubKeyHash;
+use crate::Engine;
+use serde::{Deserialize, Serialize};
+use zkdpos_crypto::franklin_crypto::eddsa::PrivateKey;
+
+use super::{TxSignature, VerifiedSignatureCache, ZkDposTx};
+
+/// A group of L2 transactions that share a single nonce slot and are
+/// authorized by a single signature over all of them combined, so that none
+/// can be dropped, reordered, or substituted without invalidating it. Each
+/// inner transaction still carries and is checked against its own signature
+/// as well (see [`Self::check_correctness`]).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AtomicBundle {
+    /// zkDpos network account ID of the bundle's initiator.
+    pub account_id: AccountId,
+    /// Inner transactions, applied in order. Their individual `nonce` fields
+    /// are ignored in favor of `Self::nonce`.
+    pub txs: Vec<ZkDposTx>,
+    /// The single nonce slot shared by every inner transaction.
+    pub nonce: Nonce,
+    /// Signature over the bundle.
+    pub signature: TxSignature,
+    #[serde(skip)]
+    cached_signer: VerifiedSignatureCache,
+}
+
+impl AtomicBundle {
+    /// Creates a bundle from all the required fields.
+    ///
+    /// While `signature` field is mandatory for new transactions, it may be `None`
+    /// in some cases (e.g. when restoring the network state from the L1 contract data).
+    pub fn new(
+        account_id: AccountId,
+        txs: Vec<ZkDposTx>,
+        nonce: Nonce,
+        signature: Option<TxSignature>,
+    ) -> Self {
+        let mut bundle = Self {
+            account_id,
+            txs,
+            nonce,
+            signature: signature.clone().unwrap_or_default(),
+            cached_signer: VerifiedSignatureCache::NotCached,
+        };
+        if signature.is_some() {
+            bundle.cached_signer = VerifiedSignatureCache::Cached(bundle.verify_signature());
+        }
+        bundle
+    }
+
+    /// Creates a signed bundle using private key and
+    /// checks for the transaction correcteness.
+    pub fn new_signed(
+        account_id: AccountId,
+        txs: Vec<ZkDposTx>,
+        nonce: Nonce,
+        private_key: &PrivateKey<Engine>,
+    ) -> Result<Self, anyhow::Error> {
+        let mut bundle = Self::new(account_id, txs, nonce, None);
+        bundle.signature = TxSignature::sign_musig(private_key, &bundle.get_bytes());
+        if !bundle.check_correctness() {
+            anyhow::bail!(crate::tx::TRANSACTION_SIGNATURE_ERROR);
+        }
+        Ok(bundle)
+    }
+
+    /// Encodes the bundle as the byte sequence covered by the signature: every
+    /// inner transaction's own encoding, concatenated in order, followed by
+    /// the bundle's shared nonce.
+    pub fn get_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        for tx in &self.txs {
+            out.extend_from_slice(&tx.get_bytes());
+        }
+        out.extend_from_slice(&self.nonce.to_be_bytes());
+        out
+    }
+
+    /// Total number of pubdata chunks required to commit the bundle: the sum
+    /// of every inner operation's own chunk count.
+    pub fn chunks(&self) -> usize {
+        self.txs.iter().map(ZkDposTx::min_chunks).sum()
+    }
+
+    /// Verifies the transaction correctness:
+    ///
+    /// - the bundle must contain at least one inner transaction.
+    /// - every inner transaction must pass its own `check_correctness`,
+    ///   which includes verifying that transaction's own signature.
+    /// - zkDpos signature must correspond to the PubKeyHash of the account.
+    pub fn check_correctness(&mut self) -> bool {
+        let mut valid = !self.txs.is_empty() && self.txs.iter_mut().all(ZkDposTx::check_correctness);
+
+        if valid {
+            let signer = self.verify_signature();
+            valid = valid && signer.is_some();
+            self.cached_signer = VerifiedSignatureCache::Cached(signer);
+        }
+        valid
+    }
+
+    /// Restores the `PubKeyHash` from the bundle's signature.
+    pub fn verify_signature(&self) -> Option<PubKeyHash> {
+        if let VerifiedSignatureCache::Cached(cached_signer) = &self.cached_signer {
+            *cached_signer
+        } else {
+            self.signature
+                .verify_musig(&self.get_bytes())
+                .map(|pub_key| PubKeyHash::from_pubkey(&pub_key))
+        }
+    }
+}
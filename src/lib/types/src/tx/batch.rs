@@ -0,0 +1,146 @@
+//! Commitment scheme for a set of transactions authorized by a single Alaya
+//! (ATP) signature.
+//!
+//! Instead of signing an opaque, externally-supplied `batch_hash`, the Alaya
+//! account key signs the root of a tagged Merkle tree built over the
+//! transactions in the batch. This binds the signature to exactly this set of
+//! transactions, in this order: nothing can be added, removed, or reordered
+//! without invalidating it.
+
+use parity_crypto::{digest::sha256, Keccak256};
+use zkdpos_basic_types::{Address, H256};
+
+use super::{eip712_digest, eip712_domain_separator, ChangePubKey, Exchange, Swap, Withdraw, ZkDposTx};
+use crate::{AddLiquidity, RemoveLiquidity};
+
+/// A transaction that can participate in an ATP-signed batch.
+///
+/// Any transaction whose canonical byte encoding is available can be used as a
+/// Merkle leaf. This is implemented for the transaction types listed in
+/// `impl_batch_tx!` below and for the `ZkDposTx` enum; other types with a
+/// `get_bytes()` method (e.g. `Transfer`, `ForcedExit`) take part by being
+/// wrapped in `ZkDposTx`.
+pub trait BatchTx {
+    /// Returns the canonical byte encoding of the transaction.
+    fn get_bytes(&self) -> Vec<u8>;
+}
+
+macro_rules! impl_batch_tx {
+    ($($tx:ty),* $(,)?) => {
+        $(
+            impl BatchTx for $tx {
+                fn get_bytes(&self) -> Vec<u8> {
+                    Self::get_bytes(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_batch_tx!(
+    ChangePubKey,
+    Exchange,
+    Withdraw,
+    Swap,
+    AddLiquidity,
+    RemoveLiquidity
+);
+
+/// Every `ZkDposTx` variant already has a `get_bytes()` of its own, so the
+/// whole enum can be used as a Merkle leaf regardless of which transaction
+/// type it happens to hold - unlike `impl_batch_tx!` above, there's no need
+/// to list variants one at a time.
+impl BatchTx for ZkDposTx {
+    fn get_bytes(&self) -> Vec<u8> {
+        Self::get_bytes(self)
+    }
+}
+
+const LEAF_TAG: &[u8] = b"zkdpos-batch-leaf";
+const BRANCH_TAG: &[u8] = b"zkdpos-batch-branch";
+
+/// `TH(tag, m) = SHA256(SHA256(tag) || SHA256(tag) || m)`, the tagged-hash
+/// construction used by BOLT12's merkle scheme to domain-separate leaf and
+/// branch hashes from one another and from hashes computed elsewhere.
+fn tagged_hash(tag: &[u8], msg: &[u8]) -> H256 {
+    let tag_hash = sha256(tag);
+    let mut preimage = Vec::with_capacity(tag_hash.len() * 2 + msg.len());
+    preimage.extend_from_slice(&tag_hash);
+    preimage.extend_from_slice(&tag_hash);
+    preimage.extend_from_slice(msg);
+    H256::from_slice(&sha256(&preimage))
+}
+
+/// Builds the tagged Merkle root committing to every transaction in the batch,
+/// in order. This is the value that should be signed (as `batch_hash`) by the
+/// Alaya account key.
+///
+/// # Panics
+///
+/// Panics if `txs` is empty; a batch commitment is meaningless without any
+/// transactions to commit to.
+pub fn compute_batch_hash(txs: &[&dyn BatchTx]) -> H256 {
+    assert!(
+        !txs.is_empty(),
+        "cannot compute a batch hash of an empty batch"
+    );
+
+    let mut level: Vec<H256> = txs
+        .iter()
+        .map(|tx| tagged_hash(LEAF_TAG, &tx.get_bytes()))
+        .collect();
+
+    while level.len() > 1 {
+        let mut next_level = Vec::with_capacity((level.len() + 1) / 2);
+        for pair in level.chunks(2) {
+            let left = pair[0];
+            // Duplicate the last node when a level has odd length.
+            let right = *pair.last().unwrap();
+            let mut preimage = Vec::with_capacity(64);
+            preimage.extend_from_slice(left.as_bytes());
+            preimage.extend_from_slice(right.as_bytes());
+            next_level.push(tagged_hash(BRANCH_TAG, &preimage));
+        }
+        level = next_level;
+    }
+
+    level[0]
+}
+
+/// Verifies that `batch_hash` is indeed the Merkle root committing to `txs`,
+/// i.e. that an ATP signature over `batch_hash` authorizes exactly this set of
+/// transactions and nothing else.
+pub fn verify_batch_hash(txs: &[&dyn BatchTx], batch_hash: H256) -> bool {
+    compute_batch_hash(txs) == batch_hash
+}
+
+/// Computes the `hashStruct` of the `ZkDposBatch(bytes32[] txHashes)` EIP-712 typed
+/// struct: a signer who wants one typed-data signature to cover a whole batch signs
+/// this, built from the ordered `eip712_struct_hash()` of every transaction it
+/// contains (obtained per-type, e.g. `ChangePubKey::eip712_struct_hash`). Per the
+/// EIP-712 encoding rules for a dynamic array of a non-atomic type, the array is
+/// encoded as `keccak256` of the concatenation of its (already-hashed) elements.
+pub fn eip712_batch_struct_hash(tx_struct_hashes: &[H256]) -> H256 {
+    let mut encoded_array = Vec::with_capacity(tx_struct_hashes.len() * 32);
+    for struct_hash in tx_struct_hashes {
+        encoded_array.extend_from_slice(struct_hash.as_bytes());
+    }
+    let tx_hashes_hash = encoded_array.keccak256();
+
+    let mut preimage = Vec::new();
+    preimage.extend_from_slice(&b"ZkDposBatch(bytes32[] txHashes)".to_vec().keccak256());
+    preimage.extend_from_slice(&tx_hashes_hash);
+    H256::from_slice(&preimage.keccak256())
+}
+
+/// Computes the EIP-712 typed-data digest that must be signed with the Alaya
+/// private key in order to authorize an entire batch via a single typed-data
+/// signature, as an alternative to signing the tagged-Merkle [`compute_batch_hash`].
+pub fn get_eip712_batch_signed_data(
+    tx_struct_hashes: &[H256],
+    chain_id: u32,
+    verifying_contract: Address,
+) -> H256 {
+    let domain_separator = eip712_domain_separator(chain_id, verifying_contract);
+    eip712_digest(&domain_separator, &eip712_batch_struct_hash(tx_struct_hashes))
+}
@@ -7,6 +7,7 @@ use crate::account::PubKeyHash;
 use anyhow::ensure;
 use num::{BigUint, Zero};
 use parity_crypto::Keccak256;
+use rand::{rngs::OsRng, RngCore};
 use serde::{Deserialize, Serialize};
 use zkdpos_basic_types::{Address, TokenId, H256};
 use zkdpos_crypto::{
@@ -15,7 +16,10 @@ use zkdpos_crypto::{
 };
 use zkdpos_utils::{format_units, BigUintSerdeAsRadix10Str};
 
-use super::{PackedAtpSignature, TimeRange, TxSignature, VerifiedSignatureCache};
+use super::{
+    compute_batch_hash, eip712_digest, eip712_domain_separator, AccountSignerSet, BatchTx,
+    PackedAtpSignature, TimeRange, TxSignature, VerifiableSignature, VerifiedSignatureCache,
+};
 use crate::tokens::{ChangePubKeyFeeType, ChangePubKeyFeeTypeArg};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -52,12 +56,46 @@ impl ChangePubKeyCREATE2Data {
     }
 }
 
+/// Computes the counterfactual smart-contract wallet address that would result from
+/// deploying via `creator_address` (the CREATE2 factory/"Deployer") with bytecode hash
+/// `code_hash`, for a wallet that will set `pubkey_hash` as its signing key. This lets
+/// a client pre-register a signing key for a wallet that hasn't been deployed to L1
+/// yet, since the address is fully determined ahead of time.
+pub fn compute_create2_wallet_address(
+    creator_address: Address,
+    salt_arg: H256,
+    code_hash: H256,
+    pubkey_hash: &PubKeyHash,
+) -> Address {
+    ChangePubKeyCREATE2Data {
+        creator_address,
+        salt_arg,
+        code_hash,
+    }
+    .get_address(pubkey_hash)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangePubKeyEIP712Data {
+    pub atp_signature: PackedAtpSignature,
+    /// Chain ID the signing domain (see `eip712_domain_separator`) was bound
+    /// to. Carried alongside the signature, the same way `ChangePubKeyCREATE2Data`
+    /// carries its own verification context, so `is_atp_auth_data_valid` can
+    /// recover and check the signer without any network context being
+    /// threaded in from outside.
+    pub chain_id: u32,
+    /// Verifying contract address the signing domain was bound to.
+    pub verifying_contract: Address,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum ChangePubKeyAtpAuthData {
     Onchain,
     ECDSA(ChangePubKeyECDSAData),
     CREATE2(ChangePubKeyCREATE2Data),
+    EIP712(ChangePubKeyEIP712Data),
 }
 
 impl ChangePubKeyAtpAuthData {
@@ -73,6 +111,10 @@ impl ChangePubKeyAtpAuthData {
         matches!(self, ChangePubKeyAtpAuthData::CREATE2(..))
     }
 
+    pub fn is_eip712(&self) -> bool {
+        matches!(self, ChangePubKeyAtpAuthData::EIP712(..))
+    }
+
     pub fn get_atp_witness(&self) -> Vec<u8> {
         match self {
             ChangePubKeyAtpAuthData::Onchain => Vec::new(),
@@ -95,6 +137,18 @@ impl ChangePubKeyAtpAuthData {
                 bytes.extend_from_slice(code_hash.as_bytes());
                 bytes
             }
+            ChangePubKeyAtpAuthData::EIP712(ChangePubKeyEIP712Data {
+                atp_signature,
+                chain_id,
+                verifying_contract,
+            }) => {
+                let mut bytes = Vec::new();
+                bytes.push(0x02);
+                bytes.extend_from_slice(&atp_signature.serialize_packed());
+                bytes.extend_from_slice(&chain_id.to_be_bytes());
+                bytes.extend_from_slice(verifying_contract.as_bytes());
+                bytes
+            }
         }
     }
 
@@ -103,6 +157,7 @@ impl ChangePubKeyAtpAuthData {
             ChangePubKeyAtpAuthData::Onchain => ChangePubKeyFeeType::Onchain,
             ChangePubKeyAtpAuthData::ECDSA(_) => ChangePubKeyFeeType::ECDSA,
             ChangePubKeyAtpAuthData::CREATE2(_) => ChangePubKeyFeeType::CREATE2,
+            ChangePubKeyAtpAuthData::EIP712(_) => ChangePubKeyFeeType::EIP712,
         }
     }
 }
@@ -139,6 +194,13 @@ pub struct ChangePubKey {
     pub atp_signature: Option<PackedAtpSignature>,
     /// Data needed to check if Alaya address authorized ChangePubKey operation
     pub atp_auth_data: Option<ChangePubKeyAtpAuthData>,
+    /// If set, this `ChangePubKey` installs a threshold-multisig signer set on
+    /// the account instead of a single signing key: `new_pk_hash` must equal
+    /// `signer_set.commitment()`, and subsequent transactions from this
+    /// account authorize via `M` of `signer_set`'s registered signers rather
+    /// than a single `TxSignature` (see [`super::ThresholdMusigSignature`]).
+    #[serde(default)]
+    pub signer_set: Option<AccountSignerSet>,
     /// Time range when the transaction is valid
     /// This fields must be Option<...> because of backward compatibility with first version of ZkDpos
     #[serde(flatten)]
@@ -189,6 +251,7 @@ impl ChangePubKey {
             signature: signature.clone().unwrap_or_default(),
             atp_signature: None,
             atp_auth_data,
+            signer_set: None,
             cached_signer: VerifiedSignatureCache::NotCached,
             time_range: Some(time_range),
         };
@@ -211,6 +274,42 @@ impl ChangePubKey {
         time_range: TimeRange,
         atp_signature: Option<PackedAtpSignature>,
         private_key: &PrivateKey,
+    ) -> Result<Self, anyhow::Error> {
+        let mut aux_rand = [0u8; 32];
+        OsRng.fill_bytes(&mut aux_rand);
+        Self::new_signed_with_aux_rand(
+            account_id,
+            account,
+            new_pk_hash,
+            fee_token,
+            fee,
+            nonce,
+            time_range,
+            atp_signature,
+            private_key,
+            aux_rand,
+        )
+    }
+
+    /// Creates a signed transaction the same way [`Self::new_signed`] does, but mixes
+    /// `aux_rand` into the musig nonce derivation via `TxSignature::sign_musig_with_aux_rand`.
+    /// Following rust-lightning's sign-with-noncedata approach, this lets an HSM or
+    /// embedded signer supply its own entropy alongside the deterministic nonce, adding
+    /// resistance to side-channel and fault-injection attacks that target purely
+    /// deterministic nonce derivation, while remaining verifiable by the ordinary
+    /// `verify_musig` path. `new_signed` is just this with OS-random `aux_rand`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_signed_with_aux_rand(
+        account_id: AccountId,
+        account: Address,
+        new_pk_hash: PubKeyHash,
+        fee_token: TokenId,
+        fee: BigUint,
+        nonce: Nonce,
+        time_range: TimeRange,
+        atp_signature: Option<PackedAtpSignature>,
+        private_key: &PrivateKey,
+        aux_rand: [u8; 32],
     ) -> Result<Self, anyhow::Error> {
         let mut tx = Self::new(
             account_id,
@@ -223,6 +322,166 @@ impl ChangePubKey {
             None,
             atp_signature,
         );
+        tx.signature =
+            TxSignature::sign_musig_with_aux_rand(private_key, &tx.get_bytes(), aux_rand);
+        if !tx.check_correctness() {
+            anyhow::bail!(crate::tx::TRANSACTION_SIGNATURE_ERROR);
+        }
+        Ok(tx)
+    }
+
+    /// Creates a `ChangePubKey` transaction authorized via a CREATE2 counterfactual
+    /// deployment: the account's address isn't deployed on L1 yet, but is fully
+    /// determined by `creator_address`/`salt_arg`/`code_hash` and `new_pk_hash`, per
+    /// EIP-1014. This lets a smart-contract wallet set its signing key before the
+    /// wallet contract itself exists on-chain, since the derived address alone proves
+    /// authorization and no Alaya signature is required.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_create2(
+        account_id: AccountId,
+        account: Address,
+        new_pk_hash: PubKeyHash,
+        fee_token: TokenId,
+        fee: BigUint,
+        nonce: Nonce,
+        time_range: TimeRange,
+        creator_address: Address,
+        salt_arg: H256,
+        code_hash: H256,
+        signature: Option<TxSignature>,
+    ) -> Result<Self, anyhow::Error> {
+        let create2_data = ChangePubKeyCREATE2Data {
+            creator_address,
+            salt_arg,
+            code_hash,
+        };
+        let derived_address = create2_data.get_address(&new_pk_hash);
+        ensure!(
+            derived_address == account,
+            "CREATE2-derived address does not match the account address"
+        );
+
+        let mut tx = Self {
+            account_id,
+            account,
+            new_pk_hash,
+            fee_token,
+            fee,
+            nonce,
+            signature: signature.clone().unwrap_or_default(),
+            atp_signature: None,
+            atp_auth_data: Some(ChangePubKeyAtpAuthData::CREATE2(create2_data)),
+            signer_set: None,
+            cached_signer: VerifiedSignatureCache::NotCached,
+            time_range: Some(time_range),
+        };
+        if signature.is_some() {
+            tx.cached_signer = VerifiedSignatureCache::Cached(tx.verify_signature());
+        }
+        Ok(tx)
+    }
+
+    /// Creates a signed CREATE2-authorized `ChangePubKey` transaction using private key
+    /// and checks for the transaction correctness.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_signed_create2(
+        account_id: AccountId,
+        account: Address,
+        new_pk_hash: PubKeyHash,
+        fee_token: TokenId,
+        fee: BigUint,
+        nonce: Nonce,
+        time_range: TimeRange,
+        creator_address: Address,
+        salt_arg: H256,
+        code_hash: H256,
+        private_key: &PrivateKey,
+    ) -> Result<Self, anyhow::Error> {
+        let mut tx = Self::new_create2(
+            account_id,
+            account,
+            new_pk_hash,
+            fee_token,
+            fee,
+            nonce,
+            time_range,
+            creator_address,
+            salt_arg,
+            code_hash,
+            None,
+        )?;
+        tx.signature = TxSignature::sign_musig(private_key, &tx.get_bytes());
+        if !tx.check_correctness() {
+            anyhow::bail!(crate::tx::TRANSACTION_SIGNATURE_ERROR);
+        }
+        Ok(tx)
+    }
+
+    /// Creates a `ChangePubKey` transaction authorized via an EIP-712 typed-data signature
+    /// over the Alaya account key, instead of the legacy plain-message ECDSA signature.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_eip712(
+        account_id: AccountId,
+        account: Address,
+        new_pk_hash: PubKeyHash,
+        fee_token: TokenId,
+        fee: BigUint,
+        nonce: Nonce,
+        time_range: TimeRange,
+        atp_signature: PackedAtpSignature,
+        chain_id: u32,
+        verifying_contract: Address,
+        signature: Option<TxSignature>,
+    ) -> Self {
+        let mut tx = Self {
+            account_id,
+            account,
+            new_pk_hash,
+            fee_token,
+            fee,
+            nonce,
+            signature: signature.clone().unwrap_or_default(),
+            atp_signature: None,
+            atp_auth_data: Some(ChangePubKeyAtpAuthData::EIP712(ChangePubKeyEIP712Data {
+                atp_signature,
+                chain_id,
+                verifying_contract,
+            })),
+            signer_set: None,
+            cached_signer: VerifiedSignatureCache::NotCached,
+            time_range: Some(time_range),
+        };
+        if signature.is_some() {
+            tx.cached_signer = VerifiedSignatureCache::Cached(tx.verify_signature());
+        }
+        tx
+    }
+
+    /// Creates a signed `ChangePubKey` transaction authorized via an EIP-712 typed-data
+    /// signature, using private key and checks for the transaction correctness.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_signed_eip712(
+        account_id: AccountId,
+        account: Address,
+        new_pk_hash: PubKeyHash,
+        fee_token: TokenId,
+        fee: BigUint,
+        nonce: Nonce,
+        time_range: TimeRange,
+        atp_signature: PackedAtpSignature,
+        private_key: &PrivateKey,
+    ) -> Result<Self, anyhow::Error> {
+        let mut tx = Self::new_eip712(
+            account_id,
+            account,
+            new_pk_hash,
+            fee_token,
+            fee,
+            nonce,
+            time_range,
+            atp_signature,
+            None,
+        );
         tx.signature = TxSignature::sign_musig(private_key, &tx.get_bytes());
         if !tx.check_correctness() {
             anyhow::bail!(crate::tx::TRANSACTION_SIGNATURE_ERROR);
@@ -230,6 +489,43 @@ impl ChangePubKey {
         Ok(tx)
     }
 
+    /// Creates a `ChangePubKey` transaction that installs a threshold-multisig
+    /// signer set on the account instead of a single signing key:
+    /// `new_pk_hash` is derived as `signer_set.commitment()`, so subsequent
+    /// transactions from this account authorize via `M` of `signer_set`'s
+    /// registered signers (see [`super::ThresholdMusigSignature`]) rather than
+    /// a single `TxSignature`. There is no single private key behind such a
+    /// `new_pk_hash` to prove possession of, so - unlike the other
+    /// constructors - this change is authorized by `atp_auth_data` (the
+    /// account's Alaya address) rather than by self-signing with the new key.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_multisig(
+        account_id: AccountId,
+        account: Address,
+        signer_set: AccountSignerSet,
+        fee_token: TokenId,
+        fee: BigUint,
+        nonce: Nonce,
+        time_range: TimeRange,
+        atp_auth_data: Option<ChangePubKeyAtpAuthData>,
+    ) -> Self {
+        let new_pk_hash = signer_set.commitment();
+        Self {
+            account_id,
+            account,
+            new_pk_hash,
+            fee_token,
+            fee,
+            nonce,
+            signature: TxSignature::default(),
+            atp_signature: None,
+            atp_auth_data: Some(atp_auth_data.unwrap_or(ChangePubKeyAtpAuthData::Onchain)),
+            signer_set: Some(signer_set),
+            cached_signer: VerifiedSignatureCache::Cached(None),
+            time_range: Some(time_range),
+        }
+    }
+
     /// Restores the `PubKeyHash` from the transaction signature.
     pub fn verify_signature(&self) -> Option<PubKeyHash> {
         if let VerifiedSignatureCache::Cached(cached_signer) = &self.cached_signer {
@@ -241,6 +537,14 @@ impl ChangePubKey {
         }
     }
 
+    /// Verifies the signatures of `txs` in parallel, rather than one at a time, which
+    /// dominates cost when validating a large mempool or replaying many transactions
+    /// while reconstructing state. See [`crate::tx::verify_signatures_batch`] for
+    /// the reusable implementation shared with other transaction types.
+    pub fn verify_batch(txs: &[&ChangePubKey]) -> Vec<Option<PubKeyHash>> {
+        crate::tx::verify_signatures_batch(txs)
+    }
+
     /// Encodes the transaction data as the byte sequence according to the zkDpos protocol.
     pub fn get_bytes(&self) -> Vec<u8> {
         let mut out = Vec::new();
@@ -251,6 +555,13 @@ impl ChangePubKey {
         out.extend_from_slice(&self.fee_token.to_be_bytes());
         out.extend_from_slice(&pack_fee_amount(&self.fee));
         out.extend_from_slice(&self.nonce.to_be_bytes());
+        if let Some(signer_set) = &self.signer_set {
+            out.push(signer_set.threshold);
+            out.push(signer_set.signers.len() as u8);
+            for signer in &signer_set.signers {
+                out.extend_from_slice(&signer.data);
+            }
+        }
         if let Some(time_range) = &self.time_range {
             out.extend_from_slice(&time_range.to_be_bytes());
         }
@@ -324,6 +635,35 @@ impl ChangePubKey {
         Ok(atp_signed_msg)
     }
 
+    /// Computes the `hashStruct` of this transaction's EIP-712 typed struct, i.e.
+    /// the part of the digest specific to the transaction rather than the signing
+    /// domain. Exposed separately so a `ZkDposBatch` struct hash can be built from
+    /// the struct hashes of the transactions it contains.
+    pub fn eip712_struct_hash(&self) -> H256 {
+        let mut preimage = Vec::new();
+        preimage.extend_from_slice(
+            &b"ChangePubKey(bytes20 pubKeyHash,uint32 nonce,uint32 accountId)"
+                .to_vec()
+                .keccak256(),
+        );
+        preimage.extend_from_slice(&self.new_pk_hash.data);
+        preimage.extend_from_slice(&[0u8; 28]);
+        preimage.extend_from_slice(&self.nonce.to_be_bytes());
+        preimage.extend_from_slice(&[0u8; 28]);
+        preimage.extend_from_slice(&self.account_id.to_be_bytes());
+        H256::from_slice(&preimage.keccak256())
+    }
+
+    /// Computes the EIP-712 typed-data digest that must be signed with the Alaya
+    /// private key in order to authorize this `ChangePubKey` via the `EIP712` auth
+    /// data variant. `chain_id` and `verifying_contract` pin the signature to a
+    /// specific deployment of the zkDpos contract, the same way any other EIP-712
+    /// signing domain does, so a signature can't be replayed across networks.
+    pub fn get_eip712_signed_data(&self, chain_id: u32, verifying_contract: Address) -> H256 {
+        let domain_separator = eip712_domain_separator(chain_id, verifying_contract);
+        eip712_digest(&domain_separator, &self.eip712_struct_hash())
+    }
+
     pub fn is_atp_auth_data_valid(&self) -> bool {
         if let Some(atp_auth_data) = &self.atp_auth_data {
             match atp_auth_data {
@@ -339,6 +679,17 @@ impl ChangePubKey {
                     let create2_address = create2_data.get_address(&self.new_pk_hash);
                     create2_address == self.account
                 }
+                ChangePubKeyAtpAuthData::EIP712(ChangePubKeyEIP712Data {
+                    atp_signature,
+                    chain_id,
+                    verifying_contract,
+                }) => {
+                    let digest = self.get_eip712_signed_data(*chain_id, *verifying_contract);
+                    let recovered_address = atp_signature
+                        .signature_recover_signer_from_digest(digest)
+                        .ok();
+                    recovered_address == Some(self.account)
+                }
             }
         } else if let Some(old_atp_signature) = &self.atp_signature {
             let recovered_address = self
@@ -354,13 +705,18 @@ impl ChangePubKey {
     /// Verifies the transaction correctness:
     ///
     /// - Alaya signature (if set) must correspond to the account address.
-    /// - zkDpos signature must correspond to the `new_pk_hash` field of the transaction.
+    /// - If `signer_set` is set, `new_pk_hash` must be its commitment (there is no
+    ///   single key to self-sign with in this case); otherwise the zkDpos signature
+    ///   must correspond to the `new_pk_hash` field of the transaction.
     /// - `account_id` field must be within supported range.
     /// - `fee_token` field must be within supported range.
     /// - `fee` field must represent a packable value.
     pub fn check_correctness(&self) -> bool {
         self.is_atp_auth_data_valid()
-            && self.verify_signature() == Some(self.new_pk_hash)
+            && match &self.signer_set {
+                Some(signer_set) => signer_set.commitment() == self.new_pk_hash,
+                None => self.verify_signature() == Some(self.new_pk_hash),
+            }
             && self.account_id <= max_account_id()
             && self.fee_token <= max_token_id()
             && is_fee_amount_packable(&self.fee)
@@ -423,3 +779,26 @@ impl ChangePubKey {
         }
     }
 }
+
+impl VerifiableSignature for ChangePubKey {
+    fn verify_signature(&self) -> Option<PubKeyHash> {
+        Self::verify_signature(self)
+    }
+}
+
+/// Recomputes the batch Merkle root over `txs` (in order) and checks that every
+/// ECDSA-authorized transaction in the batch carries that root as its stored
+/// `batch_hash`. This proves that the single ATP signature covering `batch_hash`
+/// authorizes exactly this set of transactions and nothing else.
+pub fn verify_change_pub_key_batch(
+    txs: &[&dyn BatchTx],
+    change_pub_keys: &[&ChangePubKey],
+) -> bool {
+    let root = compute_batch_hash(txs);
+    change_pub_keys.iter().all(|tx| match &tx.atp_auth_data {
+        Some(ChangePubKeyAtpAuthData::ECDSA(ChangePubKeyECDSAData { batch_hash, .. })) => {
+            *batch_hash == root
+        }
+        _ => true,
+    })
+}
@@ -0,0 +1,248 @@
+use crate::{
+    helpers::{is_fee_amount_packable, is_token_amount_packable, pack_fee_amount, pack_token_amount},
+    priority_ops::ConditionalPredicate,
+    AccountId, Nonce, TokenId,
+};
+use num::BigUint;
+
+use crate::account::PubKeyHash;
+use crate::Engine;
+use serde::{Deserialize, Serialize};
+use zkdpos_basic_types::Address;
+use zkdpos_crypto::franklin_crypto::eddsa::PrivateKey;
+use zkdpos_crypto::params::{max_account_id, max_token_id};
+use zkdpos_utils::BigUintSerdeAsRadix10Str;
+
+use super::{TimeRange, TxSignature, VerifiableSignature, VerifiedSignatureCache};
+
+/// Resolves the settlement phase of a `Conditional` priority operation (see
+/// `ConditionalOp`): releases the `amount + fee` escrowed in `pending` to
+/// `to` once `predicate` is satisfied, or refunds it to `from` once
+/// `deadline_block` has passed with the predicate still unmet. Like
+/// `ConditionalTransfer`'s `approvals`, `witness_signature` authorizes the
+/// predicate's release rather than the escrowed funds themselves, so anyone
+/// holding the proof can submit the settlement.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConditionalSettle {
+    /// zkDpos network account ID of the transaction submitter, who pays `fee`.
+    pub account_id: AccountId,
+    /// Escrow sub-account locked by the matching `ConditionalOp`.
+    pub pending: AccountId,
+    /// Address the funds release to once `predicate` is satisfied.
+    pub to: Address,
+    /// Address the funds refund to if `deadline_block` passes unmet.
+    pub from: Address,
+    pub token: TokenId,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub amount: BigUint,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub fee: BigUint,
+    /// Release condition carried over from the originating `Conditional`.
+    pub predicate: ConditionalPredicate,
+    /// Alaya deadline block copied from the originating priority operation's
+    /// `PriorityOp::deadline_block`.
+    pub deadline_block: u64,
+    /// Required only when `predicate` is `ConditionalPredicate::Signed`: a
+    /// signature over `witness_message()` recovering to the predicate's
+    /// `PubKeyHash`.
+    pub witness_signature: Option<TxSignature>,
+    pub nonce: Nonce,
+    /// Time range when the transaction is valid
+    /// This fields must be Option<...> because of backward compatibility with first version of ZkDpos
+    #[serde(flatten)]
+    pub time_range: Option<TimeRange>,
+    /// Transaction zkDpos signature.
+    pub signature: TxSignature,
+    #[serde(skip)]
+    cached_signer: VerifiedSignatureCache,
+}
+
+impl ConditionalSettle {
+    /// Unique identifier of the transaction type in zkDpos network.
+    pub const TX_TYPE: u8 = 14;
+
+    /// Creates transaction from all the required fields.
+    ///
+    /// While `signature` field is mandatory for new transactions, it may be `None`
+    /// in some cases (e.g. when restoring the network state from the L1 contract data).
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        account_id: AccountId,
+        pending: AccountId,
+        to: Address,
+        from: Address,
+        token: TokenId,
+        amount: BigUint,
+        fee: BigUint,
+        predicate: ConditionalPredicate,
+        deadline_block: u64,
+        witness_signature: Option<TxSignature>,
+        nonce: Nonce,
+        time_range: TimeRange,
+        signature: Option<TxSignature>,
+    ) -> Self {
+        let mut tx = Self {
+            account_id,
+            pending,
+            to,
+            from,
+            token,
+            amount,
+            fee,
+            predicate,
+            deadline_block,
+            witness_signature,
+            nonce,
+            time_range: Some(time_range),
+            signature: signature.clone().unwrap_or_default(),
+            cached_signer: VerifiedSignatureCache::NotCached,
+        };
+        if signature.is_some() {
+            tx.cached_signer = VerifiedSignatureCache::Cached(tx.verify_signature());
+        }
+        tx
+    }
+
+    /// Creates a signed transaction using private key and
+    /// checks for the transaction correcteness.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_signed(
+        account_id: AccountId,
+        pending: AccountId,
+        to: Address,
+        from: Address,
+        token: TokenId,
+        amount: BigUint,
+        fee: BigUint,
+        predicate: ConditionalPredicate,
+        deadline_block: u64,
+        witness_signature: Option<TxSignature>,
+        nonce: Nonce,
+        time_range: TimeRange,
+        private_key: &PrivateKey<Engine>,
+    ) -> Result<Self, anyhow::Error> {
+        let mut tx = Self::new(
+            account_id,
+            pending,
+            to,
+            from,
+            token,
+            amount,
+            fee,
+            predicate,
+            deadline_block,
+            witness_signature,
+            nonce,
+            time_range,
+            None,
+        );
+        tx.signature = TxSignature::sign_musig(private_key, &tx.get_bytes());
+        if !tx.check_correctness() {
+            anyhow::bail!(crate::tx::TRANSACTION_SIGNATURE_ERROR);
+        }
+        Ok(tx)
+    }
+
+    /// Encodes the transaction data as the byte sequence according to the zkDpos protocol.
+    pub fn get_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&[Self::TX_TYPE]);
+        out.extend_from_slice(&self.account_id.to_be_bytes());
+        out.extend_from_slice(&self.pending.to_be_bytes());
+        out.extend_from_slice(self.to.as_bytes());
+        out.extend_from_slice(self.from.as_bytes());
+        out.extend_from_slice(&self.token.to_be_bytes());
+        out.extend_from_slice(&pack_token_amount(&self.amount));
+        out.extend_from_slice(&pack_fee_amount(&self.fee));
+        out.extend_from_slice(&self.deadline_block.to_be_bytes());
+        out.extend_from_slice(&self.nonce.to_be_bytes());
+        if let Some(time_range) = &self.time_range {
+            out.extend_from_slice(&time_range.to_be_bytes());
+        }
+        out
+    }
+
+    /// The message a `Signed` predicate's witness co-signs: bound to the
+    /// predicate and the escrow being released, but not to `get_bytes()`,
+    /// since the witness proof is collected independently of (and typically
+    /// predates) the submitter's own signature over the settlement.
+    pub fn witness_message(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&[Self::TX_TYPE]);
+        out.extend_from_slice(&self.pending.to_be_bytes());
+        out.extend_from_slice(&self.predicate.to_be_bytes());
+        out
+    }
+
+    /// Recovers the `PubKeyHash` of `witness_signature`, if present and valid.
+    pub fn verify_witness(&self) -> Option<PubKeyHash> {
+        let message = self.witness_message();
+        self.witness_signature
+            .as_ref()
+            .and_then(|signature| signature.verify_musig(&message))
+            .map(|pub_key| PubKeyHash::from_pubkey(&pub_key))
+    }
+
+    /// Whether `predicate` currently releases to `to` given the current Alaya
+    /// block height `now_block` and the witness recovered from
+    /// `witness_signature`. Mirrors `ConditionalTransfer::is_satisfied`.
+    pub fn is_release(&self, now_block: u64) -> bool {
+        match &self.predicate {
+            ConditionalPredicate::After(block) => now_block >= *block,
+            ConditionalPredicate::Signed(witness_pubkey) => {
+                self.verify_witness().as_ref() == Some(witness_pubkey)
+            }
+        }
+    }
+
+    /// Whether the escrow should instead refund to `from`: the predicate is
+    /// still unmet and `deadline_block` has passed.
+    pub fn is_refund(&self, now_block: u64) -> bool {
+        !self.is_release(now_block) && now_block >= self.deadline_block
+    }
+
+    /// Verifies the transaction correctness:
+    ///
+    /// - `account_id` field must be within supported range.
+    /// - `token` field must be within supported range.
+    /// - `amount` field must represent a packable value.
+    /// - `fee` field must represent a packable value.
+    /// - zkDpos signature must correspond to the PubKeyHash of the account.
+    pub fn check_correctness(&mut self) -> bool {
+        let mut valid = is_token_amount_packable(&self.amount)
+            && is_fee_amount_packable(&self.fee)
+            && self.account_id <= max_account_id()
+            && self.token <= max_token_id()
+            && self.to != Address::zero()
+            && self.from != Address::zero()
+            && self
+                .time_range
+                .map(|r| r.check_correctness())
+                .unwrap_or(true);
+
+        if valid {
+            let signer = self.verify_signature();
+            valid = valid && signer.is_some();
+            self.cached_signer = VerifiedSignatureCache::Cached(signer);
+        }
+        valid
+    }
+
+    /// Restores the `PubKeyHash` from the transaction signature.
+    pub fn verify_signature(&self) -> Option<PubKeyHash> {
+        if let VerifiedSignatureCache::Cached(cached_signer) = &self.cached_signer {
+            *cached_signer
+        } else {
+            self.signature
+                .verify_musig(&self.get_bytes())
+                .map(|pub_key| PubKeyHash::from_pubkey(&pub_key))
+        }
+    }
+}
+
+impl VerifiableSignature for ConditionalSettle {
+    fn verify_signature(&self) -> Option<PubKeyHash> {
+        Self::verify_signature(self)
+    }
+}
@@ -0,0 +1,248 @@
+use crate::{
+    helpers::{is_fee_amount_packable, is_token_amount_packable, pack_fee_amount, pack_token_amount},
+    AccountId, Nonce, TokenId,
+};
+use num::BigUint;
+
+use crate::account::PubKeyHash;
+use crate::Engine;
+use serde::{Deserialize, Serialize};
+use zkdpos_basic_types::{Address, H256};
+use zkdpos_crypto::franklin_crypto::eddsa::PrivateKey;
+use zkdpos_crypto::params::{max_account_id, max_token_id};
+use zkdpos_utils::BigUintSerdeAsRadix10Str;
+
+use super::{PredicateNode, TimeRange, TxSignature, VerifiableSignature, VerifiedSignatureCache};
+
+/// `ConditionalTransfer` locks funds from a zkDpos account under a
+/// [`PredicateNode`] tree instead of moving them directly: they stay escrowed
+/// in the sender's own balance until a later resubmission of the same
+/// transaction (with `approvals` extended to satisfy more of the tree)
+/// evaluates `predicate` to `true`, at which point the settling op releases
+/// them to `to` in one step.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConditionalTransfer {
+    /// zkDpos network account ID of the transaction initiator.
+    pub account_id: AccountId,
+    /// Address of the account the funds are escrowed from.
+    pub from: Address,
+    /// Address the funds are released to once `predicate` is satisfied.
+    pub to: Address,
+    /// Type of token being transferred. Also represents the token in which fee will be paid.
+    pub token: TokenId,
+    /// Amount of funds to transfer once released.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub amount: BigUint,
+    /// Fee for the transaction.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub fee: BigUint,
+    /// Release condition: a tree of timelocks and required approvers.
+    pub predicate: PredicateNode,
+    /// Signatures, one per satisfied `PredicateNode::Approver` leaf, each over
+    /// `approval_message()` rather than the tx's own signed `get_bytes()` -
+    /// they authorize the predicate's release, not the transfer itself, and
+    /// accumulate across resubmissions as more approvers sign off.
+    pub approvals: Vec<TxSignature>,
+    /// Current account nonce.
+    pub nonce: Nonce,
+    /// When set, binds this transaction to the account's durable nonce
+    /// (see `zkdpos_types::tx::AdvanceNonce`) instead of `nonce`: the handler
+    /// checks it against the account's currently stored durable nonce rather
+    /// than requiring `nonce` to match the sequential counter, so the
+    /// transaction doesn't expire just because another tx from the account
+    /// landed first. Executing it rotates the stored durable nonce forward,
+    /// the same as submitting an explicit `AdvanceNonce` would - so a given
+    /// durable nonce value authorizes exactly one transaction.
+    #[serde(default)]
+    pub durable_nonce: Option<H256>,
+    /// Time range when the transaction is valid
+    /// This fields must be Option<...> because of backward compatibility with first version of ZkDpos
+    #[serde(flatten)]
+    pub time_range: Option<TimeRange>,
+    /// Transaction zkDpos signature.
+    pub signature: TxSignature,
+    #[serde(skip)]
+    cached_signer: VerifiedSignatureCache,
+}
+
+impl ConditionalTransfer {
+    /// Unique identifier of the transaction type in zkDpos network.
+    pub const TX_TYPE: u8 = 12;
+
+    /// Creates transaction from all the required fields.
+    ///
+    /// While `signature` field is mandatory for new transactions, it may be `None`
+    /// in some cases (e.g. when restoring the network state from the L1 contract data).
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        account_id: AccountId,
+        from: Address,
+        to: Address,
+        token: TokenId,
+        amount: BigUint,
+        fee: BigUint,
+        predicate: PredicateNode,
+        approvals: Vec<TxSignature>,
+        nonce: Nonce,
+        durable_nonce: Option<H256>,
+        time_range: TimeRange,
+        signature: Option<TxSignature>,
+    ) -> Self {
+        let mut tx = Self {
+            account_id,
+            from,
+            to,
+            token,
+            amount,
+            fee,
+            predicate,
+            approvals,
+            nonce,
+            durable_nonce,
+            time_range: Some(time_range),
+            signature: signature.clone().unwrap_or_default(),
+            cached_signer: VerifiedSignatureCache::NotCached,
+        };
+        if signature.is_some() {
+            tx.cached_signer = VerifiedSignatureCache::Cached(tx.verify_signature());
+        }
+        tx
+    }
+
+    /// Creates a signed transaction using private key and
+    /// checks for the transaction correcteness.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_signed(
+        account_id: AccountId,
+        from: Address,
+        to: Address,
+        token: TokenId,
+        amount: BigUint,
+        fee: BigUint,
+        predicate: PredicateNode,
+        approvals: Vec<TxSignature>,
+        nonce: Nonce,
+        durable_nonce: Option<H256>,
+        time_range: TimeRange,
+        private_key: &PrivateKey<Engine>,
+    ) -> Result<Self, anyhow::Error> {
+        let mut tx = Self::new(
+            account_id, from, to, token, amount, fee, predicate, approvals, nonce, durable_nonce,
+            time_range, None,
+        );
+        tx.signature = TxSignature::sign_musig(private_key, &tx.get_bytes());
+        if !tx.check_correctness() {
+            anyhow::bail!(crate::tx::TRANSACTION_SIGNATURE_ERROR);
+        }
+        Ok(tx)
+    }
+
+    /// Encodes the transaction data as the byte sequence according to the zkDpos protocol.
+    pub fn get_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&[Self::TX_TYPE]);
+        out.extend_from_slice(&self.account_id.to_be_bytes());
+        out.extend_from_slice(self.from.as_bytes());
+        out.extend_from_slice(self.to.as_bytes());
+        out.extend_from_slice(&self.token.to_be_bytes());
+        out.extend_from_slice(&pack_token_amount(&self.amount));
+        out.extend_from_slice(&pack_fee_amount(&self.fee));
+        out.extend_from_slice(&self.predicate.to_be_bytes());
+        out.extend_from_slice(&self.nonce.to_be_bytes());
+        if let Some(durable_nonce) = &self.durable_nonce {
+            out.extend_from_slice(durable_nonce.as_bytes());
+        }
+        if let Some(time_range) = &self.time_range {
+            out.extend_from_slice(&time_range.to_be_bytes());
+        }
+        out
+    }
+
+    /// The message approvers co-sign to satisfy an `Approver` leaf: bound to
+    /// the predicate tree and the nonce, but not to `get_bytes()`, since
+    /// approvals are collected independently of (and may post-date) the
+    /// sender's own signature over the transfer.
+    pub fn approval_message(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&[Self::TX_TYPE]);
+        out.extend_from_slice(&self.predicate.to_be_bytes());
+        out.extend_from_slice(&self.nonce.to_be_bytes());
+        out
+    }
+
+    /// Recovers the `PubKeyHash` of every `approvals` entry that verifies
+    /// against `approval_message()`. Entries that fail to verify are dropped
+    /// rather than failing the whole transaction, since an approver signing
+    /// under the wrong key simply fails to satisfy their leaf.
+    pub fn verify_approvals(&self) -> Vec<PubKeyHash> {
+        let message = self.approval_message();
+        self.approvals
+            .iter()
+            .filter_map(|approval| {
+                approval
+                    .verify_musig(&message)
+                    .map(|pub_key| PubKeyHash::from_pubkey(&pub_key))
+            })
+            .collect()
+    }
+
+    /// Whether `predicate` currently evaluates to `true` given `now` and the
+    /// approvers recovered from `approvals`.
+    pub fn is_satisfied(&self, now: u64) -> bool {
+        let approved = self.verify_approvals();
+        self.predicate
+            .is_satisfied(now, self.time_range.unwrap_or_default(), &approved)
+    }
+
+    /// The bitmap of independently-satisfied `predicate` leaves at `now`,
+    /// committed to `ConditionalTransferOp` pubdata.
+    pub fn satisfied_leaf_bitmap(&self, now: u64) -> u64 {
+        let approved = self.verify_approvals();
+        self.predicate
+            .satisfied_leaf_bitmap(now, self.time_range.unwrap_or_default(), &approved)
+    }
+
+    /// Verifies the transaction correctness:
+    ///
+    /// - `account_id` field must be within supported range.
+    /// - `token` field must be within supported range.
+    /// - `amount` field must represent a packable value.
+    /// - `fee` field must represent a packable value.
+    /// - zkDpos signature must correspond to the PubKeyHash of the account.
+    pub fn check_correctness(&mut self) -> bool {
+        let mut valid = is_token_amount_packable(&self.amount)
+            && is_fee_amount_packable(&self.fee)
+            && self.account_id <= max_account_id()
+            && self.token <= max_token_id()
+            && self.to != Address::zero()
+            && self
+                .time_range
+                .map(|r| r.check_correctness())
+                .unwrap_or(true);
+
+        if valid {
+            let signer = self.verify_signature();
+            valid = valid && signer.is_some();
+            self.cached_signer = VerifiedSignatureCache::Cached(signer);
+        }
+        valid
+    }
+
+    /// Restores the `PubKeyHash` from the transaction signature.
+    pub fn verify_signature(&self) -> Option<PubKeyHash> {
+        if let VerifiedSignatureCache::Cached(cached_signer) = &self.cached_signer {
+            *cached_signer
+        } else {
+            self.signature
+                .verify_musig(&self.get_bytes())
+                .map(|pub_key| PubKeyHash::from_pubkey(&pub_key))
+        }
+    }
+}
+
+impl VerifiableSignature for ConditionalTransfer {
+    fn verify_signature(&self) -> Option<PubKeyHash> {
+        Self::verify_signature(self)
+    }
+}
@@ -0,0 +1,259 @@
+use crate::{
+    helpers::{is_fee_amount_packable, is_token_amount_packable, pack_fee_amount, pack_token_amount},
+    AccountId, Nonce, TokenId,
+};
+use num::BigUint;
+
+use crate::account::PubKeyHash;
+use crate::Engine;
+use serde::{Deserialize, Serialize};
+use zkdpos_basic_types::Address;
+use zkdpos_crypto::franklin_crypto::eddsa::PrivateKey;
+use zkdpos_crypto::params::{max_account_id, max_token_id};
+use zkdpos_utils::BigUintSerdeAsRadix10Str;
+
+use super::{SpendingCondition, TimeRange, TxSignature, VerifiableSignature, VerifiedSignatureCache};
+
+/// `EscrowTransfer` locks funds from a zkDpos account under a
+/// [`SpendingCondition`] instead of moving them directly: they sit in the
+/// escrow's pending sub-account until a follow-up "settle" transaction
+/// presents whatever the condition requires, at which point they route to
+/// `beneficiary` (condition satisfied) or back to `refund_to` (timeout).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EscrowTransfer {
+    /// zkDpos network account ID of the transaction initiator.
+    pub account_id: AccountId,
+    /// Address of the account locking the funds.
+    pub from: Address,
+    /// Address the funds are released to once `condition` is satisfied.
+    pub beneficiary: Address,
+    /// Address the funds are returned to if settlement times out instead.
+    pub refund_to: Address,
+    /// Type of token being escrowed. Also represents the token in which fee will be paid.
+    pub token: TokenId,
+    /// Amount of funds to escrow.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub amount: BigUint,
+    /// Fee for the transaction.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub fee: BigUint,
+    /// Release condition the follow-up settle transaction must satisfy.
+    pub condition: SpendingCondition,
+    /// Current account nonce.
+    pub nonce: Nonce,
+    /// Time range when the transaction is valid
+    /// This fields must be Option<...> because of backward compatibility with first version of ZkDpos
+    #[serde(flatten)]
+    pub time_range: Option<TimeRange>,
+    /// Transaction zkDpos signature.
+    pub signature: TxSignature,
+    #[serde(skip)]
+    cached_signer: VerifiedSignatureCache,
+}
+
+impl EscrowTransfer {
+    /// Unique identifier of the transaction type in zkDpos network.
+    pub const TX_TYPE: u8 = 11;
+
+    /// Creates transaction from all the required fields.
+    ///
+    /// While `signature` field is mandatory for new transactions, it may be `None`
+    /// in some cases (e.g. when restoring the network state from the L1 contract data).
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        account_id: AccountId,
+        from: Address,
+        beneficiary: Address,
+        refund_to: Address,
+        token: TokenId,
+        amount: BigUint,
+        fee: BigUint,
+        condition: SpendingCondition,
+        nonce: Nonce,
+        time_range: TimeRange,
+        signature: Option<TxSignature>,
+    ) -> Self {
+        let mut tx = Self {
+            account_id,
+            from,
+            beneficiary,
+            refund_to,
+            token,
+            amount,
+            fee,
+            condition,
+            nonce,
+            time_range: Some(time_range),
+            signature: signature.clone().unwrap_or_default(),
+            cached_signer: VerifiedSignatureCache::NotCached,
+        };
+        if signature.is_some() {
+            tx.cached_signer = VerifiedSignatureCache::Cached(tx.verify_signature());
+        }
+        tx
+    }
+
+    /// Creates a signed transaction using private key and
+    /// checks for the transaction correcteness.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_signed(
+        account_id: AccountId,
+        from: Address,
+        beneficiary: Address,
+        refund_to: Address,
+        token: TokenId,
+        amount: BigUint,
+        fee: BigUint,
+        condition: SpendingCondition,
+        nonce: Nonce,
+        time_range: TimeRange,
+        private_key: &PrivateKey<Engine>,
+    ) -> Result<Self, anyhow::Error> {
+        let mut tx = Self::new(
+            account_id, from, beneficiary, refund_to, token, amount, fee, condition, nonce,
+            time_range, None,
+        );
+        tx.signature = TxSignature::sign_musig(private_key, &tx.get_bytes());
+        if !tx.check_correctness() {
+            anyhow::bail!(crate::tx::TRANSACTION_SIGNATURE_ERROR);
+        }
+        Ok(tx)
+    }
+
+    /// Encodes the transaction data as the byte sequence according to the zkDpos protocol.
+    pub fn get_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&[Self::TX_TYPE]);
+        out.extend_from_slice(&self.account_id.to_be_bytes());
+        out.extend_from_slice(self.from.as_bytes());
+        out.extend_from_slice(self.beneficiary.as_bytes());
+        out.extend_from_slice(self.refund_to.as_bytes());
+        out.extend_from_slice(&self.token.to_be_bytes());
+        out.extend_from_slice(&pack_token_amount(&self.amount));
+        out.extend_from_slice(&pack_fee_amount(&self.fee));
+        out.push(self.condition.condition_type());
+        if let Some(time_range) = self.condition.time_range() {
+            out.extend_from_slice(&time_range.to_be_bytes());
+        }
+        if let Some(witness) = self.condition.witness() {
+            out.extend_from_slice(witness.as_bytes());
+        }
+        out.extend_from_slice(&self.nonce.to_be_bytes());
+        if let Some(time_range) = &self.time_range {
+            out.extend_from_slice(&time_range.to_be_bytes());
+        }
+        out
+    }
+
+    /// Verifies the transaction correctness:
+    ///
+    /// - `account_id` field must be within supported range.
+    /// - `token` field must be within supported range.
+    /// - `amount` field must represent a packable value.
+    /// - `fee` field must represent a packable value.
+    /// - zkDpos signature must correspond to the PubKeyHash of the account.
+    pub fn check_correctness(&mut self) -> bool {
+        let mut valid = is_token_amount_packable(&self.amount)
+            && is_fee_amount_packable(&self.fee)
+            && self.account_id <= max_account_id()
+            && self.token <= max_token_id()
+            && self.beneficiary != Address::zero()
+            && self
+                .time_range
+                .map(|r| r.check_correctness())
+                .unwrap_or(true);
+
+        if valid {
+            let signer = self.verify_signature();
+            valid = valid && signer.is_some();
+            self.cached_signer = VerifiedSignatureCache::Cached(signer);
+        }
+        valid
+    }
+
+    /// Restores the `PubKeyHash` from the transaction signature.
+    pub fn verify_signature(&self) -> Option<PubKeyHash> {
+        if let VerifiedSignatureCache::Cached(cached_signer) = &self.cached_signer {
+            *cached_signer
+        } else {
+            self.signature
+                .verify_musig(&self.get_bytes())
+                .map(|pub_key| PubKeyHash::from_pubkey(&pub_key))
+        }
+    }
+}
+
+impl VerifiableSignature for EscrowTransfer {
+    fn verify_signature(&self) -> Option<PubKeyHash> {
+        Self::verify_signature(self)
+    }
+}
+
+// This request added `EscrowTransfer` and its `SpendingCondition` but no
+// `state`-crate handler ever applies it (no `impl TxHandler<EscrowTransfer>`,
+// no settle counterpart, no `ZkDposTx` variant) - `conditional_transfer.rs`'s
+// doc comment mentions `EscrowTransfer` only as a point of comparison, not an
+// actual caller. There is therefore no apply/rollback path to write a
+// balance-conservation test against yet; what's testable today is the
+// transaction's own encode/sign/verify round-trip, covered below.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tx::TimeRange;
+    use zkdpos_crypto::priv_key_from_fs;
+    use zkdpos_crypto::rand::{thread_rng, Rng};
+
+    fn random_key() -> zkdpos_crypto::PrivateKey<Engine> {
+        priv_key_from_fs(thread_rng().gen())
+    }
+
+    /// `condition.condition_type()` tag, and the time range or witness
+    /// address it carries, must actually land in `get_bytes()` - otherwise
+    /// the settlement side would have nothing committed to check a presented
+    /// witness/time predicate against.
+    #[test]
+    fn get_bytes_commits_to_the_condition() {
+        let base = |condition: SpendingCondition| {
+            EscrowTransfer::new(
+                AccountId(1),
+                Address::from_low_u64_be(1),
+                Address::from_low_u64_be(2),
+                Address::from_low_u64_be(3),
+                TokenId(0),
+                BigUint::from(100u32),
+                BigUint::from(1u32),
+                condition,
+                Nonce(0),
+                TimeRange::default(),
+                None,
+            )
+            .get_bytes()
+        };
+
+        let time_bytes = base(SpendingCondition::Time(TimeRange::default()));
+        let witness_bytes = base(SpendingCondition::Witness(Address::from_low_u64_be(42)));
+        assert_ne!(time_bytes, witness_bytes);
+    }
+
+    #[test]
+    fn new_signed_produces_a_verifiable_signature() {
+        let key = random_key();
+        let tx = EscrowTransfer::new_signed(
+            AccountId(1),
+            Address::from_low_u64_be(1),
+            Address::from_low_u64_be(2),
+            Address::from_low_u64_be(3),
+            TokenId(0),
+            BigUint::from(100u32),
+            BigUint::from(1u32),
+            SpendingCondition::Time(TimeRange::default()),
+            Nonce(0),
+            TimeRange::default(),
+            &key,
+        )
+        .expect("signing should succeed");
+
+        assert_eq!(tx.verify_signature(), Some(PubKeyHash::from_privkey(&key)));
+    }
+}
@@ -1,7 +1,9 @@
 use crate::{
+    dlc,
     helpers::{
         is_fee_amount_packable, is_token_amount_packable, pack_fee_amount, pack_token_amount,
     },
+    oracle::{OracleError, PriceAttestation, PriceOracleConfig},
     tx::TimeRange,
     AccountId, Nonce, TokenId,
 };
@@ -15,7 +17,51 @@ use zkdpos_crypto::franklin_crypto::eddsa::PrivateKey;
 use zkdpos_crypto::params::{max_account_id, max_token_id};
 use zkdpos_utils::{format_units, BigUintSerdeAsRadix10Str};
 
-use super::{TxSignature, VerifiedSignatureCache};
+use super::{
+    AccountSignerSet, OracleAttestation, ThresholdMusigSignature, TxSignature,
+    VerifiableSignature, VerifiedSignatureCache,
+};
+
+/// Makes an `Exchange` contingent on an oracle-attested numeric outcome (e.g. a
+/// price feed reading) landing in `[interval_start, interval_end]`, instead of
+/// it always executing at the fixed `price` above. Chosen by the operator at
+/// signing time and folded into the signed transaction bytes (see
+/// `Exchange::get_bytes`), so it can't be altered after the fact. The
+/// attestation itself necessarily arrives later, from the oracle, and is
+/// supplied separately to `Exchange::verify_condition`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExchangeCondition {
+    pub oracle_pubkey_hash: PubKeyHash,
+    pub interval_start: u64,
+    pub interval_end: u64,
+    pub base: u64,
+    pub num_digits: usize,
+}
+
+impl ExchangeCondition {
+    pub fn to_be_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&self.oracle_pubkey_hash.data);
+        out.extend_from_slice(&self.interval_start.to_be_bytes());
+        out.extend_from_slice(&self.interval_end.to_be_bytes());
+        out.extend_from_slice(&self.base.to_be_bytes());
+        out.extend_from_slice(&(self.num_digits as u64).to_be_bytes());
+        out
+    }
+
+    /// `true` if `base`/`num_digits`/`interval_start <= interval_end` describe a
+    /// well-formed, representable interval.
+    pub fn check_correctness(&self) -> bool {
+        self.interval_start <= self.interval_end
+            && self.base >= 2
+            && self.num_digits >= 1
+            && self
+                .base
+                .checked_pow(self.num_digits as u32)
+                .map_or(true, |max| self.interval_end < max)
+    }
+}
 
 /// `Exchange` transaction performs a move of funds from one zkDpos account to another.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,14 +87,32 @@ pub struct Exchange {
     /// Fee for the transaction.
     #[serde(with = "BigUintSerdeAsRadix10Str")]
     pub fee: BigUint,
+    /// If set, makes this `Exchange` contingent on an oracle attestation
+    /// instead of executing unconditionally at `price`.
+    pub condition: Option<ExchangeCondition>,
+    /// If set, bounds `price` against a price-oracle attestation: the
+    /// `TxHandler` rejects the exchange if `price` deviates from the
+    /// attested price beyond the operator's configured tolerance, or if the
+    /// attestation is stale - see [`PriceOracleConfig::check`].
+    pub price_attestation: Option<PriceAttestation>,
     /// Current account nonce.
     pub nonce: Nonce,
     /// Time range when the transaction is valid
     /// This fields must be Option<...> because of backward compatibility with first version of ZkDpos
     #[serde(flatten)]
     pub time_range: Option<TimeRange>,
+    /// Binds the signature to a specific zkDpos deployment, so it can't be
+    /// replayed against another chain sharing the same account keys (e.g.
+    /// testnet -> mainnet, or a fork of this chain). `0` means "legacy, any
+    /// chain": old signatures that predate this field keep verifying exactly
+    /// as before, since `get_bytes` only folds it in when it's non-zero.
+    #[serde(default)]
+    pub chain_id: u16,
     /// Transaction zkDpos signature.
     pub signature: TxSignature,
+    /// If set, authorizes this `Exchange` via the account's registered
+    /// threshold-multisig signer set instead of `signature`.
+    pub threshold_auth: Option<ThresholdMusigSignature>,
     #[serde(skip)]
     cached_signer: VerifiedSignatureCache,
 }
@@ -71,9 +135,12 @@ impl Exchange {
         amount_b: BigUint,
         price: BigUint,
         fee: BigUint,
+        condition: Option<ExchangeCondition>,
+        price_attestation: Option<PriceAttestation>,
         nonce: Nonce,
         time_range: TimeRange,
         signature: Option<TxSignature>,
+        chain_id: u16,
     ) -> Self {
         let mut tx = Self {
             account_id,
@@ -84,9 +151,13 @@ impl Exchange {
             amount_b,
             price,
             fee,
+            condition,
+            price_attestation,
             nonce,
             time_range: Some(time_range),
+            chain_id,
             signature: signature.clone().unwrap_or_default(),
+            threshold_auth: None,
             cached_signer: VerifiedSignatureCache::NotCached,
         };
         if signature.is_some() {
@@ -107,12 +178,16 @@ impl Exchange {
         amount_b: BigUint,
         price: BigUint,
         fee: BigUint,
+        condition: Option<ExchangeCondition>,
+        price_attestation: Option<PriceAttestation>,
         nonce: Nonce,
         time_range: TimeRange,
         private_key: &PrivateKey<Engine>,
+        chain_id: u16,
     ) -> Result<Self, anyhow::Error> {
         let mut tx = Self::new(
-            account_id, from, token_a, token_b, amount_a, amount_b, price, fee, nonce, time_range, None,
+            account_id, from, token_a, token_b, amount_a, amount_b, price, fee, condition,
+            price_attestation, nonce, time_range, None, chain_id,
         );
         tx.signature = TxSignature::sign_musig(private_key, &tx.get_bytes());
         if !tx.check_correctness() {
@@ -133,10 +208,19 @@ impl Exchange {
         out.extend_from_slice(&pack_token_amount(&self.amount_b));
         out.extend_from_slice(&pack_fee_amount(&self.price));
         out.extend_from_slice(&pack_fee_amount(&self.fee));
+        if let Some(condition) = &self.condition {
+            out.extend_from_slice(&condition.to_be_bytes());
+        }
+        if let Some(price_attestation) = &self.price_attestation {
+            out.extend_from_slice(&price_attestation.to_be_bytes());
+        }
         out.extend_from_slice(&self.nonce.to_be_bytes());
         if let Some(time_range) = &self.time_range {
             out.extend_from_slice(&time_range.to_be_bytes());
         }
+        if self.chain_id != 0 {
+            out.extend_from_slice(&self.chain_id.to_be_bytes());
+        }
         out
     }
 
@@ -162,10 +246,23 @@ impl Exchange {
             && self
                 .time_range
                 .map(|r| r.check_correctness())
+                .unwrap_or(true)
+            && self
+                .condition
+                .as_ref()
+                .map(ExchangeCondition::check_correctness)
+                .unwrap_or(true)
+            && self
+                .threshold_auth
+                .as_ref()
+                .map(|auth| {
+                    !auth.signatures.is_empty()
+                        && auth.participant_bitmap.count_ones() as usize == auth.signatures.len()
+                })
                 .unwrap_or(true);
         if valid {
             let signer = self.verify_signature();
-            valid = valid && signer.is_some();
+            valid = valid && (signer.is_some() || self.threshold_auth.is_some());
             self.cached_signer = VerifiedSignatureCache::Cached(signer);
         };
         valid
@@ -182,6 +279,72 @@ impl Exchange {
         }
     }
 
+    /// Verifies that this `Exchange`'s `threshold_auth` (if present) meets
+    /// `key_set`'s quorum over `get_bytes()`, recognizing the account's
+    /// registered threshold-multisig signers as an alternative to the single
+    /// `signature` field. Returns the signer set's key commitment on success.
+    pub fn verify_threshold_auth(&self, key_set: &AccountSignerSet) -> Option<PubKeyHash> {
+        self.threshold_auth
+            .as_ref()
+            .and_then(|auth| auth.verify(&self.get_bytes(), key_set))
+    }
+
+    /// Verifies that this `Exchange`'s `condition` is satisfied by `attestation`:
+    /// the attestation must be signed by the condition's `oracle_pubkey_hash`,
+    /// and its attested outcome's digit prefix must match one of the groups
+    /// [`dlc::decompose_range`] produces for `[interval_start, interval_end]`.
+    /// Returns `false` (rather than panicking) if this `Exchange` has no
+    /// `condition` at all, since an unconditional exchange can't be satisfied by
+    /// an attestation that doesn't apply to it.
+    pub fn verify_condition(&self, attestation: &OracleAttestation) -> bool {
+        let condition = match &self.condition {
+            Some(condition) => condition,
+            None => return false,
+        };
+        if attestation.oracle_pubkey_hash != condition.oracle_pubkey_hash {
+            return false;
+        }
+        if !attestation.verify() {
+            return false;
+        }
+        let groups = match dlc::decompose_range(
+            condition.interval_start,
+            condition.interval_end,
+            condition.base,
+            condition.num_digits,
+        ) {
+            Ok(groups) => groups,
+            Err(_) => return false,
+        };
+        groups.iter().any(|prefix| {
+            dlc::outcome_matches_prefix(
+                attestation.outcome,
+                condition.base,
+                condition.num_digits,
+                prefix,
+            )
+        })
+    }
+
+    /// Verifies this `Exchange`'s `price_attestation` (if present) against
+    /// `config`: the oracle index must resolve to a configured key, the
+    /// attestation's signature must verify, it must not be stale relative to
+    /// `time_range`'s `valid_from`, and `price` must be within the
+    /// configured tolerance of the attested price. Returns `Ok(())` if there
+    /// is no `price_attestation` at all, since an exchange with no attested
+    /// bound has nothing to check here.
+    pub fn verify_price_attestation(&self, config: &PriceOracleConfig) -> Result<(), OracleError> {
+        let attestation = match &self.price_attestation {
+            Some(attestation) => attestation,
+            None => return Ok(()),
+        };
+        let valid_from = self
+            .time_range
+            .map(|time_range| time_range.valid_from)
+            .unwrap_or(0);
+        config.check(attestation, self.token_a, self.token_b, &self.price, valid_from)
+    }
+
     /// Get the first part of the message we expect to be signed by Alaya account key.
     /// The only difference is the missing `nonce` since it's added at the end of the transactions
     /// batch message.
@@ -233,3 +396,9 @@ impl Exchange {
         )
     }
 }
+
+impl VerifiableSignature for Exchange {
+    fn verify_signature(&self) -> Option<PubKeyHash> {
+        Self::verify_signature(self)
+    }
+}
@@ -0,0 +1,156 @@
+use crate::{
+    helpers::{is_fee_amount_packable, pack_fee_amount},
+    AccountId, Nonce, TokenId,
+};
+
+use crate::account::PubKeyHash;
+use crate::Engine;
+use num::BigUint;
+use serde::{Deserialize, Serialize};
+use zkdpos_crypto::franklin_crypto::eddsa::PrivateKey;
+use zkdpos_crypto::params::{max_account_id, max_token_id};
+use zkdpos_utils::BigUintSerdeAsRadix10Str;
+
+use super::{TimeRange, TxSignature, VerifiableSignature, VerifiedSignatureCache};
+
+/// Grants signing authority over this account to `delegate` (or, when
+/// `delegate` is the default `PubKeyHash`, revokes whatever delegate is
+/// currently installed): once granted, a transaction for this account may be
+/// authorized either by the account's own `PubKeyHash` or by `delegate`,
+/// letting one hot key sign on behalf of many accounts without ever holding
+/// their individual spending keys. Borrows the same idea underlying
+/// `AdvanceNonce`'s nonce-authority separation: a distinct key authorizes
+/// state changes on an account it doesn't equal.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrantDelegate {
+    /// zkDpos network account ID granting (or revoking) delegated authority.
+    pub account_id: AccountId,
+    /// The delegate's `PubKeyHash`, or the default value to revoke.
+    pub delegate: PubKeyHash,
+    /// Token the fee is paid in.
+    pub fee_token: TokenId,
+    /// Fee for the transaction.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub fee: BigUint,
+    /// Current account nonce.
+    pub nonce: Nonce,
+    /// Time range when the transaction is valid.
+    #[serde(flatten)]
+    pub time_range: Option<TimeRange>,
+    /// Transaction zkDpos signature, from the account's own key - a
+    /// delegate cannot grant authority to itself or anyone else.
+    pub signature: TxSignature,
+    #[serde(skip)]
+    cached_signer: VerifiedSignatureCache,
+}
+
+impl GrantDelegate {
+    /// Unique identifier of the transaction type in zkDpos network.
+    pub const TX_TYPE: u8 = 17;
+
+    /// Creates transaction from all the required fields.
+    ///
+    /// While `signature` field is mandatory for new transactions, it may be `None`
+    /// in some cases (e.g. when restoring the network state from the L1 contract data).
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        account_id: AccountId,
+        delegate: PubKeyHash,
+        fee_token: TokenId,
+        fee: BigUint,
+        nonce: Nonce,
+        time_range: TimeRange,
+        signature: Option<TxSignature>,
+    ) -> Self {
+        let mut tx = Self {
+            account_id,
+            delegate,
+            fee_token,
+            fee,
+            nonce,
+            time_range: Some(time_range),
+            signature: signature.clone().unwrap_or_default(),
+            cached_signer: VerifiedSignatureCache::NotCached,
+        };
+        if signature.is_some() {
+            tx.cached_signer = VerifiedSignatureCache::Cached(tx.verify_signature());
+        }
+        tx
+    }
+
+    /// Creates a signed transaction using private key and
+    /// checks for the transaction correcteness.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_signed(
+        account_id: AccountId,
+        delegate: PubKeyHash,
+        fee_token: TokenId,
+        fee: BigUint,
+        nonce: Nonce,
+        time_range: TimeRange,
+        private_key: &PrivateKey<Engine>,
+    ) -> Result<Self, anyhow::Error> {
+        let mut tx = Self::new(account_id, delegate, fee_token, fee, nonce, time_range, None);
+        tx.signature = TxSignature::sign_musig(private_key, &tx.get_bytes());
+        if !tx.check_correctness() {
+            anyhow::bail!(crate::tx::TRANSACTION_SIGNATURE_ERROR);
+        }
+        Ok(tx)
+    }
+
+    /// Encodes the transaction data as the byte sequence according to the zkDpos protocol.
+    pub fn get_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&[Self::TX_TYPE]);
+        out.extend_from_slice(&self.account_id.to_be_bytes());
+        out.extend_from_slice(&self.delegate.data);
+        out.extend_from_slice(&self.fee_token.to_be_bytes());
+        out.extend_from_slice(&pack_fee_amount(&self.fee));
+        out.extend_from_slice(&self.nonce.to_be_bytes());
+        if let Some(time_range) = &self.time_range {
+            out.extend_from_slice(&time_range.to_be_bytes());
+        }
+        out
+    }
+
+    /// Verifies the transaction correctness:
+    ///
+    /// - `account_id` field must be within supported range.
+    /// - `fee_token` field must be within supported range.
+    /// - `fee` field must represent a packable value.
+    /// - zkDpos signature must correspond to the PubKeyHash of the account.
+    pub fn check_correctness(&mut self) -> bool {
+        let mut valid = is_fee_amount_packable(&self.fee)
+            && self.account_id <= max_account_id()
+            && self.fee_token <= max_token_id()
+            && self
+                .time_range
+                .map(|r| r.check_correctness())
+                .unwrap_or(true);
+
+        if valid {
+            let signer = self.verify_signature();
+            valid = valid && signer.is_some();
+            self.cached_signer = VerifiedSignatureCache::Cached(signer);
+        }
+        valid
+    }
+
+    /// Restores the `PubKeyHash` from the transaction signature.
+    pub fn verify_signature(&self) -> Option<PubKeyHash> {
+        if let VerifiedSignatureCache::Cached(cached_signer) = &self.cached_signer {
+            *cached_signer
+        } else {
+            self.signature
+                .verify_musig(&self.get_bytes())
+                .map(|pub_key| PubKeyHash::from_pubkey(&pub_key))
+        }
+    }
+}
+
+impl VerifiableSignature for GrantDelegate {
+    fn verify_signature(&self) -> Option<PubKeyHash> {
+        Self::verify_signature(self)
+    }
+}
@@ -1,10 +1,26 @@
 //! zkDpos network L2 transactions.
 
+mod add_liquidity;
+mod advance_nonce;
+mod atomic_bundle;
+mod batch;
 mod change_pubkey;
 mod close;
+mod conditional_settle;
+mod conditional_transfer;
+mod escrow_transfer;
+mod exchange;
 mod forced_exit;
+mod grant_delegate;
+mod order_match;
 mod primitives;
+mod range_settle;
+mod remove_liquidity;
+mod signed_batch;
+mod swap;
 mod transfer;
+mod verified_tx;
+mod versioned;
 mod withdraw;
 mod zkdpos_tx;
 
@@ -15,21 +31,51 @@ mod tests;
 #[doc(hidden)]
 pub use self::close::Close;
 pub use self::{
+    add_liquidity::AddLiquidity,
+    advance_nonce::{next_durable_nonce, AdvanceNonce},
+    atomic_bundle::AtomicBundle,
+    batch::{
+        compute_batch_hash, eip712_batch_struct_hash, get_eip712_batch_signed_data,
+        verify_batch_hash, BatchTx,
+    },
     change_pubkey::{
-        ChangePubKey, ChangePubKeyCREATE2Data, ChangePubKeyECDSAData, ChangePubKeyAtpAuthData,
+        compute_create2_wallet_address, verify_change_pub_key_batch, ChangePubKey,
+        ChangePubKeyAtpAuthData, ChangePubKeyCREATE2Data, ChangePubKeyECDSAData,
+        ChangePubKeyEIP712Data,
     },
+    conditional_settle::ConditionalSettle,
+    conditional_transfer::ConditionalTransfer,
+    escrow_transfer::EscrowTransfer,
+    exchange::{Exchange, ExchangeCondition},
     forced_exit::ForcedExit,
+    grant_delegate::GrantDelegate,
+    order_match::OrderMatch,
+    range_settle::RangeSettleComplete,
+    remove_liquidity::RemoveLiquidity,
+    signed_batch::{BatchValidationError, SignedBatch},
+    swap::Swap,
     transfer::Transfer,
+    verified_tx::{TxError, VerifiedTx},
+    versioned::{decode_envelope, encode_envelope, VersionedZkDposTx, VERSIONED_TX_SENTINEL},
     withdraw::Withdraw,
     zkdpos_tx::{AtpSignData, SignedZkDposTx, ZkDposTx},
 };
 
 // Re-export primitives associated with transactions.
 pub use self::primitives::{
-    eip1271_signature::EIP1271Signature, atp_batch_sign_data::AtpBatchSignData,
-    atp_batch_signature::AtpBatchSignatures, atp_signature::TxAtpSignature,
-    packed_atp_signature::PackedAtpSignature, packed_public_key::PackedPublicKey,
+    eip1271_signature::EIP1271Signature, account_signer_set::AccountSignerSet,
+    atp_batch_sign_data::AtpBatchSignData,
+    atp_batch_signature::AtpBatchSignatures,
+    atp_signature::{MultisigKeySet, TxAtpSignature},
+    batch_verify::{verify_batch as verify_signatures_batch, VerifiableSignature},
+    eip712::{eip712_digest, eip712_domain_separator, encode_word},
+    oracle_attestation::OracleAttestation,
+    order::Order,
+    packed_atp_signature::{AdaptorSignature, PackedAtpSignature}, packed_public_key::PackedPublicKey,
     packed_signature::PackedSignature, signature::TxSignature, time_range::TimeRange,
+    predicate_node::{PredicateLeaf, PredicateNode},
+    spending_condition::SpendingCondition,
+    threshold_musig_signature::ThresholdMusigSignature,
     tx_hash::TxHash,
 };
 
@@ -0,0 +1,167 @@
+use crate::{
+    helpers::{is_fee_amount_packable, pack_fee_amount},
+    AccountId, Nonce, TokenId,
+};
+use num::BigUint;
+
+use crate::account::PubKeyHash;
+use crate::Engine;
+use serde::{Deserialize, Serialize};
+use zkdpos_crypto::franklin_crypto::eddsa::PrivateKey;
+use zkdpos_crypto::params::max_account_id;
+use zkdpos_utils::BigUintSerdeAsRadix10Str;
+
+use super::{Order, TimeRange, TxSignature, VerifiableSignature, VerifiedSignatureCache};
+
+/// `OrderMatch` atomically settles two independently-signed limit [`Order`]s
+/// against each other: `order_a.token_sell` must equal `order_b.token_buy`
+/// (and vice versa), and `amount` (of `order_a.token_sell`) is the quantity
+/// actually filled on this match - it may be less than either order's own
+/// `amount`, leaving the remainder open for a later match. The submitter
+/// (`account_id`, which must be `order_a.account_id`) pays `fee` in
+/// `order_a.token_sell` for bringing the two orders together.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderMatch {
+    /// zkDpos network account ID of the transaction initiator; must match `order_a.account_id`.
+    pub account_id: AccountId,
+    /// The taker order; its owner pays the matching `fee`.
+    pub order_a: Order,
+    /// The counter-order being matched against.
+    pub order_b: Order,
+    /// Quantity of `order_a.token_sell` filled by this match.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub amount: BigUint,
+    /// Fee for the transaction, paid in `order_a.token_sell`.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub fee: BigUint,
+    /// Current account nonce.
+    pub nonce: Nonce,
+    /// Time range when the transaction is valid
+    /// This fields must be Option<...> because of backward compatibility with first version of ZkDpos
+    #[serde(flatten)]
+    pub time_range: Option<TimeRange>,
+    /// Transaction zkDpos signature.
+    pub signature: TxSignature,
+    #[serde(skip)]
+    cached_signer: VerifiedSignatureCache,
+}
+
+impl OrderMatch {
+    /// Unique identifier of the transaction type in zkDpos network.
+    pub const TX_TYPE: u8 = 13;
+
+    /// Creates transaction from all the required fields.
+    ///
+    /// While `signature` field is mandatory for new transactions, it may be `None`
+    /// in some cases (e.g. when restoring the network state from the L1 contract data).
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        account_id: AccountId,
+        order_a: Order,
+        order_b: Order,
+        amount: BigUint,
+        fee: BigUint,
+        nonce: Nonce,
+        time_range: TimeRange,
+        signature: Option<TxSignature>,
+    ) -> Self {
+        let mut tx = Self {
+            account_id,
+            order_a,
+            order_b,
+            amount,
+            fee,
+            nonce,
+            time_range: Some(time_range),
+            signature: signature.clone().unwrap_or_default(),
+            cached_signer: VerifiedSignatureCache::NotCached,
+        };
+        if signature.is_some() {
+            tx.cached_signer = VerifiedSignatureCache::Cached(tx.verify_signature());
+        }
+        tx
+    }
+
+    /// Creates a signed transaction using private key and
+    /// checks for the transaction correcteness.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_signed(
+        account_id: AccountId,
+        order_a: Order,
+        order_b: Order,
+        amount: BigUint,
+        fee: BigUint,
+        nonce: Nonce,
+        time_range: TimeRange,
+        private_key: &PrivateKey<Engine>,
+    ) -> Result<Self, anyhow::Error> {
+        let mut tx = Self::new(
+            account_id, order_a, order_b, amount, fee, nonce, time_range, None,
+        );
+        tx.signature = TxSignature::sign_musig(private_key, &tx.get_bytes());
+        if !tx.check_correctness() {
+            anyhow::bail!(crate::tx::TRANSACTION_SIGNATURE_ERROR);
+        }
+        Ok(tx)
+    }
+
+    /// Encodes the transaction data as the byte sequence according to the zkDpos protocol.
+    pub fn get_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&[Self::TX_TYPE]);
+        out.extend_from_slice(&self.account_id.to_be_bytes());
+        out.extend_from_slice(&self.order_a.get_bytes());
+        out.extend_from_slice(&self.order_b.get_bytes());
+        out.extend_from_slice(&self.amount.to_bytes_be());
+        out.extend_from_slice(&self.order_a.token_sell.to_be_bytes());
+        out.extend_from_slice(&pack_fee_amount(&self.fee));
+        out.extend_from_slice(&self.nonce.to_be_bytes());
+        if let Some(time_range) = &self.time_range {
+            out.extend_from_slice(&time_range.to_be_bytes());
+        }
+        out
+    }
+
+    /// Verifies the transaction correctness:
+    ///
+    /// - `account_id` field must equal `order_a.account_id`.
+    /// - `fee` field must represent a packable value.
+    /// - both orders must individually be well-formed.
+    /// - zkDpos signature must correspond to the PubKeyHash of the account.
+    pub fn check_correctness(&mut self) -> bool {
+        let mut valid = is_fee_amount_packable(&self.fee)
+            && self.account_id <= max_account_id()
+            && self.account_id == self.order_a.account_id
+            && self.order_a.check_correctness()
+            && self.order_b.check_correctness()
+            && self
+                .time_range
+                .map(|r| r.check_correctness())
+                .unwrap_or(true);
+
+        if valid {
+            let signer = self.verify_signature();
+            valid = valid && signer.is_some();
+            self.cached_signer = VerifiedSignatureCache::Cached(signer);
+        }
+        valid
+    }
+
+    /// Restores the `PubKeyHash` from the transaction signature.
+    pub fn verify_signature(&self) -> Option<PubKeyHash> {
+        if let VerifiedSignatureCache::Cached(cached_signer) = &self.cached_signer {
+            *cached_signer
+        } else {
+            self.signature
+                .verify_musig(&self.get_bytes())
+                .map(|pub_key| PubKeyHash::from_pubkey(&pub_key))
+        }
+    }
+}
+
+impl VerifiableSignature for OrderMatch {
+    fn verify_signature(&self) -> Option<PubKeyHash> {
+        Self::verify_signature(self)
+    }
+}
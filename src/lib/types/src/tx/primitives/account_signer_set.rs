@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+
+use crate::account::PubKeyHash;
+use parity_crypto::Keccak256;
+
+/// An account's registered threshold-multisig signer set: `m` zkDpos public keys
+/// (identified, as everywhere else in this crate, by their [`PubKeyHash`] rather
+/// than the raw EdDSA point) of which any `threshold` signing together authorize
+/// a [`super::threshold_musig_signature::ThresholdMusigSignature`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AccountSignerSet {
+    pub threshold: u8,
+    pub signers: Vec<PubKeyHash>,
+}
+
+impl AccountSignerSet {
+    /// A deterministic hash commitment to this `(threshold, signers)` set.
+    ///
+    /// This is a scoped, explicit substitution for the on-curve MuSig
+    /// key-aggregation the original request called for: a true aggregated key
+    /// would be a single EdDSA point computed from the participants' public
+    /// keys (with the standard per-signer coefficients to block
+    /// rogue-key-style attacks), and a transaction would then carry one
+    /// aggregate signature verifiable against it. This crate snapshot has no
+    /// type for an EdDSA curve point and no point-addition/scalar-multiply
+    /// operation to build one from (`PubKeyHash` is a one-way hash of a
+    /// public key, not the point itself), so that path isn't implementable
+    /// here. `ThresholdMusigSignature` is built against this hash-commitment
+    /// model instead: it verifies `threshold`-of-`signers` independent
+    /// signatures rather than one aggregated signature. `signers` is sorted
+    /// before hashing so membership, not registration order, determines the
+    /// commitment.
+    pub fn commitment(&self) -> PubKeyHash {
+        let mut sorted_signers = self.signers.clone();
+        sorted_signers.sort_by_key(|signer| signer.data);
+
+        let mut preimage = Vec::new();
+        preimage.push(self.threshold);
+        for signer in &sorted_signers {
+            preimage.extend_from_slice(&signer.data);
+        }
+        let hash = preimage.keccak256();
+        PubKeyHash::from_bytes(&hash[hash.len() - 20..])
+            .expect("keccak256 output truncated to 20 bytes is always a valid PubKeyHash")
+    }
+}
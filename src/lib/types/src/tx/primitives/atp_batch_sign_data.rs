@@ -4,7 +4,7 @@ use itertools::Itertools;
 // Workspace uses
 use zkdpos_basic_types::Address;
 // Local uses
-use super::atp_signature::TxAtpSignature;
+use super::atp_signature::{MultisigKeySet, TxAtpSignature};
 use crate::{Token, ZkDposTx};
 
 /// Encapsulates transactions batch signature data. Should only be created via `new()`
@@ -92,6 +92,24 @@ impl AtpBatchSignData {
         }
     }
 
+    /// Verifies a multisig-authorized batch: exactly one of `self.signatures` must be
+    /// a `TxAtpSignature::Multisig` entry collecting the partial signatures of the
+    /// account's co-signers over `self.message`, and its quorum must be met against
+    /// `key_set`. Returns the authorizing signers on success.
+    pub fn verify_multisig(&self, key_set: &MultisigKeySet) -> anyhow::Result<Vec<Address>> {
+        let multisig_entries: Vec<&TxAtpSignature> = self
+            .signatures
+            .iter()
+            .filter(|signature| matches!(signature, TxAtpSignature::Multisig { .. }))
+            .collect();
+        ensure!(
+            multisig_entries.len() == 1,
+            "Multisig batch must carry exactly one aggregated signature entry, found {}",
+            multisig_entries.len()
+        );
+        multisig_entries[0].verify_multisig(None, &self.message, key_set)
+    }
+
     /// Returns an old-format message that should be signed by Alaya account key.
     /// Needed for backwards compatibility.
     pub fn get_old_alaya_batch_message<'a, I>(txs: I) -> Vec<u8>
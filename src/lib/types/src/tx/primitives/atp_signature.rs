@@ -1,13 +1,110 @@
+use anyhow::ensure;
 use crate::tx::{EIP1271Signature, PackedAtpSignature};
 use serde::{Deserialize, Serialize};
+use zkdpos_basic_types::{Address, H256};
+
+/// An account's configured multisig signer set: any `threshold` of `signers`
+/// signing together authorize a `TxAtpSignature::Multisig`-signed message.
+/// This lives on the account rather than the signature, since a signature
+/// claiming its own threshold can't be trusted to police itself.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MultisigKeySet {
+    pub threshold: u8,
+    pub signers: Vec<Address>,
+}
 
 /// Representation of the signature secured by L1.
 /// May be either a signature generated via Alaya private key
 /// corresponding to the account address,
-/// or on-chain signature via EIP-1271.
+/// an on-chain signature via EIP-1271,
+/// or a quorum of signatures from an account's multisig signer set.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "type", content = "signature")]
 pub enum TxAtpSignature {
     AlayaSignature(PackedAtpSignature),
     EIP1271Signature(EIP1271Signature),
+    Multisig {
+        threshold: u8,
+        signatures: Vec<(Address, PackedAtpSignature)>,
+    },
+}
+
+impl TxAtpSignature {
+    /// Recovers the signer's Alaya address, accepting either an EIP-712 typed
+    /// digest or the legacy plaintext message it supersedes: `typed_digest` is
+    /// tried first (when given), falling back to `legacy_message` so that
+    /// clients that haven't adopted EIP-712 signing yet keep working.
+    ///
+    /// `EIP1271Signature` can't be checked here: unlike an Alaya-key signature,
+    /// validating it means calling `isValidSignature` on the signer's L1
+    /// contract, which this crate has no access to.
+    pub fn recover_signer(
+        &self,
+        typed_digest: Option<H256>,
+        legacy_message: &[u8],
+    ) -> Result<Address, anyhow::Error> {
+        match self {
+            TxAtpSignature::AlayaSignature(signature) => {
+                if let Some(digest) = typed_digest {
+                    if let Ok(address) = signature.signature_recover_signer_from_digest(digest) {
+                        return Ok(address);
+                    }
+                }
+                signature.signature_recover_signer(legacy_message)
+            }
+            TxAtpSignature::EIP1271Signature(_) => anyhow::bail!(
+                "EIP1271Signature can only be verified on-chain via isValidSignature"
+            ),
+            TxAtpSignature::Multisig { .. } => {
+                anyhow::bail!("Multisig signature must be checked via Self::verify_multisig")
+            }
+        }
+    }
+
+    /// Verifies a `Multisig` signature against the account's actual `key_set`:
+    /// recovers each co-signer's address over `typed_digest` (if given, falling back
+    /// to `legacy_message` on failure) or else `legacy_message` alone, checks it
+    /// matches the address it claims to be from, deduplicates recovered signers, and
+    /// requires at least `key_set.threshold` of them to belong to `key_set.signers`.
+    /// The quorum is checked against `key_set.threshold`, not the `threshold` field
+    /// carried by `self`, since the latter is attacker-controlled and can't be
+    /// trusted to police itself. Returns the deduplicated, authorized signers on
+    /// success.
+    pub fn verify_multisig(
+        &self,
+        typed_digest: Option<H256>,
+        legacy_message: &[u8],
+        key_set: &MultisigKeySet,
+    ) -> Result<Vec<Address>, anyhow::Error> {
+        let signatures = match self {
+            TxAtpSignature::Multisig { signatures, .. } => signatures,
+            _ => anyhow::bail!("not a Multisig signature"),
+        };
+        ensure!(!signatures.is_empty(), "Multisig signature carries no co-signer signatures");
+
+        let mut authorized_signers = Vec::new();
+        for (claimed_address, signature) in signatures {
+            let recovered = match typed_digest {
+                Some(digest) => signature
+                    .signature_recover_signer_from_digest(digest)
+                    .or_else(|_| signature.signature_recover_signer(legacy_message))?,
+                None => signature.signature_recover_signer(legacy_message)?,
+            };
+            ensure!(
+                recovered == *claimed_address,
+                "Multisig signature does not match its claimed signer address"
+            );
+            if key_set.signers.contains(&recovered) && !authorized_signers.contains(&recovered) {
+                authorized_signers.push(recovered);
+            }
+        }
+
+        ensure!(
+            authorized_signers.len() >= key_set.threshold as usize,
+            "Multisig quorum not met: {} of {} required signatures from the account's key set",
+            authorized_signers.len(),
+            key_set.threshold
+        );
+        Ok(authorized_signers)
+    }
 }
@@ -0,0 +1,34 @@
+//! Parallel signature verification across many transactions, of the same or
+//! different types.
+//!
+//! A true batch-accumulator scheme (checking `Σ rᵢ·(sᵢ·G − cᵢ·Pᵢ) == 0` for
+//! independently-random scalars `rᵢ`, the way Solana's ed25519 verifier
+//! batches signatures) would let the whole set be checked with a single
+//! multi-scalar multiplication instead of one verification per signature.
+//! That requires access to the raw curve arithmetic behind musig
+//! verification, which `zkdpos_crypto` does not expose publicly. Until it
+//! does, [`verify_batch`] instead parallelizes the existing per-transaction
+//! verification with rayon — which is also the fallback the accumulator
+//! scheme itself would use to locate an offending signature once a batch
+//! fails.
+
+use rayon::prelude::*;
+
+use crate::account::PubKeyHash;
+
+/// A transaction whose zkDpos signature can be verified independently of any
+/// other transaction, making it safe to verify many of them concurrently.
+pub trait VerifiableSignature: Sync {
+    /// Recovers and verifies the signer's `PubKeyHash`, or `None` if the
+    /// signature is invalid. Implementors are expected to consult their own
+    /// `VerifiedSignatureCache` first, the same way their inherent
+    /// `verify_signature` does.
+    fn verify_signature(&self) -> Option<PubKeyHash>;
+}
+
+/// Verifies the signatures of `txs` in parallel, returning one result per
+/// transaction in the same order. `txs` may be a mix of different
+/// transaction types, as long as they all implement [`VerifiableSignature`].
+pub fn verify_batch<T: VerifiableSignature>(txs: &[&T]) -> Vec<Option<PubKeyHash>> {
+    txs.par_iter().map(|tx| tx.verify_signature()).collect()
+}
@@ -1,12 +1,20 @@
 pub mod eip1271_signature;
+pub mod account_signer_set;
 pub mod atp_batch_sign_data;
 pub mod atp_batch_signature;
 pub mod atp_signature;
+pub mod batch_verify;
+pub mod eip712;
+pub mod oracle_attestation;
+pub mod order;
 pub mod packed_atp_signature;
 pub mod packed_public_key;
 pub mod packed_signature;
+pub mod predicate_node;
 pub mod signature;
 pub mod signature_cache;
+pub mod spending_condition;
+pub mod threshold_musig_signature;
 pub mod time_range;
 pub mod tx_hash;
 
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+use crate::account::PubKeyHash;
+use crate::tx::TxSignature;
+
+/// An oracle's Schnorr (zkDpos musig) signature attesting to a single numeric
+/// `outcome` it observed (e.g. a price feed reading), over the same signature
+/// scheme `TxSignature` uses to authorize transactions. `oracle_pubkey_hash`
+/// identifies the oracle the same way `PubKeyHash` identifies a zkDpos account,
+/// so verification doesn't need the oracle's raw public key on hand.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OracleAttestation {
+    pub oracle_pubkey_hash: PubKeyHash,
+    pub outcome: u64,
+    pub signature: TxSignature,
+}
+
+impl OracleAttestation {
+    /// The message the oracle signs: the outcome's big-endian bytes.
+    pub fn message(outcome: u64) -> Vec<u8> {
+        outcome.to_be_bytes().to_vec()
+    }
+
+    /// Verifies that `signature` is a valid signature by `oracle_pubkey_hash`
+    /// over `outcome`.
+    pub fn verify(&self) -> bool {
+        match self.signature.verify_musig(&Self::message(self.outcome)) {
+            Some(pub_key) => PubKeyHash::from_pubkey(&pub_key) == self.oracle_pubkey_hash,
+            None => false,
+        }
+    }
+}
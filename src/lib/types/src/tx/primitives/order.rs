@@ -0,0 +1,88 @@
+use num::BigUint;
+use serde::{Deserialize, Serialize};
+use zkdpos_basic_types::Address;
+use zkdpos_utils::BigUintSerdeAsRadix10Str;
+
+use crate::account::PubKeyHash;
+use crate::tx::TimeRange;
+use crate::{AccountId, Nonce, TokenId};
+
+use super::signature::TxSignature;
+
+/// A byte independent of any `ZkDposTx::TX_TYPE`: an `Order` is never submitted
+/// as a transaction on its own, only embedded (and independently signed)
+/// inside an [`super::super::order_match::OrderMatch`], so its signed message
+/// needs its own domain separator rather than one of the registered tx types.
+const ORDER_MESSAGE_PREFIX: u8 = 0xa1;
+
+/// A single limit order: sell up to `amount` of `token_sell` for `token_buy`,
+/// at a price no worse than `price` (`sell_amount : buy_amount`, so a higher
+/// ratio is better for the seller), signed independently of whatever
+/// `OrderMatch` ends up settling it against a counter-order.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Order {
+    /// zkDpos network account ID of the order's owner.
+    pub account_id: AccountId,
+    /// Address credited with `token_buy` once the order is (partially) filled.
+    pub recipient: Address,
+    /// Current account nonce; prevents the same order from being replayed
+    /// once its owner has moved on (e.g. cancelled and resubmitted at a
+    /// different price under the same or a later nonce).
+    pub nonce: Nonce,
+    /// Token the order sells.
+    pub token_sell: TokenId,
+    /// Token the order buys.
+    pub token_buy: TokenId,
+    /// Minimum acceptable `sell_amount : buy_amount` ratio.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub price_sell: BigUint,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub price_buy: BigUint,
+    /// Maximum amount of `token_sell` this order is willing to fill.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub amount: BigUint,
+    /// Time range when the order is valid.
+    pub time_range: TimeRange,
+    /// Order owner's signature over this order's own fields.
+    pub signature: TxSignature,
+}
+
+impl Order {
+    /// The ratio the order requires: `(sell_amount, buy_amount)`.
+    pub fn price(&self) -> (BigUint, BigUint) {
+        (self.price_sell.clone(), self.price_buy.clone())
+    }
+
+    /// Encodes the order as the byte sequence it's signed over.
+    pub fn get_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.push(ORDER_MESSAGE_PREFIX);
+        out.extend_from_slice(&self.account_id.to_be_bytes());
+        out.extend_from_slice(self.recipient.as_bytes());
+        out.extend_from_slice(&self.nonce.to_be_bytes());
+        out.extend_from_slice(&self.token_sell.to_be_bytes());
+        out.extend_from_slice(&self.token_buy.to_be_bytes());
+        out.extend_from_slice(&self.price_sell.to_bytes_be());
+        out.extend_from_slice(&self.price_buy.to_bytes_be());
+        out.extend_from_slice(&self.amount.to_bytes_be());
+        out.extend_from_slice(&self.time_range.to_be_bytes());
+        out
+    }
+
+    /// Restores the `PubKeyHash` from the order's own signature.
+    pub fn verify_signature(&self) -> Option<PubKeyHash> {
+        self.signature
+            .verify_musig(&self.get_bytes())
+            .map(|pub_key| PubKeyHash::from_pubkey(&pub_key))
+    }
+
+    /// `price_sell`/`price_buy` must both be nonzero (otherwise the ratio is
+    /// undefined) and the order's own `time_range` must be well-formed.
+    pub fn check_correctness(&self) -> bool {
+        self.price_sell != BigUint::from(0u8)
+            && self.price_buy != BigUint::from(0u8)
+            && self.amount != BigUint::from(0u8)
+            && self.time_range.check_correctness()
+    }
+}
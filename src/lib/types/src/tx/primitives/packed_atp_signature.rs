@@ -3,6 +3,7 @@ use parity_crypto::{
     publickey::{public_to_address, recover, sign, KeyPair, Signature as ATPSignature},
     Keccak256,
 };
+use secp256k1_zkp::{ecdsa_adaptor::EcdsaAdaptorSignature, Message, PublicKey, Secp256k1, SecretKey};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use zkdpos_basic_types::{Address, H256};
 use zkdpos_utils::ZeroPrefixHexSerde;
@@ -71,10 +72,131 @@ impl PackedAtpSignature {
         Ok(public_to_address(&public_key))
     }
 
+    /// Checks signature and returns the Alaya address of the signer, for a
+    /// signature produced over a raw digest (e.g. an EIP-712 typed-data digest)
+    /// rather than a `personal_sign`-style message. Unlike
+    /// [`Self::signature_recover_signer`], `digest` is recovered from directly,
+    /// with no `\x19Alaya Signed Message:` prefix added, since EIP-712 signers
+    /// sign the digest as-is.
+    pub fn signature_recover_signer_from_digest(&self, digest: H256) -> Result<Address, anyhow::Error> {
+        let public_key = recover(&self.0, &digest)?;
+        Ok(public_to_address(&public_key))
+    }
+
     /// Get Alaya address from private key.
     pub fn address_from_private_key(private_key: &H256) -> Result<Address, anyhow::Error> {
         Ok(KeyPair::from_secret((*private_key).into())?.address())
     }
+
+    /// Produces an ECDSA "adaptor" pre-signature over `msg` under
+    /// `private_key`, encrypted with respect to `adaptor_point`. The result
+    /// is not itself a valid signature - see [`AdaptorSignature`].
+    pub fn encrypt_sign(
+        private_key: &H256,
+        msg: &[u8],
+        adaptor_point: &PublicKey,
+    ) -> Result<AdaptorSignature, anyhow::Error> {
+        let secp = Secp256k1::signing_only();
+        let secret_key = SecretKey::from_slice(private_key.as_bytes())?;
+        let signed_bytes = Self::message_to_signed_bytes(msg);
+        let message = Message::from_slice(signed_bytes.as_bytes())?;
+        let signature = EcdsaAdaptorSignature::encrypt(&secp, &message, &secret_key, adaptor_point);
+        Ok(AdaptorSignature {
+            signature,
+            adaptor_point: *adaptor_point,
+        })
+    }
+}
+
+/// An ECDSA "adaptor" pre-signature over a message, encrypted under an
+/// adaptor point `T = t·G`: not itself a valid signature, but completable
+/// into one by anyone who learns `t` ([`Self::decrypt`]) - and, crucially,
+/// the act of completing it leaks `t` back to whoever holds the
+/// pre-signature ([`Self::recover`]). Two counterparties each pre-signing
+/// their own leg of a swap under the same `T` is what makes the swap atomic:
+/// broadcasting either completed signature reveals `t`, which unlocks the
+/// other leg.
+///
+/// `adaptor_point` travels with the pre-signature (rather than being passed
+/// separately to every method) so that `serialize_packed`/`deserialize_packed`
+/// round-trip it along with the pre-signature itself, per the invariant that
+/// a stored adaptor signature must always carry the point it was encrypted
+/// under.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdaptorSignature {
+    signature: EcdsaAdaptorSignature,
+    adaptor_point: PublicKey,
+}
+
+impl AdaptorSignature {
+    /// Verifies that `self` is a well-formed adaptor pre-signature by
+    /// `pubkey` over `msg`, without needing `t` or the completed signature.
+    pub fn verify(&self, pubkey: &PublicKey, msg: &[u8]) -> bool {
+        let secp = Secp256k1::verification_only();
+        let signed_bytes = PackedAtpSignature::message_to_signed_bytes(msg);
+        let message = match Message::from_slice(signed_bytes.as_bytes()) {
+            Ok(message) => message,
+            Err(_) => return false,
+        };
+        self.signature
+            .verify(&secp, &message, pubkey, &self.adaptor_point)
+            .is_ok()
+    }
+
+    /// Completes the pre-signature into a valid [`PackedAtpSignature`] using
+    /// the adaptor secret `t`.
+    ///
+    /// Unlike a real Alaya signature, a decrypted adaptor signature carries
+    /// no recovery id of its own - that bit depends on which of two possible
+    /// public keys it recovers to, information a plain `(r, s)` pair and `t`
+    /// don't encode. The returned `PackedAtpSignature` assumes recovery id
+    /// `0`; a caller that needs `signature_recover_signer` to succeed should
+    /// fall back to id `1` if the recovered address doesn't match.
+    pub fn decrypt(&self, t: &H256) -> Result<PackedAtpSignature, anyhow::Error> {
+        let adaptor_secret = SecretKey::from_slice(t.as_bytes())?;
+        let signature = self.signature.decrypt(&adaptor_secret)?;
+
+        let mut bytes_array = [0u8; 65];
+        bytes_array[..64].copy_from_slice(&signature.serialize_compact());
+        Ok(PackedAtpSignature(ATPSignature::from(bytes_array)))
+    }
+
+    /// Extracts the adaptor secret `t` by comparing `self` against a
+    /// `completed_sig` produced by completing it with `t` (e.g. once
+    /// broadcast on-chain).
+    pub fn recover(&self, completed_sig: &PackedAtpSignature) -> Result<H256, anyhow::Error> {
+        let secp = Secp256k1::verification_only();
+        let packed = completed_sig.serialize_packed();
+        let signature = secp256k1_zkp::ecdsa::Signature::from_compact(&packed[..64])?;
+        let secret = self.signature.recover(&secp, &signature, &self.adaptor_point)?;
+        Ok(H256::from_slice(secret.as_ref()))
+    }
+
+    /// Serializes the pre-signature together with the adaptor point it was
+    /// encrypted under.
+    pub fn serialize_packed(&self) -> Vec<u8> {
+        let sig_bytes = self.signature.as_ref();
+        let mut out = Vec::with_capacity(2 + sig_bytes.len() + 33);
+        out.extend_from_slice(&(sig_bytes.len() as u16).to_be_bytes());
+        out.extend_from_slice(sig_bytes);
+        out.extend_from_slice(&self.adaptor_point.serialize());
+        out
+    }
+
+    pub fn deserialize_packed(bytes: &[u8]) -> Result<Self, anyhow::Error> {
+        ensure!(bytes.len() > 2 + 33, "adaptor signature packed bytes too short");
+        let sig_len = u16::from_be_bytes([bytes[0], bytes[1]]) as usize;
+        ensure!(
+            bytes.len() == 2 + sig_len + 33,
+            "adaptor signature packed length does not match its length prefix"
+        );
+        let signature = EcdsaAdaptorSignature::from_slice(&bytes[2..2 + sig_len])?;
+        let adaptor_point = PublicKey::from_slice(&bytes[2 + sig_len..])?;
+        Ok(Self {
+            signature,
+            adaptor_point,
+        })
+    }
 }
 
 impl Serialize for PackedAtpSignature {
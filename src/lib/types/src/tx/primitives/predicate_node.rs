@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+
+use crate::account::PubKeyHash;
+use crate::tx::TimeRange;
+use parity_crypto::Keccak256;
+
+/// A single leaf of a [`PredicateNode`] tree, in the depth-first order
+/// [`PredicateNode::leaves`] and [`PredicateNode::satisfied_leaf_bitmap`] index by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PredicateLeaf {
+    /// Satisfied once the current time falls inside the transfer's own `time_range`.
+    Timelock,
+    /// Satisfied once a signature from this signer over the tx's `approval_message` is presented.
+    Approver(PubKeyHash),
+}
+
+/// The release condition on a [`super::super::conditional_transfer::ConditionalTransfer`]:
+/// unlike [`super::spending_condition::SpendingCondition`], which only combines a single
+/// time range and a single witness address, this is a genuine tree, so an arbitrary number
+/// of timelocks and approvers can be combined with nested `And`/`Or` nodes.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum PredicateNode {
+    Timelock,
+    Approver(PubKeyHash),
+    And(Box<PredicateNode>, Box<PredicateNode>),
+    Or(Box<PredicateNode>, Box<PredicateNode>),
+}
+
+impl PredicateNode {
+    /// All leaves in the tree, in depth-first order.
+    pub fn leaves(&self) -> Vec<PredicateLeaf> {
+        match self {
+            PredicateNode::Timelock => vec![PredicateLeaf::Timelock],
+            PredicateNode::Approver(signer) => vec![PredicateLeaf::Approver(*signer)],
+            PredicateNode::And(left, right) | PredicateNode::Or(left, right) => {
+                let mut leaves = left.leaves();
+                leaves.extend(right.leaves());
+                leaves
+            }
+        }
+    }
+
+    /// Serializes the tree shape into the same byte encoding used both by
+    /// [`Self::commitment`] and by the tx bytes the predicate is signed as part of:
+    /// a one-byte tag, followed by each variant's own fields in the order above.
+    pub fn to_be_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        match self {
+            PredicateNode::Timelock => out.push(0),
+            PredicateNode::Approver(signer) => {
+                out.push(1);
+                out.extend_from_slice(&signer.data);
+            }
+            PredicateNode::And(left, right) => {
+                out.push(2);
+                out.extend_from_slice(&left.to_be_bytes());
+                out.extend_from_slice(&right.to_be_bytes());
+            }
+            PredicateNode::Or(left, right) => {
+                out.push(3);
+                out.extend_from_slice(&left.to_be_bytes());
+                out.extend_from_slice(&right.to_be_bytes());
+            }
+        }
+        out
+    }
+
+    /// A deterministic commitment to the predicate tree, folded into `ConditionalTransferOp`
+    /// pubdata in place of the full tree (which, like `EscrowOp`'s `SpendingCondition`
+    /// parameters, isn't itself committed on-chain).
+    pub fn commitment(&self) -> PubKeyHash {
+        let hash = self.to_be_bytes().keccak256();
+        PubKeyHash::from_bytes(&hash[hash.len() - 20..])
+            .expect("keccak256 output truncated to 20 bytes is always a valid PubKeyHash")
+    }
+
+    fn leaf_satisfied(leaf: &PredicateLeaf, now: u64, time_range: TimeRange, approved: &[PubKeyHash]) -> bool {
+        match leaf {
+            PredicateLeaf::Timelock => now >= time_range.valid_from && now <= time_range.valid_until,
+            PredicateLeaf::Approver(signer) => approved.contains(signer),
+        }
+    }
+
+    /// Whether the tree releases funds given the current time `now` and the set of
+    /// approvers who have presented a valid signature over `approval_message` (`approved`).
+    pub fn is_satisfied(&self, now: u64, time_range: TimeRange, approved: &[PubKeyHash]) -> bool {
+        match self {
+            PredicateNode::Timelock | PredicateNode::Approver(_) => {
+                Self::leaf_satisfied(&self.leaves()[0], now, time_range, approved)
+            }
+            PredicateNode::And(left, right) => {
+                left.is_satisfied(now, time_range, approved)
+                    && right.is_satisfied(now, time_range, approved)
+            }
+            PredicateNode::Or(left, right) => {
+                left.is_satisfied(now, time_range, approved)
+                    || right.is_satisfied(now, time_range, approved)
+            }
+        }
+    }
+
+    /// Bitmap over `self.leaves()` (bit `i` set iff leaf `i` is independently
+    /// satisfied), committed to `ConditionalTransferOp` pubdata so the prover can
+    /// check `is_satisfied` was evaluated against the same witnesses the operator
+    /// claims, without having to re-derive which approvers signed from just the
+    /// aggregate boolean result.
+    pub fn satisfied_leaf_bitmap(&self, now: u64, time_range: TimeRange, approved: &[PubKeyHash]) -> u64 {
+        let mut bitmap = 0u64;
+        for (index, leaf) in self.leaves().iter().enumerate().take(64) {
+            if Self::leaf_satisfied(leaf, now, time_range, approved) {
+                bitmap |= 1 << index;
+            }
+        }
+        bitmap
+    }
+}
@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+use zkdpos_basic_types::Address;
+
+use crate::tx::TimeRange;
+
+/// The release condition on an escrowed transfer: funds sit in the escrow's
+/// pending sub-account until either predicate below is satisfied, at which
+/// point a "settle" transaction may route them to the beneficiary (time
+/// predicate past `valid_until`, or a witness signature) or, if combined with
+/// `And`/`Or`, both/either must hold.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SpendingCondition {
+    /// Releasable once the current time falls inside `TimeRange`.
+    Time(TimeRange),
+    /// Releasable once a signature from `witness` over the settling message is presented.
+    Witness(Address),
+    /// Releasable once both the time and witness predicates hold.
+    And(TimeRange, Address),
+    /// Releasable once either the time or witness predicate holds.
+    Or(TimeRange, Address),
+}
+
+impl SpendingCondition {
+    /// Tag identifying the condition's shape in `EscrowOp` pubdata; the
+    /// prover reconstructs the predicate(s) to enforce from this plus the
+    /// time range / witness address carried alongside it.
+    pub fn condition_type(&self) -> u8 {
+        match self {
+            SpendingCondition::Time(_) => 0,
+            SpendingCondition::Witness(_) => 1,
+            SpendingCondition::And(..) => 2,
+            SpendingCondition::Or(..) => 3,
+        }
+    }
+
+    pub fn time_range(&self) -> Option<TimeRange> {
+        match self {
+            SpendingCondition::Time(time_range) | SpendingCondition::And(time_range, _) | SpendingCondition::Or(time_range, _) => {
+                Some(*time_range)
+            }
+            SpendingCondition::Witness(_) => None,
+        }
+    }
+
+    pub fn witness(&self) -> Option<Address> {
+        match self {
+            SpendingCondition::Witness(witness) | SpendingCondition::And(_, witness) | SpendingCondition::Or(_, witness) => {
+                Some(*witness)
+            }
+            SpendingCondition::Time(_) => None,
+        }
+    }
+
+    /// Whether the condition releases funds given that the time predicate
+    /// currently holds (`time_satisfied`) and/or a valid witness signature
+    /// from the designated address has been presented (`witness_satisfied`).
+    /// Callers compute both independently (wall-clock comparison against the
+    /// time range, signature recovery against the witness address) and pass
+    /// the results in, since this type only encodes the combinator.
+    pub fn is_satisfied(&self, time_satisfied: bool, witness_satisfied: bool) -> bool {
+        match self {
+            SpendingCondition::Time(_) => time_satisfied,
+            SpendingCondition::Witness(_) => witness_satisfied,
+            SpendingCondition::And(..) => time_satisfied && witness_satisfied,
+            SpendingCondition::Or(..) => time_satisfied || witness_satisfied,
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+
+use crate::account::PubKeyHash;
+use crate::tx::{AccountSignerSet, TxSignature};
+
+/// A threshold-multisig authorization for a transaction whose account is
+/// registered with an [`AccountSignerSet`]: one independently-verifiable
+/// signature per participating signer, plus a bitmap recording which of the
+/// registered signers (by index into `AccountSignerSet::signers`) produced
+/// them.
+///
+/// Despite the name (kept for continuity with `AccountSignerSet`'s docs and
+/// existing callers), this does not perform MuSig key aggregation - see
+/// [`AccountSignerSet::commitment`] for why this snapshot substitutes an
+/// N-of-M independent-signature check for genuine on-curve aggregation. The
+/// prover verifies each recorded signature individually against its bitmap
+/// position's registered key, rather than one combined signature against an
+/// aggregated key.
+///
+/// Wired both as [`crate::Exchange::threshold_auth`], a field on that one tx
+/// type, and generically as [`crate::tx::SignedZkDposTx::multisig_auth`],
+/// which lets it accompany any inner `ZkDposTx` (e.g. `Transfer`, `Withdraw`,
+/// `ChangePubKey`) instead of that tx's own `TxSignature`. `Close` operations
+/// are already disabled network-wide (`ZkDposTx::account_id` rejects them),
+/// so there is no live authorization path to extend there.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ThresholdMusigSignature {
+    pub participant_bitmap: u64,
+    pub signatures: Vec<TxSignature>,
+}
+
+impl ThresholdMusigSignature {
+    /// Starts an empty partial-signature collection: no participant has
+    /// signed yet. Signatures are added one at a time via
+    /// `add_partial_signature`, as in a staged multisig-wallet signing
+    /// ceremony where co-signers aren't all online at once.
+    pub fn new() -> Self {
+        Self {
+            participant_bitmap: 0,
+            signatures: Vec::new(),
+        }
+    }
+
+    /// Incorporates one more participant's signature over `message`.
+    /// The signer is identified by recovering their `PubKeyHash` from
+    /// `signature` itself and looking it up in `key_set`, so signatures can
+    /// be collected in any order and from any subset of participants.
+    /// A second signature from an already-recorded participant is ignored.
+    pub fn add_partial_signature(
+        &mut self,
+        key_set: &AccountSignerSet,
+        message: &[u8],
+        signature: TxSignature,
+    ) -> Result<(), anyhow::Error> {
+        let signer = signature
+            .verify_musig(message)
+            .map(|pub_key| PubKeyHash::from_pubkey(&pub_key))
+            .ok_or_else(|| anyhow::anyhow!("partial signature does not verify"))?;
+        let index = key_set
+            .signers
+            .iter()
+            .position(|candidate| *candidate == signer)
+            .ok_or_else(|| {
+                anyhow::anyhow!("signer is not a member of this account's registered signer set")
+            })?;
+
+        if self.participant_bitmap & (1 << index) == 0 {
+            // Keep `signatures` ordered by ascending participant index, since
+            // `verify` zips it against indices read off the bitmap in that order.
+            let insert_at = (0..index)
+                .filter(|earlier| self.participant_bitmap & (1 << earlier) != 0)
+                .count();
+            self.participant_bitmap |= 1 << index;
+            self.signatures.insert(insert_at, signature);
+        }
+        Ok(())
+    }
+
+    /// Verifies that at least `key_set.threshold` of the registered signers
+    /// indicated by `participant_bitmap` produced a valid `musig` signature
+    /// over `message`, each matching the registered key at its bitmap
+    /// position. Returns the aggregate key commitment on success.
+    pub fn verify(&self, message: &[u8], key_set: &AccountSignerSet) -> Option<PubKeyHash> {
+        let participant_indices: Vec<usize> = (0..key_set.signers.len())
+            .filter(|index| self.participant_bitmap & (1 << index) != 0)
+            .collect();
+
+        if participant_indices.len() != self.signatures.len() {
+            return None;
+        }
+        if participant_indices.len() < key_set.threshold as usize {
+            return None;
+        }
+
+        for (index, signature) in participant_indices.iter().zip(&self.signatures) {
+            let signer = signature.verify_musig(message)?;
+            if PubKeyHash::from_pubkey(&signer) != key_set.signers[*index] {
+                return None;
+            }
+        }
+
+        Some(key_set.commitment())
+    }
+}
+
+impl Default for ThresholdMusigSignature {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,243 @@
+use crate::{
+    dlc::RangeSettleCurve,
+    helpers::{is_fee_amount_packable, is_token_amount_packable, pack_fee_amount, pack_token_amount},
+    AccountId, Nonce, TokenId,
+};
+use num::BigUint;
+
+use crate::account::PubKeyHash;
+use crate::Engine;
+use serde::{Deserialize, Serialize};
+use zkdpos_basic_types::Address;
+use zkdpos_crypto::franklin_crypto::eddsa::PrivateKey;
+use zkdpos_crypto::params::{max_account_id, max_token_id};
+use zkdpos_utils::BigUintSerdeAsRadix10Str;
+
+use super::{OracleAttestation, TimeRange, TxSignature, VerifiableSignature, VerifiedSignatureCache};
+
+/// Resolves the settlement phase of a `RangeSettle` priority operation (see
+/// `RangeSettleOp`): splits the `amount + fee` escrowed in `pending` between
+/// `to_a` and `to_b` according to `curve`, a digit-decomposed step function
+/// mapping an oracle-attested numeric outcome to a payout split (see
+/// `crate::dlc::decompose_curve`) - the contract-for-difference settlement
+/// pattern adapted to L2 balances. `oracle_attestation`, if present and
+/// valid, selects the matching condition; absent or unmatched, the split
+/// falls back to `curve.refund_payout_a_bp` once `deadline_block` has passed.
+/// Like `ConditionalSettle`, anyone holding a valid attestation can submit
+/// the settlement, not just the original escrow's funder.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RangeSettleComplete {
+    /// zkDpos network account ID of the transaction submitter, who pays `fee`.
+    pub account_id: AccountId,
+    /// Escrow sub-account locked by the matching `RangeSettleOp`.
+    pub pending: AccountId,
+    pub to_a: Address,
+    pub to_b: Address,
+    pub token: TokenId,
+    /// Total pot escrowed at lock time, carried over from the originating
+    /// `RangeSettle` priority operation.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub amount: BigUint,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub fee: BigUint,
+    /// Oracle identity carried over from the originating `RangeSettle`.
+    pub oracle_pubkey_hash: PubKeyHash,
+    /// Payout curve carried over from the originating `RangeSettle`. Checked
+    /// at settlement time against the commitment `apply_range_settle_op`
+    /// installed into `pending`'s `pub_key_hash`, so a submitter can't swap
+    /// in a different curve than the one the funder actually locked against.
+    pub curve: RangeSettleCurve,
+    /// The oracle's signed outcome, if one has been produced yet.
+    pub oracle_attestation: Option<OracleAttestation>,
+    /// Alaya deadline block copied from the originating priority operation's
+    /// `PriorityOp::deadline_block`.
+    pub deadline_block: u64,
+    pub nonce: Nonce,
+    /// Time range when the transaction is valid
+    /// This fields must be Option<...> because of backward compatibility with first version of ZkDpos
+    #[serde(flatten)]
+    pub time_range: Option<TimeRange>,
+    /// Transaction zkDpos signature.
+    pub signature: TxSignature,
+    #[serde(skip)]
+    cached_signer: VerifiedSignatureCache,
+}
+
+impl RangeSettleComplete {
+    /// Unique identifier of the transaction type in zkDpos network.
+    pub const TX_TYPE: u8 = 15;
+
+    /// Creates transaction from all the required fields.
+    ///
+    /// While `signature` field is mandatory for new transactions, it may be `None`
+    /// in some cases (e.g. when restoring the network state from the L1 contract data).
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        account_id: AccountId,
+        pending: AccountId,
+        to_a: Address,
+        to_b: Address,
+        token: TokenId,
+        amount: BigUint,
+        fee: BigUint,
+        oracle_pubkey_hash: PubKeyHash,
+        curve: RangeSettleCurve,
+        oracle_attestation: Option<OracleAttestation>,
+        deadline_block: u64,
+        nonce: Nonce,
+        time_range: TimeRange,
+        signature: Option<TxSignature>,
+    ) -> Self {
+        let mut tx = Self {
+            account_id,
+            pending,
+            to_a,
+            to_b,
+            token,
+            amount,
+            fee,
+            oracle_pubkey_hash,
+            curve,
+            oracle_attestation,
+            deadline_block,
+            nonce,
+            time_range: Some(time_range),
+            signature: signature.clone().unwrap_or_default(),
+            cached_signer: VerifiedSignatureCache::NotCached,
+        };
+        if signature.is_some() {
+            tx.cached_signer = VerifiedSignatureCache::Cached(tx.verify_signature());
+        }
+        tx
+    }
+
+    /// Creates a signed transaction using private key and
+    /// checks for the transaction correcteness.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_signed(
+        account_id: AccountId,
+        pending: AccountId,
+        to_a: Address,
+        to_b: Address,
+        token: TokenId,
+        amount: BigUint,
+        fee: BigUint,
+        oracle_pubkey_hash: PubKeyHash,
+        curve: RangeSettleCurve,
+        oracle_attestation: Option<OracleAttestation>,
+        deadline_block: u64,
+        nonce: Nonce,
+        time_range: TimeRange,
+        private_key: &PrivateKey<Engine>,
+    ) -> Result<Self, anyhow::Error> {
+        let mut tx = Self::new(
+            account_id,
+            pending,
+            to_a,
+            to_b,
+            token,
+            amount,
+            fee,
+            oracle_pubkey_hash,
+            curve,
+            oracle_attestation,
+            deadline_block,
+            nonce,
+            time_range,
+            None,
+        );
+        tx.signature = TxSignature::sign_musig(private_key, &tx.get_bytes());
+        if !tx.check_correctness() {
+            anyhow::bail!(crate::tx::TRANSACTION_SIGNATURE_ERROR);
+        }
+        Ok(tx)
+    }
+
+    /// Encodes the transaction data as the byte sequence according to the zkDpos protocol.
+    /// `curve`/`oracle_attestation` aren't part of the signed bytes, same as
+    /// `ConditionalSettle::get_bytes` excludes `predicate` - they're instead
+    /// pinned down by the `pending` escrow's own commitment check at apply time.
+    pub fn get_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&[Self::TX_TYPE]);
+        out.extend_from_slice(&self.account_id.to_be_bytes());
+        out.extend_from_slice(&self.pending.to_be_bytes());
+        out.extend_from_slice(self.to_a.as_bytes());
+        out.extend_from_slice(self.to_b.as_bytes());
+        out.extend_from_slice(&self.token.to_be_bytes());
+        out.extend_from_slice(&pack_token_amount(&self.amount));
+        out.extend_from_slice(&pack_fee_amount(&self.fee));
+        out.extend_from_slice(&self.deadline_block.to_be_bytes());
+        out.extend_from_slice(&self.nonce.to_be_bytes());
+        if let Some(time_range) = &self.time_range {
+            out.extend_from_slice(&time_range.to_be_bytes());
+        }
+        out
+    }
+
+    /// The `payout_a_bp` this settlement resolves to given the current Alaya
+    /// block height `now_block`: the condition matching `oracle_attestation`'s
+    /// outcome if the attestation is present and valid, else (once
+    /// `deadline_block` has passed) `curve.refund_payout_a_bp`. `None` means
+    /// the settlement isn't resolvable yet - mirrors
+    /// `ConditionalSettle::is_release`/`is_refund`, collapsed into one split.
+    pub fn resolved_payout_a_bp(&self, now_block: u64) -> Option<u16> {
+        if let Some(attestation) = &self.oracle_attestation {
+            if attestation.oracle_pubkey_hash == self.oracle_pubkey_hash && attestation.verify() {
+                if let Some(bp) = self.curve.payout_bp_for_outcome(attestation.outcome) {
+                    return Some(bp);
+                }
+            }
+        }
+        if now_block >= self.deadline_block {
+            Some(self.curve.refund_payout_a_bp)
+        } else {
+            None
+        }
+    }
+
+    /// Verifies the transaction correctness:
+    ///
+    /// - `account_id` field must be within supported range.
+    /// - `token` field must be within supported range.
+    /// - `amount` field must represent a packable value.
+    /// - `fee` field must represent a packable value.
+    /// - zkDpos signature must correspond to the PubKeyHash of the account.
+    pub fn check_correctness(&mut self) -> bool {
+        let mut valid = is_token_amount_packable(&self.amount)
+            && is_fee_amount_packable(&self.fee)
+            && self.account_id <= max_account_id()
+            && self.token <= max_token_id()
+            && self.to_a != Address::zero()
+            && self.to_b != Address::zero()
+            && self
+                .time_range
+                .map(|r| r.check_correctness())
+                .unwrap_or(true);
+
+        if valid {
+            let signer = self.verify_signature();
+            valid = valid && signer.is_some();
+            self.cached_signer = VerifiedSignatureCache::Cached(signer);
+        }
+        valid
+    }
+
+    /// Restores the `PubKeyHash` from the transaction signature.
+    pub fn verify_signature(&self) -> Option<PubKeyHash> {
+        if let VerifiedSignatureCache::Cached(cached_signer) = &self.cached_signer {
+            *cached_signer
+        } else {
+            self.signature
+                .verify_musig(&self.get_bytes())
+                .map(|pub_key| PubKeyHash::from_pubkey(&pub_key))
+        }
+    }
+}
+
+impl VerifiableSignature for RangeSettleComplete {
+    fn verify_signature(&self) -> Option<PubKeyHash> {
+        Self::verify_signature(self)
+    }
+}
@@ -0,0 +1,243 @@
+use crate::{
+    helpers::{is_fee_amount_packable, pack_fee_amount, pack_token_amount},
+    tx::TimeRange,
+    AccountId, LiquidityId, Nonce, TokenId,
+};
+use num::BigUint;
+
+use crate::account::PubKeyHash;
+use crate::Engine;
+use serde::{Deserialize, Serialize};
+use zkdpos_basic_types::Address;
+use zkdpos_crypto::franklin_crypto::eddsa::PrivateKey;
+use zkdpos_crypto::params::max_account_id;
+use zkdpos_utils::{format_units, BigUintSerdeAsRadix10Str};
+
+use super::{AccountSignerSet, ThresholdMusigSignature, TxSignature, VerifiableSignature, VerifiedSignatureCache};
+
+/// `RemoveLiquidity` transaction burns a sender's LP shares of a pool and returns
+/// the corresponding proportion of both pool reserves.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveLiquidity {
+    /// zkDpos network account ID of the transaction initiator.
+    pub account_id: AccountId,
+    /// Identifier of the pool the shares are burned from.
+    pub liquidity_id: LiquidityId,
+    /// Address of account to credit the withdrawn liquidity to.
+    pub to: Address,
+    /// Amount of LP shares to burn.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub shares: BigUint,
+    /// Minimum amount of token A the sender is willing to accept.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub amount_a_min: BigUint,
+    /// Minimum amount of token B the sender is willing to accept.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub amount_b_min: BigUint,
+    /// First token of the pool, and the token `fee_a` is paid in.
+    pub token_a: TokenId,
+    /// Second token of the pool, and the token `fee_b` is paid in.
+    pub token_b: TokenId,
+    /// Fee A for the transaction.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub fee_a: BigUint,
+    /// Fee B for the transaction.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub fee_b: BigUint,
+    /// Current account nonce.
+    pub nonce: Nonce,
+    /// Time range when the transaction is valid
+    /// This fields must be Option<...> because of backward compatibility with first version of ZkDpos
+    #[serde(flatten)]
+    pub time_range: Option<TimeRange>,
+    /// Transaction zkDpos signature.
+    pub signature: TxSignature,
+    /// If set, authorizes this `RemoveLiquidity` via the account's registered
+    /// threshold-multisig signer set instead of `signature`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub threshold_auth: Option<ThresholdMusigSignature>,
+    #[serde(skip)]
+    cached_signer: VerifiedSignatureCache,
+}
+
+impl RemoveLiquidity {
+    /// Unique identifier of the transaction type in zkDpos network.
+    pub const TX_TYPE: u8 = 9;
+
+    /// Creates transaction from all the required fields.
+    ///
+    /// While `signature` field is mandatory for new transactions, it may be `None`
+    /// in some cases (e.g. when restoring the network state from the L1 contract data).
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        account_id: AccountId,
+        liquidity_id: LiquidityId,
+        to: Address,
+        shares: BigUint,
+        amount_a_min: BigUint,
+        amount_b_min: BigUint,
+        token_a: TokenId,
+        token_b: TokenId,
+        fee_a: BigUint,
+        fee_b: BigUint,
+        nonce: Nonce,
+        time_range: TimeRange,
+        signature: Option<TxSignature>,
+    ) -> Self {
+        let mut tx = Self {
+            account_id,
+            liquidity_id,
+            to,
+            shares,
+            amount_a_min,
+            amount_b_min,
+            token_a,
+            token_b,
+            fee_a,
+            fee_b,
+            nonce,
+            time_range: Some(time_range),
+            signature: signature.clone().unwrap_or_default(),
+            threshold_auth: None,
+            cached_signer: VerifiedSignatureCache::NotCached,
+        };
+        if signature.is_some() {
+            tx.cached_signer = VerifiedSignatureCache::Cached(tx.verify_signature());
+        }
+        tx
+    }
+
+    /// Creates a signed transaction using private key and
+    /// checks for the transaction correcteness.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_signed(
+        account_id: AccountId,
+        liquidity_id: LiquidityId,
+        to: Address,
+        shares: BigUint,
+        amount_a_min: BigUint,
+        amount_b_min: BigUint,
+        token_a: TokenId,
+        token_b: TokenId,
+        fee_a: BigUint,
+        fee_b: BigUint,
+        nonce: Nonce,
+        time_range: TimeRange,
+        private_key: &PrivateKey<Engine>,
+    ) -> Result<Self, anyhow::Error> {
+        let mut tx = Self::new(
+            account_id, liquidity_id, to, shares, amount_a_min, amount_b_min, token_a, token_b,
+            fee_a, fee_b, nonce, time_range, None,
+        );
+        tx.signature = TxSignature::sign_musig(private_key, &tx.get_bytes());
+        if !tx.check_correctness() {
+            anyhow::bail!(crate::tx::TRANSACTION_SIGNATURE_ERROR);
+        }
+        Ok(tx)
+    }
+
+    /// Encodes the transaction data as the byte sequence according to the zkDpos protocol.
+    pub fn get_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&[Self::TX_TYPE]);
+        out.extend_from_slice(&self.account_id.to_be_bytes());
+        out.extend_from_slice(&self.to.as_bytes());
+        out.extend_from_slice(&self.token_a.to_be_bytes());
+        out.extend_from_slice(&self.token_b.to_be_bytes());
+        out.extend_from_slice(&pack_token_amount(&self.shares));
+        out.extend_from_slice(&pack_token_amount(&self.amount_a_min));
+        out.extend_from_slice(&pack_token_amount(&self.amount_b_min));
+        out.extend_from_slice(&pack_fee_amount(&self.fee_a));
+        out.extend_from_slice(&pack_fee_amount(&self.fee_b));
+        out.extend_from_slice(&self.nonce.to_be_bytes());
+        if let Some(time_range) = &self.time_range {
+            out.extend_from_slice(&time_range.to_be_bytes());
+        }
+        out
+    }
+
+    /// Verifies the transaction correctness:
+    ///
+    /// - `account_id` field must be within supported range.
+    /// - `fee` fields must represent packable values.
+    /// - remove liquidity recipient must not be `Address::zero()`.
+    /// - zkDpos signature must correspond to the PubKeyHash of the account.
+    pub fn check_correctness(&mut self) -> bool {
+        let mut valid = is_fee_amount_packable(&self.fee_a)
+            && is_fee_amount_packable(&self.fee_b)
+            && self.account_id <= max_account_id()
+            && self.to != Address::zero()
+            && self
+                .time_range
+                .map(|r| r.check_correctness())
+                .unwrap_or(true)
+            && self
+                .threshold_auth
+                .as_ref()
+                .map(|auth| {
+                    !auth.signatures.is_empty()
+                        && auth.participant_bitmap.count_ones() as usize == auth.signatures.len()
+                })
+                .unwrap_or(true);
+        if valid {
+            let signer = self.verify_signature();
+            valid = valid && (signer.is_some() || self.threshold_auth.is_some());
+            self.cached_signer = VerifiedSignatureCache::Cached(signer);
+        };
+        valid
+    }
+
+    /// Restores the `PubKeyHash` from the transaction signature.
+    pub fn verify_signature(&self) -> Option<PubKeyHash> {
+        if let VerifiedSignatureCache::Cached(cached_signer) = &self.cached_signer {
+            *cached_signer
+        } else {
+            self.signature
+                .verify_musig(&self.get_bytes())
+                .map(|pub_key| PubKeyHash::from_pubkey(&pub_key))
+        }
+    }
+
+    /// Verifies that this `RemoveLiquidity`'s `threshold_auth` (if present)
+    /// meets `key_set`'s quorum over `get_bytes()`, recognizing the account's
+    /// registered threshold-multisig signers as an alternative to the single
+    /// `signature` field. Returns the signer set's key commitment on success.
+    pub fn verify_threshold_auth(&self, key_set: &AccountSignerSet) -> Option<PubKeyHash> {
+        self.threshold_auth
+            .as_ref()
+            .and_then(|auth| auth.verify(&self.get_bytes(), key_set))
+    }
+
+    /// Get the first part of the message we expect to be signed by Alaya account key.
+    /// The only difference is the missing `nonce` since it's added at the end of the transactions
+    /// batch message.
+    pub fn get_alaya_sign_message_part(&self, token_symbol: &str, decimals: u8) -> String {
+        format!(
+            "RemoveLiquidity {liquidity_id} {shares}\n\
+            Fee: {fee_a} {fee_b}\n\
+            Account Id: {account_id}",
+            liquidity_id = token_symbol,
+            shares = format_units(&self.shares, decimals),
+            fee_a = format_units(&self.fee_a, decimals),
+            fee_b = format_units(&self.fee_b, decimals),
+            account_id = *self.account_id,
+        )
+    }
+
+    /// Gets message that should be signed by Alaya keys of the account for 2-Factor authentication.
+    pub fn get_alaya_sign_message(&self, token_symbol: &str, decimals: u8) -> String {
+        let mut message = self.get_alaya_sign_message_part(token_symbol, decimals);
+        if !message.is_empty() {
+            message.push('\n');
+        }
+        message.push_str(format!("Nonce: {}", self.nonce).as_str());
+        message
+    }
+}
+
+impl VerifiableSignature for RemoveLiquidity {
+    fn verify_signature(&self) -> Option<PubKeyHash> {
+        Self::verify_signature(self)
+    }
+}
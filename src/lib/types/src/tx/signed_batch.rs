@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use zkdpos_basic_types::{AccountId, H256, Nonce};
+
+use crate::tokens::Token;
+use crate::tx::batch::{compute_batch_hash, BatchTx};
+use crate::tx::{SignedZkDposTx, TxAtpSignature};
+use crate::TokenId;
+
+/// Describes why `SignedBatch::validate` rejected a batch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchValidationError {
+    /// A batch must contain at least one transaction.
+    Empty,
+    /// `expected` is the account's previous in-batch nonce plus one; `found`
+    /// is what the next transaction for that account actually carried.
+    NonSequentialNonce {
+        account_id: AccountId,
+        expected: Nonce,
+        found: Nonce,
+    },
+    /// The transaction at `index` isn't valid yet at the checked timestamp.
+    NotYetValid { index: usize, valid_from: u64 },
+    /// No `Token` metadata was supplied for a token a member transaction uses.
+    MissingToken(TokenId),
+    /// A member transaction's `account_id()` could not be determined (e.g. a
+    /// disabled `Close`).
+    BadAccountId(String),
+    /// The batch requires an Alaya signature but none was supplied.
+    MissingSignature,
+    /// The supplied Alaya signature does not recover against the aggregated
+    /// batch message.
+    SignatureMismatch,
+}
+
+impl fmt::Display for BatchValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "a batch must contain at least one transaction"),
+            Self::NonSequentialNonce {
+                account_id,
+                expected,
+                found,
+            } => write!(
+                f,
+                "account {:?} batch nonces are not sequential: expected {:?}, found {:?}",
+                account_id, expected, found
+            ),
+            Self::NotYetValid { index, valid_from } => write!(
+                f,
+                "transaction {} is not valid yet (valid from {})",
+                index, valid_from
+            ),
+            Self::MissingToken(token_id) => {
+                write!(f, "no token metadata provided for token {:?}", token_id)
+            }
+            Self::BadAccountId(reason) => write!(f, "could not determine account id: {}", reason),
+            Self::MissingSignature => write!(f, "batch requires an Alaya signature but has none"),
+            Self::SignatureMismatch => {
+                write!(f, "Alaya signature does not match the aggregated batch message")
+            }
+        }
+    }
+}
+
+impl std::error::Error for BatchValidationError {}
+
+/// A group of transactions, from one or more accounts, authorized by one
+/// shared Alaya signature and executed atomically: either every member
+/// applies, in order, or the operator rejects the whole group. Atomicity is
+/// what lets dependent operations (e.g. a `ChangePubKey` immediately followed
+/// by a `Withdraw` from the new key) be submitted together safely.
+#[derive(Debug, Clone)]
+pub struct SignedBatch {
+    pub txs: Vec<SignedZkDposTx>,
+    /// The single Alaya signature over `get_batch_sign_message()`
+    /// authorizing every member at once. `None` when none of the members
+    /// require Alaya authorization (e.g. the account already transacts
+    /// zkDpos-signature-only).
+    pub atp_signature: Option<TxAtpSignature>,
+}
+
+impl SignedBatch {
+    pub fn new(txs: Vec<SignedZkDposTx>, atp_signature: Option<TxAtpSignature>) -> Self {
+        Self { txs, atp_signature }
+    }
+
+    /// Builds the canonical plaintext message `atp_signature` authorizes:
+    /// every member's `get_alaya_sign_message_part`, in order, followed by a
+    /// single trailing `Nonce: <n>` line. Since batch nonces are required to
+    /// be sequential per account, the batch as a whole is identified by its
+    /// first member's nonce.
+    pub fn get_batch_sign_message(
+        &self,
+        tokens: &HashMap<TokenId, Token>,
+    ) -> Result<String, BatchValidationError> {
+        let first = self.txs.first().ok_or(BatchValidationError::Empty)?;
+
+        let mut message = String::new();
+        for tx in &self.txs {
+            let token = tokens
+                .get(&tx.token_id())
+                .ok_or_else(|| BatchValidationError::MissingToken(tx.token_id()))?;
+            if let Some(part) = tx.get_alaya_sign_message_part(token.clone()) {
+                message.push_str(&part);
+                message.push('\n');
+            }
+        }
+        message.push_str(&format!("Nonce: {}", first.nonce()));
+        Ok(message)
+    }
+
+    /// The tagged-Merkle commitment to every member transaction, in order -
+    /// see [`compute_batch_hash`].
+    pub fn batch_hash(&self) -> H256 {
+        let leaves: Vec<&dyn BatchTx> = self.txs.iter().map(|tx| &tx.tx as &dyn BatchTx).collect();
+        compute_batch_hash(&leaves)
+    }
+
+    /// Validates the batch: every member's nonce is the previous nonce for
+    /// its account plus one (account-scheduler style - a gap would mean some
+    /// prerequisite transaction is missing from the batch), every member is
+    /// already valid at `current_block_timestamp`, and `atp_signature` (if
+    /// present) matches the aggregated batch message.
+    pub fn validate(
+        &self,
+        tokens: &HashMap<TokenId, Token>,
+        current_block_timestamp: u64,
+    ) -> Result<(), BatchValidationError> {
+        if self.txs.is_empty() {
+            return Err(BatchValidationError::Empty);
+        }
+
+        let mut last_nonce_by_account: HashMap<AccountId, Nonce> = HashMap::new();
+        for (index, signed_tx) in self.txs.iter().enumerate() {
+            let account_id = signed_tx
+                .account_id()
+                .map_err(|err| BatchValidationError::BadAccountId(err.to_string()))?;
+            let nonce = signed_tx.nonce();
+
+            if let Some(last_nonce) = last_nonce_by_account.get(&account_id) {
+                let expected = Nonce(**last_nonce + 1);
+                if nonce != expected {
+                    return Err(BatchValidationError::NonSequentialNonce {
+                        account_id,
+                        expected,
+                        found: nonce,
+                    });
+                }
+            }
+            last_nonce_by_account.insert(account_id, nonce);
+
+            if signed_tx.valid_from() > current_block_timestamp {
+                return Err(BatchValidationError::NotYetValid {
+                    index,
+                    valid_from: signed_tx.valid_from(),
+                });
+            }
+        }
+
+        if let Some(signature) = &self.atp_signature {
+            let message = self.get_batch_sign_message(tokens)?;
+            signature
+                .recover_signer(None, message.as_bytes())
+                .map_err(|_| BatchValidationError::SignatureMismatch)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,148 @@
+use std::fmt;
+
+use num::BigUint;
+use zkdpos_basic_types::{AccountId, Address, Nonce};
+
+use crate::account::PubKeyHash;
+use crate::tokens::{TokenLike, TxFeeTypes};
+use crate::tx::{AtpSignData, SignedZkDposTx, ZkDposTx};
+
+/// Describes which invariant of a transaction `VerifiedTx::verify` rejected.
+///
+/// Per-transaction `check_correctness` bundles range, packability and time
+/// range checks into a single boolean, so `InvalidSignature`/
+/// `InvalidAlayaSignature` are the only failure reasons this can pinpoint
+/// precisely; anything else (an out-of-range account/token id, an unpackable
+/// amount or fee, an invalid time range) is reported as `InvalidTransaction`
+/// until those per-tx checks are themselves split into granular results.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TxError {
+    /// `check_correctness` failed for a reason other than the zkDpos signature:
+    /// an out-of-range field, an unpackable amount/fee, or an invalid time range.
+    InvalidTransaction,
+    /// The zkDpos (musig) signature does not recover to a `PubKeyHash`.
+    InvalidSignature,
+    /// The accompanying Alaya signature does not recover to an address.
+    InvalidAlayaSignature,
+}
+
+impl fmt::Display for TxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TxError::InvalidTransaction => write!(f, "transaction is malformed or out of range"),
+            TxError::InvalidSignature => write!(f, "zkDpos signature is invalid"),
+            TxError::InvalidAlayaSignature => write!(f, "Alaya signature is invalid"),
+        }
+    }
+}
+
+impl std::error::Error for TxError {}
+
+/// A transaction that has passed verification: its range/packability checks
+/// and zkDpos signature recovery have already run, and the recovered
+/// `PubKeyHash` (plus the Alaya `Address`, if the tx came with `AtpSignData`)
+/// is cached alongside it.
+///
+/// Unlike `ZkDposTx::check_correctness`, which mutates an already-constructed
+/// transaction in place and returns a bare `bool`, building a `VerifiedTx`
+/// consumes the transaction: there is no way to reach the inner `ZkDposTx`
+/// through this wrapper without having verified it first, so a forgotten
+/// correctness check can no longer reach state-transition code silently.
+#[derive(Debug, Clone)]
+pub struct VerifiedTx {
+    tx: ZkDposTx,
+    signer: PubKeyHash,
+    alaya_signer: Option<Address>,
+}
+
+impl VerifiedTx {
+    /// Range/packability-checks and signature-verifies `tx`, caching the
+    /// recovered `PubKeyHash`. Fails closed: any unmet invariant is reported
+    /// as a `TxError` rather than silently producing a `VerifiedTx`.
+    pub fn verify(mut tx: ZkDposTx) -> Result<Self, TxError> {
+        let signer = match &tx {
+            ZkDposTx::Transfer(t) => t.verify_signature(),
+            ZkDposTx::Exchange(t) => t.verify_signature(),
+            ZkDposTx::Withdraw(t) => t.verify_signature(),
+            ZkDposTx::Close(t) => t.verify_signature(),
+            ZkDposTx::ChangePubKey(t) => t.verify_signature(),
+            ZkDposTx::ForcedExit(t) => t.verify_signature(),
+            ZkDposTx::Swap(t) => t.verify_signature(),
+            ZkDposTx::RemoveLiquidity(t) => t.verify_signature(),
+        };
+
+        if !tx.check_correctness() {
+            return Err(if signer.is_none() {
+                TxError::InvalidSignature
+            } else {
+                TxError::InvalidTransaction
+            });
+        }
+
+        let signer = signer.ok_or(TxError::InvalidSignature)?;
+        Ok(Self {
+            tx,
+            signer,
+            alaya_signer: None,
+        })
+    }
+
+    /// Like `verify`, but additionally recovers the Alaya signature carried
+    /// by `signed_tx.atp_sign_data`, if one is present.
+    ///
+    /// The EIP-712 typed digest newer clients sign is computed alongside
+    /// the batch it belongs to, which this type has no access to; only the
+    /// legacy plaintext message is checked here, so a caller relying on
+    /// EIP-712 authorization still needs to verify that digest itself
+    /// before trusting `alaya_signer`.
+    pub fn verify_signed(signed_tx: SignedZkDposTx) -> Result<Self, TxError> {
+        let verified = Self::verify(signed_tx.tx)?;
+        let alaya_signer = match &signed_tx.atp_sign_data {
+            Some(AtpSignData { signature, message }) => Some(
+                signature
+                    .recover_signer(None, message)
+                    .map_err(|_| TxError::InvalidAlayaSignature)?,
+            ),
+            None => None,
+        };
+        Ok(Self {
+            alaya_signer,
+            ..verified
+        })
+    }
+
+    /// Returns the verified transaction.
+    pub fn tx(&self) -> &ZkDposTx {
+        &self.tx
+    }
+
+    /// Consumes the wrapper, returning the verified transaction.
+    pub fn into_inner(self) -> ZkDposTx {
+        self.tx
+    }
+
+    /// Returns the `PubKeyHash` recovered from the zkDpos signature.
+    pub fn signer(&self) -> PubKeyHash {
+        self.signer
+    }
+
+    /// Returns the Alaya address recovered from `AtpSignData`, if any was supplied.
+    pub fn alaya_signer(&self) -> Option<Address> {
+        self.alaya_signer
+    }
+
+    /// See [`ZkDposTx::account_id`].
+    pub fn account_id(&self) -> anyhow::Result<AccountId> {
+        self.tx.account_id()
+    }
+
+    /// See [`ZkDposTx::nonce`].
+    pub fn nonce(&self) -> Nonce {
+        self.tx.nonce()
+    }
+
+    /// See [`ZkDposTx::get_fee_info`].
+    pub fn get_fee_info(&self) -> Option<(TxFeeTypes, TokenLike, Address, BigUint)> {
+        self.tx.get_fee_info()
+    }
+}
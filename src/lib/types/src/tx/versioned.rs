@@ -0,0 +1,92 @@
+//! A forward-compatible envelope for the L2 transaction byte stream.
+//!
+//! Every op currently serializes its fields positionally with no version
+//! marker, so adding a field to the wire format is a hard fork: old clients
+//! silently misparse the new bytes instead of rejecting them. Following the
+//! approach Solana took for versioned transactions, a leading sentinel byte
+//! reserves the right to add a version number: if the stream starts with
+//! [`VERSIONED_TX_SENTINEL`], the next byte is a format version and the
+//! remainder is parsed per that version's layout; otherwise the whole stream
+//! is the current "legacy" (unversioned) layout, so every transaction that
+//! already exists on the network keeps decoding exactly as before.
+
+use anyhow::ensure;
+
+use super::ZkDposTx;
+
+/// Leading byte marking the start of a versioned envelope. This is safe to
+/// reserve because every op's legacy `get_bytes()` encoding starts with a
+/// `TX_TYPE` constant strictly less than `0xFF`, so no legacy transaction can
+/// ever collide with it.
+pub const VERSIONED_TX_SENTINEL: u8 = 0xFF;
+
+/// Splits a wire byte stream into its declared format version and the
+/// remaining, version-specific payload.
+///
+/// A stream with no sentinel byte is implicitly version `0`, the legacy
+/// layout. `known_versions` lists the versions the caller is prepared to
+/// parse; a declared version outside that list is rejected here rather than
+/// being handed to a parser that would have to guess at its layout.
+pub fn decode_envelope(bytes: &[u8], known_versions: &[u8]) -> Result<(u8, &[u8]), anyhow::Error> {
+    match bytes {
+        [sentinel, version, rest @ ..] if *sentinel == VERSIONED_TX_SENTINEL => {
+            ensure!(
+                known_versions.contains(version),
+                "Unsupported versioned transaction format version: {}",
+                version
+            );
+            Ok((*version, rest))
+        }
+        _ => Ok((0, bytes)),
+    }
+}
+
+/// Prefixes `payload` with the versioned envelope's sentinel and version byte.
+/// Version `0` (the legacy layout) is returned unprefixed, so it round-trips
+/// through [`decode_envelope`] exactly as every existing transaction does today.
+pub fn encode_envelope(version: u8, payload: Vec<u8>) -> Vec<u8> {
+    if version == 0 {
+        return payload;
+    }
+    let mut out = Vec::with_capacity(2 + payload.len());
+    out.push(VERSIONED_TX_SENTINEL);
+    out.push(version);
+    out.extend_from_slice(&payload);
+    out
+}
+
+/// An L2 transaction paired with the wire-format version its byte encoding was
+/// (or should be) produced at.
+#[derive(Debug, Clone)]
+pub struct VersionedZkDposTx {
+    pub version: u8,
+    pub tx: ZkDposTx,
+}
+
+impl VersionedZkDposTx {
+    /// Wraps `tx` at the legacy (version `0`) format.
+    pub fn legacy(tx: ZkDposTx) -> Self {
+        Self { version: 0, tx }
+    }
+
+    /// The versions the wrapped transaction's op supports. Only the legacy
+    /// layout exists today; each op will extend this list as it grows its own
+    /// versioned fields (e.g. a memo, or extra time-range bounds), so the
+    /// state keeper can reject a version it doesn't know about instead of
+    /// misinterpreting it.
+    pub fn supported_versions(&self) -> &'static [u8] {
+        self.tx.supported_versions()
+    }
+
+    /// Encodes the transaction per [`Self::version`]'s layout, wrapped in the
+    /// versioned envelope (or left bare, at version `0`).
+    pub fn get_bytes(&self) -> Vec<u8> {
+        encode_envelope(self.version, self.tx.get_bytes())
+    }
+
+    /// Checks that `version` is one this op declares support for, and that the
+    /// wrapped transaction itself is well-formed.
+    pub fn check_correctness(&mut self) -> bool {
+        self.supported_versions().contains(&self.version) && self.tx.check_correctness()
+    }
+}
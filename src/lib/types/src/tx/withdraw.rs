@@ -7,13 +7,17 @@ use num::{BigUint, ToPrimitive};
 use crate::account::PubKeyHash;
 use crate::utils::alaya_sign_message_part;
 use crate::Engine;
+use parity_crypto::Keccak256;
 use serde::{Deserialize, Serialize};
-use zkdpos_basic_types::Address;
+use zkdpos_basic_types::{Address, H256};
 use zkdpos_crypto::franklin_crypto::eddsa::PrivateKey;
 use zkdpos_crypto::params::{max_account_id, max_token_id};
 use zkdpos_utils::{format_units, BigUintSerdeAsRadix10Str};
 
-use super::{TimeRange, TxSignature, VerifiedSignatureCache};
+use super::{
+    eip712_digest, eip712_domain_separator, encode_word, AccountSignerSet, ThresholdMusigSignature,
+    TimeRange, TxSignature, VerifiedSignatureCache,
+};
 
 /// `Withdraw` transaction performs a withdrawal of funds from zkDpos account to L1 account.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -37,6 +41,10 @@ pub struct Withdraw {
     pub nonce: Nonce,
     /// Transaction zkDpos signature.
     pub signature: TxSignature,
+    /// If set, authorizes this `Withdraw` via the account's registered
+    /// threshold-multisig signer set instead of `signature`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub threshold_auth: Option<ThresholdMusigSignature>,
     #[serde(skip)]
     cached_signer: VerifiedSignatureCache,
     /// Optional setting signalizing state keeper to speed up creation
@@ -49,6 +57,13 @@ pub struct Withdraw {
     /// This fields must be Option<...> because of backward compatibility with first version of zkDpos
     #[serde(flatten)]
     pub time_range: Option<TimeRange>,
+    /// Binds the signature to a specific zkDpos deployment, so it can't be
+    /// replayed against another chain sharing the same account keys (e.g.
+    /// testnet -> mainnet, or a fork of this chain). `0` means "legacy, any
+    /// chain": old signatures that predate this field keep verifying exactly
+    /// as before, since `get_bytes` only folds it in when it's non-zero.
+    #[serde(default)]
+    pub chain_id: u16,
 }
 
 impl Withdraw {
@@ -70,6 +85,7 @@ impl Withdraw {
         nonce: Nonce,
         time_range: TimeRange,
         signature: Option<TxSignature>,
+        chain_id: u16,
     ) -> Self {
         let mut tx = Self {
             account_id,
@@ -80,9 +96,11 @@ impl Withdraw {
             fee,
             nonce,
             signature: signature.clone().unwrap_or_default(),
+            threshold_auth: None,
             cached_signer: VerifiedSignatureCache::NotCached,
             fast: false,
             time_range: Some(time_range),
+            chain_id,
         };
         if signature.is_some() {
             tx.cached_signer = VerifiedSignatureCache::Cached(tx.verify_signature());
@@ -103,9 +121,10 @@ impl Withdraw {
         nonce: Nonce,
         time_range: TimeRange,
         private_key: &PrivateKey<Engine>,
+        chain_id: u16,
     ) -> Result<Self, anyhow::Error> {
         let mut tx = Self::new(
-            account_id, from, to, token, amount, fee, nonce, time_range, None,
+            account_id, from, to, token, amount, fee, nonce, time_range, None, chain_id,
         );
         tx.signature = TxSignature::sign_musig(private_key, &tx.get_bytes());
         if !tx.check_correctness() {
@@ -128,9 +147,43 @@ impl Withdraw {
         if let Some(time_range) = &self.time_range {
             out.extend_from_slice(&time_range.to_be_bytes());
         }
+        if self.chain_id != 0 {
+            out.extend_from_slice(&self.chain_id.to_be_bytes());
+        }
         out
     }
 
+    /// Computes the `hashStruct` of this transaction's EIP-712 typed struct, the
+    /// part of the digest specific to the transaction rather than the signing
+    /// domain. Mirrors the fields covered by [`Self::get_alaya_sign_message_part`]:
+    /// everything a signer needs to see in order to understand what they're
+    /// authorizing, `fee` included, since the `EIP712Domain` replaces the plain
+    /// `personal_sign` wrapper rather than zkDpos's own signature.
+    pub fn eip712_struct_hash(&self) -> H256 {
+        let mut preimage = Vec::new();
+        preimage.extend_from_slice(
+            &b"Withdraw(address to,uint16 token,uint256 amount,uint256 fee,uint32 nonce)"
+                .to_vec()
+                .keccak256(),
+        );
+        preimage.extend_from_slice(&encode_word(self.to.as_bytes()));
+        preimage.extend_from_slice(&encode_word(&self.token.to_be_bytes()));
+        preimage.extend_from_slice(&encode_word(&self.amount.to_bytes_be()));
+        preimage.extend_from_slice(&encode_word(&self.fee.to_bytes_be()));
+        preimage.extend_from_slice(&encode_word(&self.nonce.to_be_bytes()));
+        H256::from_slice(&preimage.keccak256())
+    }
+
+    /// Computes the EIP-712 typed-data digest that must be signed with the Alaya
+    /// private key in order to authorize this withdrawal, as an alternative to
+    /// the free-form [`Self::get_alaya_sign_message`]. `chain_id` and
+    /// `verifying_contract` pin the signature to a specific deployment of the
+    /// zkDpos contract, so it can't be replayed across networks.
+    pub fn get_eip712_signed_data(&self, chain_id: u32, verifying_contract: Address) -> H256 {
+        let domain_separator = eip712_domain_separator(chain_id, verifying_contract);
+        eip712_digest(&domain_separator, &self.eip712_struct_hash())
+    }
+
     /// Verifies the transaction correctness:
     ///
     /// - `account_id` field must be within supported range.
@@ -146,11 +199,19 @@ impl Withdraw {
             && self
                 .time_range
                 .map(|t| t.check_correctness())
+                .unwrap_or(true)
+            && self
+                .threshold_auth
+                .as_ref()
+                .map(|auth| {
+                    !auth.signatures.is_empty()
+                        && auth.participant_bitmap.count_ones() as usize == auth.signatures.len()
+                })
                 .unwrap_or(true);
 
         if valid {
             let signer = self.verify_signature();
-            valid = valid && signer.is_some();
+            valid = valid && (signer.is_some() || self.threshold_auth.is_some());
             self.cached_signer = VerifiedSignatureCache::Cached(signer);
         }
         valid
@@ -167,6 +228,16 @@ impl Withdraw {
         }
     }
 
+    /// Verifies that this `Withdraw`'s `threshold_auth` (if present) meets
+    /// `key_set`'s quorum over `get_bytes()`, recognizing the account's
+    /// registered threshold-multisig signers as an alternative to the single
+    /// `signature` field. Returns the signer set's key commitment on success.
+    pub fn verify_threshold_auth(&self, key_set: &AccountSignerSet) -> Option<PubKeyHash> {
+        self.threshold_auth
+            .as_ref()
+            .and_then(|auth| auth.verify(&self.get_bytes(), key_set))
+    }
+
     /// Get the first part of the message we expect to be signed by Alaya account key.
     /// The only difference is the missing `nonce` since it's added at the end of the transactions
     /// batch message.
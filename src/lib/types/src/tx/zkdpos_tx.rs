@@ -4,11 +4,16 @@ use serde::{Deserialize, Serialize};
 
 use zkdpos_basic_types::{AccountId, Address};
 
+use crate::account::PubKeyHash;
 use crate::{
     operations::ChangePubKeyOp,
-    tx::{ChangePubKey, Close, ForcedExit, Transfer, TxAtpSignature, TxHash, Withdraw, Exchange},
+    tx::{
+        AccountSignerSet, ChangePubKey, Close, ForcedExit, RemoveLiquidity, Swap,
+        ThresholdMusigSignature, Transfer, TxAtpSignature, TxHash, TxSignature, Withdraw, Exchange,
+    },
     utils::deserialize_atp_message,
-    CloseOp, ForcedExitOp, Nonce, Token, TokenId, TokenLike, TransferOp, TxFeeTypes, WithdrawOp,
+    CloseOp, ForcedExitOp, Nonce, RemoveLiquidityOp, SwapOp, Token, TokenId, TokenLike,
+    TransferOp, TxFeeTypes, WithdrawOp,
 };
 use zkdpos_crypto::params::ATP_TOKEN_ID;
 
@@ -28,6 +33,13 @@ pub struct SignedZkDposTx {
     /// which user should have signed with their private key.
     /// Can be `None` if the Alaya signature is not required.
     pub atp_sign_data: Option<AtpSignData>,
+    /// An n-of-m threshold-multisig authorization for `tx`, collected from an
+    /// account's registered [`AccountSignerSet`] instead of a single
+    /// `TxSignature`. When present, it authorizes the transaction regardless
+    /// of whatever `tx`'s own signature field holds - see `add_partial_signature`
+    /// and `verify_multisig`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multisig_auth: Option<ThresholdMusigSignature>,
 }
 
 /// A set of L2 transaction supported by the zkDpos network.
@@ -41,6 +53,8 @@ pub enum ZkDposTx {
     Close(Box<Close>),
     ChangePubKey(Box<ChangePubKey>),
     ForcedExit(Box<ForcedExit>),
+    Swap(Box<Swap>),
+    RemoveLiquidity(Box<RemoveLiquidity>),
 }
 
 impl From<Transfer> for ZkDposTx {
@@ -73,11 +87,24 @@ impl From<ForcedExit> for ZkDposTx {
     }
 }
 
+impl From<Swap> for ZkDposTx {
+    fn from(tx: Swap) -> Self {
+        Self::Swap(Box::new(tx))
+    }
+}
+
+impl From<RemoveLiquidity> for ZkDposTx {
+    fn from(tx: RemoveLiquidity) -> Self {
+        Self::RemoveLiquidity(Box::new(tx))
+    }
+}
+
 impl From<ZkDposTx> for SignedZkDposTx {
     fn from(tx: ZkDposTx) -> Self {
         Self {
             tx,
             atp_sign_data: None,
+            multisig_auth: None,
         }
     }
 }
@@ -90,6 +117,33 @@ impl std::ops::Deref for SignedZkDposTx {
     }
 }
 
+impl SignedZkDposTx {
+    /// Adds one co-signer's partial signature to this transaction's
+    /// threshold-multisig authorization, creating it if this is the first
+    /// one collected. `key_set` must be the inner `tx`'s account's actual
+    /// registered signer set: the partial signature is only accepted if it
+    /// recovers to one of `key_set.signers`.
+    pub fn add_partial_signature(
+        &mut self,
+        key_set: &AccountSignerSet,
+        signature: TxSignature,
+    ) -> Result<(), anyhow::Error> {
+        let message = self.tx.get_bytes();
+        self.multisig_auth
+            .get_or_insert_with(ThresholdMusigSignature::new)
+            .add_partial_signature(key_set, &message, signature)
+    }
+
+    /// Verifies this transaction's `multisig_auth` (if any) against `key_set`,
+    /// returning the signer set's key commitment on success. This is the
+    /// multisig counterpart to each tx type's own `verify_signature`.
+    pub fn verify_multisig(&self, key_set: &AccountSignerSet) -> Option<PubKeyHash> {
+        self.multisig_auth
+            .as_ref()
+            .and_then(|auth| auth.verify(&self.tx.get_bytes(), key_set))
+    }
+}
+
 impl ZkDposTx {
     /// Returns the hash of the transaction.
     pub fn hash(&self) -> TxHash {
@@ -100,6 +154,8 @@ impl ZkDposTx {
             ZkDposTx::Close(tx) => tx.get_bytes(),
             ZkDposTx::ChangePubKey(tx) => tx.get_bytes(),
             ZkDposTx::ForcedExit(tx) => tx.get_bytes(),
+            ZkDposTx::Swap(tx) => tx.get_bytes(),
+            ZkDposTx::RemoveLiquidity(tx) => tx.get_bytes(),
         };
 
         let hash = sha256(&bytes);
@@ -117,6 +173,10 @@ impl ZkDposTx {
             ZkDposTx::Close(tx) => tx.account,
             ZkDposTx::ChangePubKey(tx) => tx.account,
             ZkDposTx::ForcedExit(tx) => tx.target,
+            // `Swap` only identifies its initiator by `account_id`; it has no
+            // L1 address of its own to report here.
+            ZkDposTx::Swap(_) => Address::zero(),
+            ZkDposTx::RemoveLiquidity(tx) => tx.to,
         }
     }
 
@@ -127,6 +187,8 @@ impl ZkDposTx {
             ZkDposTx::Withdraw(tx) => Ok(tx.account_id),
             ZkDposTx::ChangePubKey(tx) => Ok(tx.account_id),
             ZkDposTx::ForcedExit(tx) => Ok(tx.initiator_account_id),
+            ZkDposTx::Swap(tx) => Ok(tx.account_id),
+            ZkDposTx::RemoveLiquidity(tx) => Ok(tx.account_id),
             ZkDposTx::Close(_) => Err(anyhow::anyhow!("Close operations are disabled")),
         }
     }
@@ -140,6 +202,8 @@ impl ZkDposTx {
             ZkDposTx::Close(tx) => tx.nonce,
             ZkDposTx::ChangePubKey(tx) => tx.nonce,
             ZkDposTx::ForcedExit(tx) => tx.nonce,
+            ZkDposTx::Swap(tx) => tx.nonce,
+            ZkDposTx::RemoveLiquidity(tx) => tx.nonce,
         }
     }
 
@@ -155,6 +219,10 @@ impl ZkDposTx {
             ZkDposTx::Close(_) => ATP_TOKEN_ID,
             ZkDposTx::ChangePubKey(tx) => tx.fee_token,
             ZkDposTx::ForcedExit(tx) => tx.token,
+            ZkDposTx::Swap(tx) => tx.token_in,
+            // `RemoveLiquidity` pays `fee_a`/`fee_b` in both pool tokens; `token_a`
+            // is reported here as the primary one.
+            ZkDposTx::RemoveLiquidity(tx) => tx.token_a,
         }
     }
 
@@ -162,6 +230,11 @@ impl ZkDposTx {
     ///
     /// Note that this method doesn't check whether transaction will succeed, so transaction
     /// can fail even if this method returned `true` (i.e., if account didn't have enough balance).
+    ///
+    /// This is kept as a thin, non-consuming way to run the same checks
+    /// [`crate::tx::VerifiedTx::verify`] does; prefer `VerifiedTx::verify` for
+    /// state-transition code, since it makes it impossible to act on a
+    /// transaction that was never checked.
     pub fn check_correctness(&mut self) -> bool {
         match self {
             ZkDposTx::Transfer(tx) => tx.check_correctness(),
@@ -170,6 +243,8 @@ impl ZkDposTx {
             ZkDposTx::Close(tx) => tx.check_correctness(),
             ZkDposTx::ChangePubKey(tx) => tx.check_correctness(),
             ZkDposTx::ForcedExit(tx) => tx.check_correctness(),
+            ZkDposTx::Swap(tx) => tx.check_correctness(),
+            ZkDposTx::RemoveLiquidity(tx) => tx.check_correctness(),
         }
     }
 
@@ -188,6 +263,10 @@ impl ZkDposTx {
             ZkDposTx::ForcedExit(tx) => {
                 Some(tx.get_alaya_sign_message(&token.symbol, token.decimals))
             }
+            ZkDposTx::Swap(tx) => Some(tx.get_alaya_sign_message(&token.symbol, token.decimals)),
+            ZkDposTx::RemoveLiquidity(tx) => {
+                Some(tx.get_alaya_sign_message(&token.symbol, token.decimals))
+            }
             _ => None,
         }
     }
@@ -224,6 +303,12 @@ impl ZkDposTx {
             ZkDposTx::ForcedExit(tx) => {
                 Some(tx.get_alaya_sign_message_part(&token.symbol, token.decimals))
             }
+            ZkDposTx::Swap(tx) => {
+                Some(tx.get_alaya_sign_message_part(&token.symbol, token.decimals))
+            }
+            ZkDposTx::RemoveLiquidity(tx) => {
+                Some(tx.get_alaya_sign_message_part(&token.symbol, token.decimals))
+            }
             _ => None,
         }
     }
@@ -237,6 +322,8 @@ impl ZkDposTx {
             ZkDposTx::Close(tx) => tx.get_bytes(),
             ZkDposTx::ChangePubKey(tx) => tx.get_bytes(),
             ZkDposTx::ForcedExit(tx) => tx.get_bytes(),
+            ZkDposTx::Swap(tx) => tx.get_bytes(),
+            ZkDposTx::RemoveLiquidity(tx) => tx.get_bytes(),
         }
     }
 
@@ -251,6 +338,8 @@ impl ZkDposTx {
             ZkDposTx::Close(_) => CloseOp::CHUNKS,
             ZkDposTx::ChangePubKey(_) => ChangePubKeyOp::CHUNKS,
             ZkDposTx::ForcedExit(_) => ForcedExitOp::CHUNKS,
+            ZkDposTx::Swap(_) => SwapOp::CHUNKS,
+            ZkDposTx::RemoveLiquidity(_) => RemoveLiquidityOp::CHUNKS,
         }
     }
 
@@ -265,6 +354,22 @@ impl ZkDposTx {
         matches!(self, ZkDposTx::Close(_))
     }
 
+    /// Returns the versioned-envelope format versions this transaction's op
+    /// declares support for. Every op currently only understands the legacy
+    /// (version `0`) byte layout; see [`crate::tx::VersionedZkDposTx`].
+    pub fn supported_versions(&self) -> &'static [u8] {
+        match self {
+            ZkDposTx::Transfer(_) => &[0],
+            ZkDposTx::Exchange(_) => &[0],
+            ZkDposTx::Withdraw(_) => &[0],
+            ZkDposTx::Close(_) => &[0],
+            ZkDposTx::ChangePubKey(_) => &[0],
+            ZkDposTx::ForcedExit(_) => &[0],
+            ZkDposTx::Swap(_) => &[0],
+            ZkDposTx::RemoveLiquidity(_) => &[0],
+        }
+    }
+
     /// Returns the data required to calculate fee for the transaction.
     ///
     /// Response includes the following items:
@@ -321,6 +426,78 @@ impl ZkDposTx {
             ZkDposTx::ChangePubKey(tx) => tx.time_range.unwrap_or_default().valid_from,
             ZkDposTx::ForcedExit(tx) => tx.time_range.valid_from,
             ZkDposTx::Close(tx) => tx.time_range.valid_from,
+            ZkDposTx::Swap(tx) => tx.time_range.unwrap_or_default().valid_from,
+            ZkDposTx::RemoveLiquidity(tx) => tx.time_range.unwrap_or_default().valid_from,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tx::TimeRange;
+    use num::Zero;
+    use zkdpos_basic_types::{LiquidityId, Nonce, TokenId};
+
+    fn sample_swap() -> Swap {
+        Swap::new(
+            AccountId(1),
+            LiquidityId(0),
+            TokenId(0),
+            TokenId(1),
+            BigUint::from(100u32),
+            BigUint::from(1u32),
+            BigUint::from(1u32),
+            Nonce(3),
+            TimeRange::default(),
+            None,
+        )
+    }
+
+    fn sample_remove_liquidity() -> RemoveLiquidity {
+        RemoveLiquidity::new(
+            AccountId(1),
+            LiquidityId(0),
+            Address::from_low_u64_be(1),
+            BigUint::from(10u32),
+            BigUint::zero(),
+            BigUint::zero(),
+            TokenId(0),
+            TokenId(1),
+            BigUint::from(1u32),
+            BigUint::from(1u32),
+            Nonce(3),
+            TimeRange::default(),
+            None,
+        )
+    }
+
+    /// `ZkDposTx::Swap`'s `account_id`/`nonce`/`token_id`/`get_bytes` dispatch
+    /// arms must route to the wrapped `Swap`'s own fields and encoding - this
+    /// is the wiring `From<Swap> for ZkDposTx` and the match arms above it
+    /// actually added, as opposed to `Swap`'s own apply logic (covered under
+    /// `state`'s handler tests).
+    #[test]
+    fn swap_dispatches_through_zkdpos_tx() {
+        let swap = sample_swap();
+        let tx: ZkDposTx = swap.clone().into();
+        assert_eq!(tx.account_id().unwrap(), swap.account_id);
+        assert_eq!(tx.nonce(), swap.nonce);
+        assert_eq!(tx.token_id(), swap.token_in);
+        assert_eq!(tx.get_bytes(), swap.get_bytes());
+        assert!(matches!(tx, ZkDposTx::Swap(_)));
+    }
+
+    /// Same check for `ZkDposTx::RemoveLiquidity`, this request's other
+    /// newly-wired variant.
+    #[test]
+    fn remove_liquidity_dispatches_through_zkdpos_tx() {
+        let remove_liquidity = sample_remove_liquidity();
+        let tx: ZkDposTx = remove_liquidity.clone().into();
+        assert_eq!(tx.account_id().unwrap(), remove_liquidity.account_id);
+        assert_eq!(tx.nonce(), remove_liquidity.nonce);
+        assert_eq!(tx.account(), remove_liquidity.to);
+        assert_eq!(tx.get_bytes(), remove_liquidity.get_bytes());
+        assert!(matches!(tx, ZkDposTx::RemoveLiquidity(_)));
+    }
+}
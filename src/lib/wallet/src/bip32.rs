@@ -0,0 +1,108 @@
+use std::convert::TryInto;
+
+use hmac::{Hmac, Mac, NewMac};
+use parity_crypto::publickey::{KeyPair, Secret};
+use sha2::Sha512;
+use zkdpos_basic_types::H256;
+
+use crate::error::WalletError;
+
+type HmacSha512 = Hmac<Sha512>;
+
+const HARDENED_OFFSET: u32 = 0x8000_0000;
+
+/// One step of a derivation path, e.g. the `44'` or `0` in `m/44'/60'/0'/0/0`.
+#[derive(Debug, Clone, Copy)]
+struct ChildIndex {
+    index: u32,
+    hardened: bool,
+}
+
+fn parse_path(path: &str) -> Result<Vec<ChildIndex>, WalletError> {
+    let trimmed = path.strip_prefix("m/").unwrap_or(path);
+    if trimmed.is_empty() {
+        return Ok(Vec::new());
+    }
+    trimmed
+        .split('/')
+        .map(|segment| {
+            let (digits, hardened) = match segment.strip_suffix('\'') {
+                Some(digits) => (digits, true),
+                None => (segment, false),
+            };
+            let index: u32 = digits
+                .parse()
+                .map_err(|_| WalletError::InvalidDerivationPath(path.to_owned()))?;
+            Ok(ChildIndex { index, hardened })
+        })
+        .collect()
+}
+
+/// An extended secp256k1 private key: the 32-byte key plus the 32-byte chain
+/// code BIP-32 mixes into every child derivation.
+struct ExtendedPrivKey {
+    key: Secret,
+    chain_code: [u8; 32],
+}
+
+impl ExtendedPrivKey {
+    /// Derives the BIP-32 master key from a BIP-39 seed, using the standard
+    /// `"Bitcoin seed"` HMAC key shared by every BIP-32 wallet regardless of
+    /// which secp256k1-based chain it ultimately derives keys for.
+    fn master(seed: &[u8]) -> Result<Self, WalletError> {
+        let mut mac =
+            HmacSha512::new_from_slice(b"Bitcoin seed").expect("HMAC accepts a key of any length");
+        mac.update(seed);
+        let digest = mac.finalize().into_bytes();
+        let (key, chain_code) = digest.split_at(32);
+
+        Ok(Self {
+            key: Secret::from_slice(key).ok_or(WalletError::InvalidChildKey)?,
+            chain_code: chain_code.try_into().expect("chain code is 32 bytes"),
+        })
+    }
+
+    /// Derives the child at `index`: hardened steps mix in the parent
+    /// private key, normal steps mix in the parent public key instead.
+    fn child(&self, index: ChildIndex) -> Result<Self, WalletError> {
+        let mut mac = HmacSha512::new_from_slice(&self.chain_code)
+            .expect("HMAC accepts a key of any length");
+        if index.hardened {
+            mac.update(&[0u8]);
+            mac.update(self.key.as_bytes());
+        } else {
+            let key_pair =
+                KeyPair::from_secret(self.key.clone()).map_err(|_| WalletError::InvalidChildKey)?;
+            mac.update(key_pair.public().as_bytes());
+        }
+        let raw_index = if index.hardened {
+            index.index | HARDENED_OFFSET
+        } else {
+            index.index
+        };
+        mac.update(&raw_index.to_be_bytes());
+
+        let digest = mac.finalize().into_bytes();
+        let (tweak, chain_code) = digest.split_at(32);
+
+        let mut child_key = self.key.clone();
+        child_key
+            .add(&Secret::from_slice(tweak).ok_or(WalletError::InvalidChildKey)?)
+            .map_err(|_| WalletError::InvalidChildKey)?;
+
+        Ok(Self {
+            key: child_key,
+            chain_code: chain_code.try_into().expect("chain code is 32 bytes"),
+        })
+    }
+}
+
+/// Derives the Alaya (secp256k1) private key at `path` from a BIP-39 `seed`.
+pub fn derive(seed: &[u8], path: &str) -> Result<H256, WalletError> {
+    let steps = parse_path(path)?;
+    let mut key = ExtendedPrivKey::master(seed)?;
+    for step in steps {
+        key = key.child(step)?;
+    }
+    Ok(H256::from_slice(key.key.as_bytes()))
+}
@@ -0,0 +1,13 @@
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum WalletError {
+    #[error("mnemonic has an unrecognized word or fails its checksum")]
+    InvalidMnemonic,
+    #[error("BIP-32 derivation path is malformed: {0}")]
+    InvalidDerivationPath(String),
+    #[error("derived secp256k1 child key is invalid")]
+    InvalidChildKey,
+    #[error("could not derive an Alaya address from the derived key")]
+    DefineAddress,
+}
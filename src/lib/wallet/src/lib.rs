@@ -0,0 +1,21 @@
+//! BIP-39 mnemonic + BIP-32 derivation for both of zkDpos's signing domains:
+//! the zkDpos (JubJub/EdDSA) key `TxSignature::sign_musig` uses, and the
+//! Alaya (secp256k1) key `PackedAtpSignature::sign` / `address_from_private_key`
+//! use. [`Wallet::from_mnemonic`] is the single entry point: it reconstructs a
+//! full zkDpos identity from a backup phrase alone.
+//!
+//! The lower-level building blocks ([`Seed`], [`derive_alaya_key`],
+//! [`zkdpos_key::from_bip32_secret`]) are also exported for callers that need
+//! to derive along an arbitrary path rather than a plain `account_index`,
+//! such as `test_account::ZkDposAccount::from_mnemonic`.
+
+mod bip32;
+pub mod error;
+mod mnemonic;
+mod wallet;
+pub mod zkdpos_key;
+
+pub use bip32::derive as derive_alaya_key;
+pub use error::WalletError;
+pub use mnemonic::{generate as generate_mnemonic, Seed};
+pub use wallet::Wallet;
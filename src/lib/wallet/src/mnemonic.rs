@@ -0,0 +1,35 @@
+use bip39::{Language, Mnemonic, MnemonicType};
+
+use crate::error::WalletError;
+
+/// Generates a fresh random BIP-39 mnemonic with `word_count` words (12, 15,
+/// 18, 21 or 24), so a new account can keep a recoverable phrase instead of
+/// raw RNG bytes.
+pub fn generate(word_count: usize) -> Result<String, WalletError> {
+    let mnemonic_type =
+        MnemonicType::for_word_count(word_count).map_err(|_| WalletError::InvalidMnemonic)?;
+    Ok(Mnemonic::new(mnemonic_type, Language::English).into_phrase())
+}
+
+/// A validated BIP-39 mnemonic together with its derived 64-byte seed.
+///
+/// Validation checks the phrase's words against the BIP-39 English wordlist
+/// and its embedded checksum; the seed itself is PBKDF2-HMAC-SHA512 of the
+/// mnemonic with 2048 iterations and salt `"mnemonic" + passphrase`, exactly
+/// as BIP-39 specifies. Both the zkDpos and Alaya signing keys are derived
+/// from this one seed via domain separation - see [`crate::Wallet`].
+pub struct Seed(bip39::Seed);
+
+impl Seed {
+    /// Validates `mnemonic` (English wordlist) and derives its seed.
+    pub fn new(mnemonic: &str, passphrase: &str) -> Result<Self, WalletError> {
+        let mnemonic =
+            Mnemonic::from_phrase(mnemonic, Language::English).map_err(|_| WalletError::InvalidMnemonic)?;
+        Ok(Self(bip39::Seed::new(&mnemonic, passphrase)))
+    }
+
+    /// Returns the 64-byte derived seed.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+}
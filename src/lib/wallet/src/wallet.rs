@@ -0,0 +1,46 @@
+use zkdpos_basic_types::{Address, H256};
+use zkdpos_crypto::PrivateKey;
+use zkdpos_types::tx::PackedAtpSignature;
+
+use crate::error::WalletError;
+use crate::mnemonic::Seed;
+use crate::{bip32, zkdpos_key};
+
+/// The Alaya-domain derivation path for account `account_index`: BIP-44 with
+/// Alaya's registered coin type (60, inherited from Ethereum) and a fixed
+/// purpose/account/change prefix.
+fn alaya_path(account_index: u32) -> String {
+    format!("m/44'/60'/0'/0/{}", account_index)
+}
+
+/// A full zkDpos identity reconstructed from a single BIP-39 mnemonic: the
+/// zkDpos signing key [`TxSignature::sign_musig`](zkdpos_types::tx::TxSignature::sign_musig)
+/// uses, the Alaya signing key `PackedAtpSignature::sign` uses, and the
+/// Alaya `Address` the latter corresponds to.
+pub struct Wallet {
+    pub zkdpos_private_key: PrivateKey,
+    pub atp_private_key: H256,
+    pub address: Address,
+}
+
+impl Wallet {
+    /// Derives both domain keys for `account_index` from `mnemonic`/`passphrase`.
+    pub fn from_mnemonic(
+        mnemonic: &str,
+        passphrase: &str,
+        account_index: u32,
+    ) -> Result<Self, WalletError> {
+        let seed = Seed::new(mnemonic, passphrase)?;
+
+        let atp_private_key = bip32::derive(seed.as_bytes(), &alaya_path(account_index))?;
+        let address = PackedAtpSignature::address_from_private_key(&atp_private_key)
+            .map_err(|_| WalletError::DefineAddress)?;
+        let zkdpos_private_key = zkdpos_key::derive(seed.as_bytes(), account_index);
+
+        Ok(Self {
+            zkdpos_private_key,
+            atp_private_key,
+            address,
+        })
+    }
+}
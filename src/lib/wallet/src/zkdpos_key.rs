@@ -0,0 +1,58 @@
+use std::convert::TryInto;
+
+use hmac::{Hmac, Mac, NewMac};
+use sha2::Sha512;
+use zkdpos_basic_types::H256;
+use zkdpos_crypto::ff::Field;
+use zkdpos_crypto::rand::{Rng, SeedableRng, XorShiftRng};
+use zkdpos_crypto::{priv_key_from_fs, PrivateKey};
+
+type HmacSha512 = Hmac<Sha512>;
+
+/// Maps a domain-separated HMAC-SHA512 digest of `message` into the JubJub
+/// scalar field: seed a deterministic RNG from the digest and sample a field
+/// element with it. On the vanishingly unlikely chance that comes out to
+/// zero, the digest is re-derived with a bumped counter and resampled.
+fn key_from_hmac(domain: &'static [u8], message: &[u8]) -> PrivateKey {
+    let mut attempt: u32 = 0;
+    loop {
+        let mut mac =
+            HmacSha512::new_from_slice(domain).expect("HMAC accepts a key of any length");
+        mac.update(message);
+        mac.update(&attempt.to_be_bytes());
+        let digest = mac.finalize().into_bytes();
+
+        let mut rng_seed = [0u32; 4];
+        for (word, chunk) in rng_seed.iter_mut().zip(digest.chunks_exact(4)) {
+            *word = u32::from_le_bytes(chunk.try_into().expect("4-byte chunk"));
+        }
+
+        let mut rng = XorShiftRng::from_seed(rng_seed);
+        let key = priv_key_from_fs(rng.gen());
+        if !key.0.is_zero() {
+            return key;
+        }
+        attempt += 1;
+    }
+}
+
+/// Derives the zkDpos (JubJub/EdDSA) signing key for `account_index` from a
+/// BIP-39 `seed`.
+///
+/// There's no BIP-32 for the JubJub curve `zkdpos_crypto::Engine` uses, so
+/// instead of walking a derivation path this maps a domain-separated
+/// HMAC-SHA512 digest of the seed into the scalar field the same way
+/// `test_account::ZkDposAccount::rand` turns raw entropy into a
+/// `PrivateKey`: seed a deterministic RNG from the digest and sample a field
+/// element with it.
+pub fn derive(seed: &[u8], account_index: u32) -> PrivateKey {
+    key_from_hmac(b"zkDpos seed", &[seed, &account_index.to_be_bytes()].concat())
+}
+
+/// Maps a BIP-32 secret (derived along an arbitrary path by [`crate::bip32`])
+/// into the JubJub scalar field via the same `key_from_hmac` sampling
+/// `derive` uses, under a distinct domain tag so a path's zkDpos key can
+/// never collide with its `account_index`-derived counterpart.
+pub fn from_bip32_secret(secret: &H256) -> PrivateKey {
+    key_from_hmac(b"zkDpos seed from bip32", secret.as_bytes())
+}
@@ -7,12 +7,15 @@ use zkdpos_basic_types::H256;
 use zkdpos_crypto::rand::{thread_rng, Rng};
 use zkdpos_crypto::{priv_key_from_fs, PrivateKey};
 use zkdpos_types::tx::{
-    ChangePubKey, ChangePubKeyECDSAData, ChangePubKeyAtpAuthData, PackedAtpSignature, TimeRange,
-    TxSignature,
+    compute_batch_hash, BatchTx, ChangePubKey, ChangePubKeyECDSAData, ChangePubKeyAtpAuthData,
+    GrantDelegate, PackedAtpSignature, TimeRange, TxSignature, ZkDposTx,
 };
 use zkdpos_types::{
     AccountId, Address, Close, ForcedExit, Nonce, PubKeyHash, TokenId, Transfer, Withdraw,
 };
+use zkdpos_wallet::{
+    derive_alaya_key, generate_mnemonic as generate_mnemonic_phrase, zkdpos_key, Seed,
+};
 
 /// Structure used to sign ZKDpos transactions, keeps tracks of its nonce internally
 pub struct ZkDposAccount {
@@ -98,6 +101,36 @@ impl ZkDposAccount {
         }
     }
 
+    /// Reconstructs an account from a BIP-39 `mnemonic`, so a wallet can back
+    /// up and restore an account from a phrase instead of raw key material.
+    ///
+    /// The zkDpos (JubJub) key is derived from the BIP-32 secret at `hd_path`
+    /// via `zkdpos_key::from_bip32_secret`; the Alaya (secp256k1) key is
+    /// derived from the sibling path one level below it, so the two keys
+    /// never share derivation material.
+    pub fn from_mnemonic(mnemonic: &str, passphrase: &str, hd_path: &str) -> Self {
+        let seed = Seed::new(mnemonic, passphrase).expect("Invalid mnemonic or passphrase");
+
+        let zk_secret =
+            derive_alaya_key(seed.as_bytes(), hd_path).expect("Invalid HD derivation path");
+        let pk = zkdpos_key::from_bip32_secret(&zk_secret);
+
+        let atp_path = format!("{}/1", hd_path);
+        let atp_pk =
+            derive_alaya_key(seed.as_bytes(), &atp_path).expect("Invalid HD derivation path");
+        let atp_address = PackedAtpSignature::address_from_private_key(&atp_pk)
+            .expect("Can't derive an Alaya address from the derived key");
+
+        Self::new(pk, Nonce(0), atp_address, atp_pk)
+    }
+
+    /// Generates a fresh BIP-39 mnemonic with `word_count` words (12, 15, 18,
+    /// 21 or 24), so new accounts can keep a recoverable phrase instead of
+    /// raw RNG bytes like `rand()` does.
+    pub fn generate_mnemonic(word_count: usize) -> String {
+        generate_mnemonic_phrase(word_count).expect("Unsupported mnemonic word count")
+    }
+
     pub fn nonce(&self) -> Nonce {
         let n = self.nonce.lock().unwrap();
         *n
@@ -211,6 +244,7 @@ impl ZkDposAccount {
             nonce.unwrap_or_else(|| *stored_nonce),
             time_range,
             &self.private_key,
+            0,
         )
         .expect("Failed to sign withdraw");
 
@@ -240,6 +274,15 @@ impl ZkDposAccount {
         close
     }
 
+    /// Signs a `ChangePubKey` operation. When `other_batch_txs` is non-empty,
+    /// the ECDSA auth data's `batch_hash` is the real Merkle root committing
+    /// to this transaction together with `other_batch_txs`, in order (see
+    /// `zkdpos_types::tx::compute_batch_hash`), rather than a zeroed-out
+    /// placeholder - so the ATP signature binds to exactly this set and
+    /// ordering of transactions and can't be replayed in a different batch.
+    /// Pass an empty slice for a `ChangePubKey` submitted on its own; it is
+    /// then the sole member of its own one-transaction "batch".
+    #[allow(clippy::too_many_arguments)]
     pub fn sign_change_pubkey_tx(
         &self,
         nonce: Option<Nonce>,
@@ -248,6 +291,7 @@ impl ZkDposAccount {
         fee: BigUint,
         auth_onchain: bool,
         time_range: TimeRange,
+        other_batch_txs: &[ZkDposTx],
     ) -> ChangePubKey {
         let account_id = self
             .account_id
@@ -272,6 +316,22 @@ impl ZkDposAccount {
         change_pubkey.atp_auth_data = if auth_onchain {
             Some(ChangePubKeyAtpAuthData::Onchain)
         } else {
+            let mut batch: Vec<&dyn BatchTx> = Vec::with_capacity(other_batch_txs.len() + 1);
+            batch.push(&change_pubkey);
+            batch.extend(other_batch_txs.iter().map(|tx| tx as &dyn BatchTx));
+            let batch_hash = compute_batch_hash(&batch);
+
+            // `get_atp_signed_data` folds `batch_hash` into the signed message
+            // only once it's already stored in `atp_auth_data`, so a
+            // placeholder signature is needed to populate the field before
+            // the real signing pass below can read the right message back out.
+            let placeholder_signature = PackedAtpSignature::sign(&self.atp_private_key, &[])
+                .expect("Signing placeholder data unexpectedly failed");
+            change_pubkey.atp_auth_data = Some(ChangePubKeyAtpAuthData::ECDSA(ChangePubKeyECDSAData {
+                atp_signature: placeholder_signature,
+                batch_hash,
+            }));
+
             let sign_bytes = change_pubkey
                 .get_atp_signed_data()
                 .expect("Failed to construct change pubkey signed message.");
@@ -279,7 +339,7 @@ impl ZkDposAccount {
                 .expect("Signature should succeed");
             Some(ChangePubKeyAtpAuthData::ECDSA(ChangePubKeyECDSAData {
                 atp_signature,
-                batch_hash: H256::zero(),
+                batch_hash,
             }))
         };
 
@@ -294,4 +354,70 @@ impl ZkDposAccount {
 
         change_pubkey
     }
+
+    /// Signs a `GrantDelegate` transaction installing `delegate` as this
+    /// account's delegated signer (or, when `delegate` is the default
+    /// `PubKeyHash`, revoking whichever delegate is currently installed).
+    pub fn sign_grant_delegate(
+        &self,
+        delegate: PubKeyHash,
+        fee_token: TokenId,
+        fee: BigUint,
+        nonce: Option<Nonce>,
+        increment_nonce: bool,
+        time_range: TimeRange,
+    ) -> GrantDelegate {
+        let mut stored_nonce = self.nonce.lock().unwrap();
+        let grant_delegate = GrantDelegate::new_signed(
+            self.account_id
+                .lock()
+                .unwrap()
+                .expect("can't sign tx without account id"),
+            delegate,
+            fee_token,
+            fee,
+            nonce.unwrap_or_else(|| *stored_nonce),
+            time_range,
+            &self.private_key,
+        )
+        .expect("Failed to sign grant delegate");
+
+        if increment_nonce {
+            **stored_nonce += 1;
+        }
+
+        grant_delegate
+    }
+
+    /// Signs a `Transfer` on behalf of `delegator_account_id` /
+    /// `delegator_address` using this account's own key, standing in for the
+    /// delegator the way a `GrantDelegate`-installed delegate does: the
+    /// delegator is expected to have granted this account's `pubkey_hash` as
+    /// its delegate beforehand. Unlike `sign_transfer`, the delegator's nonce
+    /// isn't tracked locally, so the caller must pass it explicitly.
+    #[allow(clippy::too_many_arguments)]
+    pub fn sign_transfer_as(
+        &self,
+        delegator_account_id: AccountId,
+        delegator_address: Address,
+        token_id: TokenId,
+        amount: BigUint,
+        fee: BigUint,
+        to: &Address,
+        nonce: Nonce,
+        time_range: TimeRange,
+    ) -> Transfer {
+        Transfer::new_signed(
+            delegator_account_id,
+            delegator_address,
+            *to,
+            token_id,
+            amount,
+            fee,
+            nonce,
+            time_range,
+            &self.private_key,
+        )
+        .expect("Failed to sign transfer as delegate")
+    }
 }